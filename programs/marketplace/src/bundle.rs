@@ -0,0 +1,159 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// SPL Token and Metaplex imports
+use anchor_spl::{
+    associated_token::{create_idempotent, get_associated_token_address_with_program_id, Create},
+    metadata::mpl_token_metadata::accounts::Metadata as MetadataPda,
+    token_interface::Mint,
+};
+
+// Local state and error imports
+use crate::error::MarketplaceError;
+use crate::nft_metadata::NftMetadata;
+use crate::nft_transfer::{close_vault, NftTransfer, ProgrammableAccounts};
+use crate::BundleListing;
+
+/// Remaining accounts passed per NFT when a bundle is released:
+/// the NFT mint, its metadata, its edition, its bundle vault, the vault's token
+/// record, the recipient's token account, its token record and the NFT's rule set
+/// Edition, token records and rule set are only read for programmable NFTs; pass
+/// the marketplace program ID in their place otherwise
+/// The metadata PDA is always passed, uninitialized for Token-2022 group members
+pub const RELEASE_ACCOUNTS_PER_NFT: usize = 8;
+
+/// Address of the vault holding `mint` for `bundle`, the bundle PDA's ATA
+pub fn vault_address(bundle: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(bundle, mint, token_program)
+}
+
+/// An optional per-NFT account, left out by passing the marketplace program ID
+/// in its place (the same convention Anchor uses for optional accounts)
+pub fn optional_account<'info>(account: &AccountInfo<'info>) -> Option<AccountInfo<'info>> {
+    (account.key() != crate::ID).then(|| account.clone())
+}
+
+/// Moves every NFT out of a bundle's vaults and closes the vaults
+pub struct BundleRelease<'info> {
+    pub payer: AccountInfo<'info>,                               // Pays for missing recipient token accounts and records
+    pub recipient: AccountInfo<'info>,                           // Wallet receiving the NFTs
+    pub rent_destination: AccountInfo<'info>,                    // Receives the vault rent (always the seller)
+    pub token_program: AccountInfo<'info>,                       // Token program of the NFT mints
+    pub associated_token_program: AccountInfo<'info>,            // For recipient ATA creation
+    pub system_program: AccountInfo<'info>,                      // For recipient ATA creation
+    pub metadata_program: AccountInfo<'info>,                    // Token Metadata, which moves programmable NFTs
+    pub authorization_rules_program: Option<AccountInfo<'info>>, // Token Auth Rules program, if a pNFT has a rule set
+    pub sysvar_instructions: Option<AccountInfo<'info>>,         // Instructions sysvar, needed for pNFTs
+}
+
+impl<'info> BundleRelease<'info> {
+    /// Transfers each bundled NFT to the recipient and closes its vault
+    /// Programmable NFTs go through Token Metadata, everything else through the token program
+    ///
+    /// `nft_accounts` must hold RELEASE_ACCOUNTS_PER_NFT accounts per mint in
+    /// `bundle.mints`, in the same order; `extra_accounts` are the transfer hook
+    /// accounts of any Token-2022 mint with a hook, passed to every transfer
+    pub fn release(
+        &self,
+        bundle: &Account<'info, BundleListing>,
+        marketplace: Pubkey,
+        nft_accounts: &'info [AccountInfo<'info>],
+        extra_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        require!(
+            nft_accounts.len() == bundle.mints.len() * RELEASE_ACCOUNTS_PER_NFT,
+            MarketplaceError::InvalidBundleAccount
+        );
+
+        // Create PDA signing seeds for bundle authority over the vaults
+        let maker = bundle.maker;
+        let bundle_id = bundle.bundle_id.to_le_bytes();
+        let seeds = &[
+            b"bundle".as_ref(), // Bundle seed prefix
+            marketplace.as_ref(), // Marketplace address
+            maker.as_ref(), // Seller address
+            bundle_id.as_ref(), // Seller-chosen bundle id
+            &[bundle.bump], // Bundle bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array structure for PDA signing
+
+        for (mint_key, accounts) in bundle
+            .mints
+            .iter()
+            .zip(nft_accounts.chunks(RELEASE_ACCOUNTS_PER_NFT))
+        {
+            let [mint, metadata, edition, vault, vault_token_record, recipient_ata, recipient_token_record, authorization_rules] =
+                accounts
+            else {
+                return err!(MarketplaceError::InvalidBundleAccount); // Length checked above
+            };
+
+            // Only the bundle's own vaults, in the order the NFTs were listed
+            require_keys_eq!(mint.key(), *mint_key, MarketplaceError::InvalidBundleAccount);
+            require_keys_eq!(
+                vault.key(),
+                vault_address(&bundle.key(), mint_key, &self.token_program.key()),
+                MarketplaceError::InvalidBundleAccount
+            );
+
+            // Metadata decides how the NFT moves, so it must be the NFT's own
+            require_keys_eq!(
+                metadata.key(),
+                MetadataPda::find_pda(mint_key).0,
+                MarketplaceError::InvalidBundleAccount
+            );
+            let metadata = NftMetadata::try_from_account(metadata)?;
+            let decimals = InterfaceAccount::<Mint>::try_from(mint)?.decimals;
+
+            // Recipient may not hold a token account for this mint yet
+            create_idempotent(CpiContext::new(
+                self.associated_token_program.clone(),
+                Create {
+                    payer: self.payer.clone(), // Pays rent if the account is created
+                    associated_token: recipient_ata.clone(), // ATA address checked by the ATA program
+                    authority: self.recipient.clone(), // Recipient owns the token account
+                    mint: mint.clone(), // NFT mint
+                    system_program: self.system_program.clone(),
+                    token_program: self.token_program.clone(),
+                },
+            ))?;
+
+            let programmable = ProgrammableAccounts::from_optional(
+                &self.metadata_program,
+                metadata.as_ref(),
+                optional_account(edition).as_ref(),
+                optional_account(vault_token_record).as_ref(),
+                optional_account(recipient_token_record).as_ref(),
+                optional_account(authorization_rules).as_ref(),
+                self.authorization_rules_program.as_ref(),
+                self.sysvar_instructions.as_ref(),
+                &self.system_program,
+                &self.associated_token_program,
+            );
+
+            NftTransfer {
+                from: vault.clone(), // Source: bundle vault
+                from_owner: bundle.to_account_info(), // Bundle PDA owns the vault
+                to: recipient_ata.clone(), // Destination: recipient's token account
+                to_owner: self.recipient.clone(), // Recipient owns the destination account
+                authority: bundle.to_account_info(), // Bundle PDA has authority over vault
+                payer: self.payer.clone(), // Pays for the recipient's token record
+                mint: mint.clone(), // NFT mint being transferred
+                token_program: self.token_program.clone(),
+                signer_seeds,
+                extra_accounts,
+            }
+            .transfer(1, decimals, programmable.as_ref())?; // A single NFT
+
+            close_vault(
+                vault.clone(), // Empty bundle vault
+                self.rent_destination.clone(), // Vault rent back to the seller
+                bundle.to_account_info(), // Bundle PDA has authority
+                self.token_program.clone(),
+                signer_seeds,
+            )?;
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,208 @@
+// Core Anchor framework imports
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        keccak,
+        program::invoke_signed,
+        pubkey,
+    },
+};
+
+// Local error imports
+use crate::error::MarketplaceError;
+use crate::settlement::Royalties;
+
+/// Metaplex Bubblegum program
+pub const BUBBLEGUM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+/// SPL Account Compression program, which owns the merkle trees
+pub const SPL_ACCOUNT_COMPRESSION_ID: Pubkey =
+    pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+/// SPL Noop program, used by Bubblegum to log leaf changes
+pub const SPL_NOOP_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+// Anchor discriminator of Bubblegum's transfer instruction
+const TRANSFER_DISCRIMINATOR: [u8; 8] = [163, 52, 200, 231, 140, 3, 69, 186];
+
+/// Bubblegum MetadataArgs, with its enums read as their variant index
+/// Field order must match Bubblegum exactly, the leaf hash is over these bytes
+#[derive(AnchorDeserialize)]
+pub struct MetadataArgs {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub edition_nonce: Option<u8>,
+    pub token_standard: Option<u8>,
+    pub collection: Option<CompressedCollection>,
+    pub uses: Option<CompressedUses>,
+    pub token_program_version: u8,
+    pub creators: Vec<CompressedCreator>,
+}
+
+/// Bubblegum Collection
+#[derive(AnchorDeserialize)]
+pub struct CompressedCollection {
+    pub verified: bool,
+    pub key: Pubkey,
+}
+
+/// Bubblegum Uses
+#[derive(AnchorDeserialize)]
+pub struct CompressedUses {
+    pub use_method: u8,
+    pub remaining: u64,
+    pub total: u64,
+}
+
+/// Bubblegum Creator
+#[derive(AnchorDeserialize)]
+pub struct CompressedCreator {
+    pub address: Pubkey,
+    pub verified: bool,
+    pub share: u8,
+}
+
+/// Leaf contents the marketplace can check before handing them to Bubblegum
+pub struct LeafData {
+    pub metadata: MetadataArgs, // Decoded metadata
+    pub data_hash: [u8; 32],    // Hash of the metadata, as stored in the leaf
+    pub creator_hash: [u8; 32], // Hash of the creators, as stored in the leaf
+}
+
+impl LeafData {
+    /// Decodes Borsh-serialized MetadataArgs and hashes them the way Bubblegum does
+    /// Bubblegum checks both hashes against the tree, so the decoded data can be trusted
+    /// once a transfer built from them has succeeded
+    pub fn from_metadata_args(metadata_args: &[u8]) -> Result<Self> {
+        let metadata = MetadataArgs::try_from_slice(metadata_args)
+            .map_err(|_| MarketplaceError::InvalidCompressedNft)?;
+
+        let metadata_hash = keccak::hashv(&[metadata_args]);
+        let data_hash = keccak::hashv(&[
+            &metadata_hash.to_bytes(),
+            &metadata.seller_fee_basis_points.to_le_bytes(),
+        ])
+        .to_bytes();
+
+        let creator_data = metadata
+            .creators
+            .iter()
+            .map(|creator| {
+                [creator.address.as_ref(), &[creator.verified as u8], &[creator.share]].concat()
+            })
+            .collect::<Vec<_>>();
+        let creator_hash = keccak::hashv(
+            &creator_data
+                .iter()
+                .map(|creator| creator.as_slice())
+                .collect::<Vec<_>>(),
+        )
+        .to_bytes();
+
+        Ok(LeafData {
+            metadata,
+            data_hash,
+            creator_hash,
+        })
+    }
+
+    /// Royalties of the compressed NFT; only verified creators get paid
+    pub fn royalties(&self) -> Royalties {
+        Royalties {
+            basis_points: self.metadata.seller_fee_basis_points,
+            creators: self
+                .metadata
+                .creators
+                .iter()
+                .filter(|creator| creator.verified)
+                .map(|creator| (creator.address, creator.share))
+                .collect(),
+        }
+    }
+}
+
+/// Asset id Bubblegum derives for the leaf minted at `nonce` in `merkle_tree`
+pub fn asset_id(merkle_tree: &Pubkey, nonce: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"asset", merkle_tree.as_ref(), &nonce.to_le_bytes()],
+        &BUBBLEGUM_ID,
+    )
+    .0
+}
+
+/// Moves a compressed NFT to a new owner with Bubblegum's transfer instruction
+pub struct CompressedTransfer<'a, 'info> {
+    pub bubblegum_program: AccountInfo<'info>,   // Bubblegum program
+    pub tree_authority: AccountInfo<'info>,      // Tree config PDA of the merkle tree
+    pub leaf_owner: AccountInfo<'info>,          // Current owner, signs the transfer
+    pub leaf_delegate: AccountInfo<'info>,       // Current delegate (the owner if none)
+    pub new_leaf_owner: AccountInfo<'info>,      // Owner after the transfer
+    pub merkle_tree: AccountInfo<'info>,         // Tree holding the leaf
+    pub log_wrapper: AccountInfo<'info>,         // SPL Noop program
+    pub compression_program: AccountInfo<'info>, // SPL Account Compression program
+    pub system_program: AccountInfo<'info>,      // System program
+    pub proof: &'a [AccountInfo<'info>],         // Proof nodes not covered by the tree's canopy
+    pub signer_seeds: &'a [&'a [&'a [u8]]],      // PDA seeds when `leaf_owner` is a PDA
+}
+
+impl<'a, 'info> CompressedTransfer<'a, 'info> {
+    /// Transfers the leaf at `index` with the given hashes, proving it against `root`
+    pub fn transfer(
+        &self,
+        root: [u8; 32],
+        data_hash: [u8; 32],
+        creator_hash: [u8; 32],
+        nonce: u64,
+        index: u32,
+    ) -> Result<()> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(self.tree_authority.key(), false),
+            AccountMeta::new_readonly(self.leaf_owner.key(), true),
+            AccountMeta::new_readonly(self.leaf_delegate.key(), false),
+            AccountMeta::new_readonly(self.new_leaf_owner.key(), false),
+            AccountMeta::new(self.merkle_tree.key(), false),
+            AccountMeta::new_readonly(self.log_wrapper.key(), false),
+            AccountMeta::new_readonly(self.compression_program.key(), false),
+            AccountMeta::new_readonly(self.system_program.key(), false),
+        ];
+        accounts.extend(
+            self.proof
+                .iter()
+                .map(|node| AccountMeta::new_readonly(node.key(), false)),
+        );
+
+        let mut data = TRANSFER_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&root);
+        data.extend_from_slice(&data_hash);
+        data.extend_from_slice(&creator_hash);
+        data.extend_from_slice(&nonce.to_le_bytes());
+        data.extend_from_slice(&index.to_le_bytes());
+
+        let mut account_infos = vec![
+            self.tree_authority.clone(),
+            self.leaf_owner.clone(),
+            self.leaf_delegate.clone(),
+            self.new_leaf_owner.clone(),
+            self.merkle_tree.clone(),
+            self.log_wrapper.clone(),
+            self.compression_program.clone(),
+            self.system_program.clone(),
+            self.bubblegum_program.clone(),
+        ];
+        account_infos.extend_from_slice(self.proof);
+
+        invoke_signed(
+            &Instruction {
+                program_id: BUBBLEGUM_ID,
+                accounts,
+                data,
+            },
+            &account_infos,
+            self.signer_seeds,
+        )
+        .map_err(Into::into)
+    }
+}
\ No newline at end of file
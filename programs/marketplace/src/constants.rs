@@ -1,4 +1,20 @@
 use anchor_lang::prelude::*;
 
 #[constant]
-pub const SEED: &str = "anchor";
\ No newline at end of file
+pub const SEED: &str = "anchor";
+
+#[constant]
+pub const MAX_FEE_BPS: u16 = 5000; // Hard ceiling on marketplace fee (5000 basis points = 50%)
+
+#[constant]
+pub const MAX_BID_INCREMENT_BPS: u16 = 10000; // Auctions can require at most double the high bid
+
+#[constant]
+pub const MAX_AUCTION_EXTENSIONS: i64 = 10; // Late bids extend an auction by at most this many windows in total
+
+// Not a #[constant]: the IDL has no usize type, which would break IDL generation
+pub const MAX_NAME_LEN: usize = 32; // Must match #[max_len] on Marketplace::name
+
+pub const MAX_PAYMENT_MINTS: usize = 8; // Must match #[max_len] on Marketplace::payment_mints
+
+pub const MAX_BUNDLE_SIZE: usize = 5; // Must match #[max_len] on BundleListing::mints
\ No newline at end of file
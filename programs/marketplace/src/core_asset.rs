@@ -0,0 +1,259 @@
+// Core Anchor framework imports
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke_signed,
+        pubkey,
+    },
+};
+
+// Local error imports
+use crate::error::MarketplaceError;
+use crate::settlement::Royalties;
+
+/// Metaplex Core program
+pub const MPL_CORE_ID: Pubkey = pubkey!("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
+
+// Account keys and instruction/plugin discriminators from the Core program
+const ASSET_V1: u8 = 1; // Key of an asset account
+const PLUGIN_HEADER_V1: u8 = 3; // Key of the plugin header after the account data
+const PLUGIN_REGISTRY_V1: u8 = 4; // Key of the plugin registry
+const COLLECTION_V1: u8 = 5; // Key of a collection account
+const UPDATE_AUTHORITY_COLLECTION: u8 = 2; // UpdateAuthority::Collection variant
+const AUTHORITY_ADDRESS: u8 = 3; // Authority::Address variant, the only one with a payload
+const ROYALTIES_PLUGIN: u8 = 0; // PluginType::Royalties variant
+const PERMANENT_FREEZE_DELEGATE_PLUGIN: u8 = 5; // PluginType::PermanentFreezeDelegate variant
+const PERMANENT_TRANSFER_DELEGATE_PLUGIN: u8 = 7; // PluginType::PermanentTransferDelegate variant
+const PERMANENT_BURN_DELEGATE_PLUGIN: u8 = 8; // PluginType::PermanentBurnDelegate variant
+const TRANSFER_V1: u8 = 14; // MplAssetInstruction::TransferV1 variant
+
+/// Fields of a Core asset the marketplace needs
+pub struct CoreAsset {
+    pub owner: Pubkey,                // Current owner of the asset
+    pub collection: Option<Pubkey>,   // Collection the asset belongs to, if any
+    pub royalties: Option<Royalties>, // Asset-level royalties plugin, if any
+    pub permanent_delegate: bool,     // Asset has a permanent transfer, freeze or burn delegate
+}
+
+impl CoreAsset {
+    /// Reads a Core asset account, checking it is owned by the Core program
+    pub fn try_from_account(info: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(*info.owner, MPL_CORE_ID, MarketplaceError::InvalidCoreAsset);
+        let data = info.try_borrow_data()?;
+        let mut reader = Reader::new(&data);
+
+        require!(reader.u8()? == ASSET_V1, MarketplaceError::InvalidCoreAsset);
+        let owner = reader.pubkey()?;
+
+        // Collection membership is the asset's update authority being the collection
+        let collection = match reader.u8()? {
+            0 => None, // UpdateAuthority::None
+            1 => {
+                reader.pubkey()?; // UpdateAuthority::Address, not a collection
+                None
+            }
+            UPDATE_AUTHORITY_COLLECTION => Some(reader.pubkey()?),
+            _ => return err!(MarketplaceError::InvalidCoreAsset),
+        };
+
+        reader.string()?; // Name
+        reader.string()?; // URI
+        if reader.u8()? == 1 {
+            reader.u64()?; // Seq, only set on compressed assets
+        }
+
+        let plugins = read_plugins(&data, reader.pos)?;
+        Ok(CoreAsset {
+            owner,
+            collection,
+            royalties: plugins.royalties,
+            permanent_delegate: plugins.permanent_delegate,
+        })
+    }
+}
+
+/// Fields of a Core collection the marketplace needs
+pub struct CoreCollection {
+    pub royalties: Option<Royalties>, // Collection-level royalties plugin, if any
+    pub permanent_delegate: bool,     // Collection has a permanent transfer, freeze or burn delegate
+}
+
+impl CoreCollection {
+    /// Reads a Core collection account, checking it is owned by the Core program
+    pub fn try_from_account(info: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(*info.owner, MPL_CORE_ID, MarketplaceError::InvalidCollection);
+        let data = info.try_borrow_data()?;
+        let mut reader = Reader::new(&data);
+
+        require!(reader.u8()? == COLLECTION_V1, MarketplaceError::InvalidCollection);
+        reader.pubkey()?; // Update authority
+        reader.string()?; // Name
+        reader.string()?; // URI
+        reader.u32()?; // Number minted
+        reader.u32()?; // Current size
+
+        let plugins = read_plugins(&data, reader.pos)?;
+        Ok(CoreCollection {
+            royalties: plugins.royalties,
+            permanent_delegate: plugins.permanent_delegate,
+        })
+    }
+}
+
+/// Moves a Core asset to `new_owner` with the Core TransferV1 instruction
+/// `authority` must be the current owner; pass `signer_seeds` when it is a PDA
+pub fn transfer_core_asset<'info>(
+    core_program: &AccountInfo<'info>,
+    asset: &AccountInfo<'info>,
+    collection: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    new_owner: &AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let ix = Instruction {
+        program_id: MPL_CORE_ID,
+        accounts: vec![
+            AccountMeta::new(asset.key(), false), // Asset being moved
+            AccountMeta::new_readonly(collection.key(), false), // Collection of the asset
+            AccountMeta::new(payer.key(), true), // Payer
+            AccountMeta::new_readonly(authority.key(), true), // Current owner
+            AccountMeta::new_readonly(new_owner.key(), false), // New owner
+            AccountMeta::new_readonly(MPL_CORE_ID, false), // No system program needed
+            AccountMeta::new_readonly(MPL_CORE_ID, false), // No log wrapper
+        ],
+        data: vec![TRANSFER_V1, 0], // TransferV1 with no compression proof
+    };
+
+    invoke_signed(
+        &ix,
+        &[
+            asset.clone(),
+            collection.clone(),
+            payer.clone(),
+            authority.clone(),
+            new_owner.clone(),
+            core_program.clone(),
+        ],
+        signer_seeds,
+    )
+    .map_err(Into::into)
+}
+
+/// Plugins of a Core asset or collection the marketplace reads
+#[derive(Default)]
+struct CorePlugins {
+    royalties: Option<Royalties>, // Royalties plugin, if any
+    permanent_delegate: bool,     // Whether a permanent transfer, freeze or burn delegate is set
+}
+
+/// Walks the plugin registry found through the plugin header that follows the account data
+fn read_plugins(data: &[u8], header_offset: usize) -> Result<CorePlugins> {
+    let mut plugins = CorePlugins::default();
+
+    // Accounts without plugins end right after their data
+    if header_offset >= data.len() {
+        return Ok(plugins);
+    }
+
+    let mut reader = Reader::at(data, header_offset);
+    require!(reader.u8()? == PLUGIN_HEADER_V1, MarketplaceError::InvalidCoreAsset);
+    let registry_offset = reader.offset()?;
+
+    let mut reader = Reader::at(data, registry_offset);
+    require!(reader.u8()? == PLUGIN_REGISTRY_V1, MarketplaceError::InvalidCoreAsset);
+
+    for _ in 0..reader.u32()? {
+        let plugin_type = reader.u8()?;
+        if reader.u8()? == AUTHORITY_ADDRESS {
+            reader.pubkey()?; // Plugin authority address
+        }
+        let plugin_offset = reader.offset()?;
+
+        match plugin_type {
+            ROYALTIES_PLUGIN => plugins.royalties = Some(read_royalties(data, plugin_offset)?),
+            PERMANENT_FREEZE_DELEGATE_PLUGIN
+            | PERMANENT_TRANSFER_DELEGATE_PLUGIN
+            | PERMANENT_BURN_DELEGATE_PLUGIN => plugins.permanent_delegate = true,
+            _ => {}
+        }
+    }
+
+    Ok(plugins)
+}
+
+/// Reads the royalties plugin stored at `plugin_offset`
+fn read_royalties(data: &[u8], plugin_offset: usize) -> Result<Royalties> {
+    let mut reader = Reader::at(data, plugin_offset);
+    require!(reader.u8()? == ROYALTIES_PLUGIN, MarketplaceError::InvalidCoreAsset);
+    let basis_points = reader.u16()?;
+
+    let mut creators = Vec::new();
+    for _ in 0..reader.u32()? {
+        let address = reader.pubkey()?;
+        let percentage = reader.u8()?;
+        creators.push((address, percentage));
+    }
+
+    Ok(Royalties {
+        basis_points,
+        creators,
+    })
+}
+
+/// Little-endian Borsh reader over raw Core account data
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader::at(data, 0)
+    }
+
+    fn at(data: &'a [u8], pos: usize) -> Self {
+        Reader { data, pos }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len).ok_or(MarketplaceError::InvalidCoreAsset)?;
+        let bytes = self
+            .data
+            .get(self.pos..end)
+            .ok_or(MarketplaceError::InvalidCoreAsset)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn offset(&mut self) -> Result<usize> {
+        usize::try_from(self.u64()?).map_err(|_| MarketplaceError::InvalidCoreAsset.into())
+    }
+
+    fn pubkey(&mut self) -> Result<Pubkey> {
+        Ok(Pubkey::new_from_array(self.take(32)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Result<()> {
+        let len = self.u32()? as usize;
+        self.take(len)?;
+        Ok(())
+    }
+}
\ No newline at end of file
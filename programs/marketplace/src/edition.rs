@@ -0,0 +1,87 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Metaplex imports
+use anchor_spl::metadata::{
+    mpl_token_metadata::{self, accounts::Edition, types::Key},
+    MasterEditionAccount,
+};
+use std::ops::Deref;
+
+// Local error imports
+use crate::error::MarketplaceError;
+
+/// Print edition of a limited edition NFT, the counterpart of anchor-spl's MasterEditionAccount
+#[derive(Clone, Debug, PartialEq)]
+pub struct EditionAccount(Edition);
+
+impl AccountDeserialize for EditionAccount {
+    fn try_deserialize(buf: &mut &[u8]) -> Result<Self> {
+        let edition = Self::try_deserialize_unchecked(buf)?;
+        if edition.key != Key::EditionV1 {
+            return Err(ErrorCode::AccountNotInitialized.into());
+        }
+        Ok(edition)
+    }
+
+    fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self> {
+        let edition = Edition::deserialize(buf).map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+        Ok(Self(edition))
+    }
+}
+
+impl Deref for EditionAccount {
+    type Target = Edition;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AccountSerialize for EditionAccount {}
+
+impl Owner for EditionAccount {
+    fn owner() -> Pubkey {
+        mpl_token_metadata::ID
+    }
+}
+
+/// Edition PDA of a Metaplex NFT, which holds a master edition for originals
+/// and a print edition for NFTs printed from one
+pub enum NftEdition {
+    Master(MasterEditionAccount), // Original NFT
+    Print(EditionAccount),        // Numbered print of a master edition
+}
+
+impl NftEdition {
+    /// Reads the edition PDA, checking it is a Token Metadata master or print edition
+    pub fn try_from_account(info: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(*info.owner, mpl_token_metadata::ID, MarketplaceError::InvalidEdition);
+        let data = info.try_borrow_data()?;
+
+        if let Ok(master) = MasterEditionAccount::try_deserialize(&mut &data[..]) {
+            return Ok(NftEdition::Master(master));
+        }
+        EditionAccount::try_deserialize(&mut &data[..])
+            .map(NftEdition::Print)
+            .map_err(|_| MarketplaceError::InvalidEdition.into())
+    }
+
+    /// Print number to record on the listing, None for a master edition
+    /// Fails for prints unless the marketplace allows them
+    pub fn listable_number(&self, allow_print_editions: bool) -> Result<Option<u64>> {
+        match self {
+            NftEdition::Master(_) => Ok(None),
+            NftEdition::Print(edition) => {
+                require!(allow_print_editions, MarketplaceError::PrintEditionNotAllowed);
+                Ok(Some(edition.edition))
+            }
+        }
+    }
+
+    /// Print number of a Metaplex NFT that is listed, auctioned or sold into an offer
+    /// The edition must be passed and be a master edition or a print the marketplace allows
+    pub fn check_listable(edition: Option<&AccountInfo>, allow_print_editions: bool) -> Result<Option<u64>> {
+        let edition = edition.ok_or(MarketplaceError::MissingEditionAccount)?;
+        Self::try_from_account(edition)?.listable_number(allow_print_editions)
+    }
+}
\ No newline at end of file
@@ -14,4 +14,106 @@ pub enum MarketplaceError {
     MathOverflow,
     #[msg("Fee percentage too high")]
     FeeTooHigh,
+    #[msg("Signer is not the pending admin")]
+    NotPendingAdmin,
+    #[msg("No admin handoff is in progress")]
+    NoPendingAdmin,
+    #[msg("Name is Empty")]
+    EmptyName,
+    #[msg("Treasury balance is insufficient")]
+    InsufficientTreasuryBalance,
+    #[msg("Creator account is missing")]
+    MissingCreatorAccount,
+    #[msg("Creator account does not match metadata")]
+    InvalidCreator,
+    #[msg("Payment mint is not accepted by this marketplace")]
+    PaymentMintNotAllowed,
+    #[msg("Payment mint is already accepted")]
+    PaymentMintAlreadyAllowed,
+    #[msg("Too many payment mints")]
+    TooManyPaymentMints,
+    #[msg("Payment mint does not match the listing")]
+    PaymentMintMismatch,
+    #[msg("Payment account is missing")]
+    MissingPaymentAccount,
+    #[msg("Account holding the NFT is missing")]
+    MissingNftAccount,
+    #[msg("Quantity must be greater than zero")]
+    InvalidQuantity,
+    #[msg("Auction times or extension window are invalid")]
+    InvalidAuctionTimes,
+    #[msg("Auction has not started")]
+    AuctionNotStarted,
+    #[msg("Auction has ended")]
+    AuctionEnded,
+    #[msg("Auction has not ended")]
+    AuctionNotEnded,
+    #[msg("Bid is below the reserve or current high bid")]
+    BidTooLow,
+    #[msg("Bidder does not match the auction")]
+    InvalidBidder,
+    #[msg("Dutch auction schedule is invalid")]
+    InvalidDutchAuction,
+    #[msg("Price is above the buyer's maximum")]
+    PriceAboveMax,
+    #[msg("Listing price does not match the expected price")]
+    PriceMismatch,
+    #[msg("Listing seller does not match the expected seller")]
+    SellerMismatch,
+    #[msg("Bundle must hold between 2 and MAX_BUNDLE_SIZE NFTs")]
+    InvalidBundleSize,
+    #[msg("Bundle NFT accounts do not match the bundle")]
+    InvalidBundleAccount,
+    #[msg("Listing does not have that quantity left")]
+    InsufficientQuantity,
+    #[msg("Programmable NFT accounts are missing")]
+    MissingProgrammableAccount,
+    #[msg("Account is not a valid Metaplex Core asset owned by the seller")]
+    InvalidCoreAsset,
+    #[msg("Compressed NFT metadata doesn't match the listed leaf")]
+    InvalidCompressedNft,
+    #[msg("Master edition account is missing")]
+    MissingEditionAccount,
+    #[msg("Metadata account is missing")]
+    MissingMetadataAccount,
+    #[msg("Programmable NFTs can only be listed in escrow")]
+    ProgrammableNotDelegable,
+    #[msg("Listing is no longer delegated the NFT")]
+    DelegationRevoked,
+    #[msg("Non-transferable tokens can't be listed")]
+    NonTransferableMint,
+    #[msg("Tokens with a permanent delegate can't be listed")]
+    PermanentDelegateMint,
+    #[msg("Token-2022 NFT must have 0 decimals, a supply of 1 and no mint authority")]
+    NotUniqueToken,
+    #[msg("Listing expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Listing has expired")]
+    ListingExpired,
+    #[msg("Listing has not expired")]
+    ListingNotExpired,
+    #[msg("Collection is disabled on this marketplace")]
+    CollectionDisabled,
+    #[msg("Price is outside the collection's price range")]
+    PriceOutOfRange,
+    #[msg("Minimum price is above the maximum price")]
+    InvalidPriceRange,
+    #[msg("Edition account is not a Token Metadata master or print edition")]
+    InvalidEdition,
+    #[msg("Print editions can't be listed on this marketplace")]
+    PrintEditionNotAllowed,
+    #[msg("Only delegated listings can be closed this way")]
+    NotDelegatedListing,
+    #[msg("Listing still holds its delegation and can be bought")]
+    ListingStillDelegated,
+    #[msg("Minimum bid increment is above MAX_BID_INCREMENT_BPS")]
+    InvalidBidIncrement,
+    #[msg("Every NFT in a bundle must have the same royalties")]
+    BundleRoyaltiesMismatch,
+    #[msg("Core listings are priced in SOL")]
+    CoreListingSolOnly,
+    #[msg("Only fungible and semi-fungible tokens can be listed by quantity")]
+    NotFungibleToken,
+    #[msg("Core assets with a permanent transfer, freeze or burn delegate can't be listed")]
+    PermanentDelegateAsset,
 }
\ No newline at end of file
@@ -0,0 +1,238 @@
+use anchor_lang::prelude::*; // Import essential Anchor framework components
+
+#[event] // Emitted when a new marketplace is created
+pub struct MarketplaceInitialized {
+    pub marketplace: Pubkey, // Marketplace PDA that was created
+    pub admin: Pubkey,       // Admin of the new marketplace
+    pub name: String,        // Marketplace name in the PDA seeds
+    pub fee: u16,            // Starting fee (basis points)
+    pub max_fee: u16,        // Highest fee the admin may ever set (basis points)
+    pub timestamp: i64,      // Unix timestamp of the creation
+}
+
+#[event] // Emitted when the admin changes marketplace settings
+pub struct MarketplaceUpdated {
+    pub marketplace: Pubkey,           // Marketplace that was updated
+    pub old_fee: u16,                  // Fee before the update (basis points)
+    pub new_fee: u16,                  // Fee after the update (basis points)
+    pub old_buyer_reward_rate: u64,    // Buyer reward rate before the update
+    pub new_buyer_reward_rate: u64,    // Buyer reward rate after the update
+    pub old_seller_reward_rate: u64,   // Seller reward rate before the update
+    pub new_seller_reward_rate: u64,   // Seller reward rate after the update
+    pub pending_admin: Option<Pubkey>, // Admin proposed by this update, if any
+    pub old_allow_print_editions: bool, // Print edition policy before the update
+    pub new_allow_print_editions: bool, // Print edition policy after the update
+}
+
+#[event] // Emitted when a proposed admin accepts the handoff
+pub struct AdminTransferred {
+    pub marketplace: Pubkey, // Marketplace whose admin changed
+    pub old_admin: Pubkey,   // Admin before the handoff
+    pub new_admin: Pubkey,   // Admin after the handoff
+}
+
+#[event] // Emitted when the admin withdraws a proposal before it is accepted
+pub struct AdminTransferCancelled {
+    pub marketplace: Pubkey,   // Marketplace whose handoff was cancelled
+    pub pending_admin: Pubkey, // Admin that can no longer accept
+}
+
+#[event] // Emitted when the admin withdraws collected fees
+pub struct TreasuryWithdrawn {
+    pub marketplace: Pubkey,  // Marketplace whose treasury paid out
+    pub mint: Option<Pubkey>, // Payment mint withdrawn, None for SOL
+    pub destination: Pubkey,  // Account that received the funds
+    pub amount: u64,          // Lamports or token base units withdrawn
+    pub remaining: u64,       // Amount left in the treasury (or its token account)
+}
+
+#[event] // Emitted when a seller lists an NFT, token quantity, Core asset or compressed NFT
+pub struct Listed {
+    pub marketplace: Pubkey,          // Marketplace the listing belongs to
+    pub mint: Pubkey,                 // Listed mint, Core asset or compressed asset id
+    pub seller: Pubkey,               // Seller who listed
+    pub price: u64,                   // Price per whole token (start price for Dutch listings)
+    pub quantity: u64,                // Base units listed (1 for an NFT)
+    pub payment_mint: Option<Pubkey>, // Payment mint of the price, None for SOL
+    pub expires_at: Option<i64>,      // When the listing stops being buyable, None for never
+    pub timestamp: i64,               // Unix timestamp of the listing
+}
+
+#[event] // Emitted when a listing is taken down by its seller, or cranked after expiring
+pub struct Delisted {
+    pub marketplace: Pubkey, // Marketplace the listing belonged to
+    pub mint: Pubkey,        // Listed mint, Core asset or compressed asset id
+    pub seller: Pubkey,      // Seller who gets the NFT back
+    pub timestamp: i64,      // Unix timestamp of the delisting
+}
+
+#[event] // Emitted when a purchase settles, with the full payment breakdown
+pub struct Sold {
+    pub marketplace: Pubkey,   // Marketplace the sale happened on
+    pub mint: Pubkey,          // Mint, Core asset or compressed asset id that was sold
+    pub buyer: Pubkey,         // Buyer who received it
+    pub seller: Pubkey,        // Seller who listed it
+    pub price: u64,            // Total paid by the buyer
+    pub quantity: u64,         // Base units of the token bought (1 for an NFT)
+    pub marketplace_fee: u64,  // Amount sent to the treasury
+    pub royalty: u64,          // Amount sent to verified creators
+    pub seller_proceeds: u64,  // Amount sent to the seller
+    pub timestamp: i64,        // Unix timestamp of the sale
+}
+
+#[event] // Emitted when the admin adds or removes an accepted payment mint
+pub struct PaymentMintUpdated {
+    pub marketplace: Pubkey, // Marketplace whose allowlist changed
+    pub mint: Pubkey,        // Payment mint added or removed
+    pub allowed: bool,       // True if added, false if removed
+}
+
+#[event] // Emitted when the admin adds or changes a collection's settings
+pub struct CollectionConfigUpdated {
+    pub marketplace: Pubkey,    // Marketplace the collection is approved on
+    pub collection: Pubkey,     // Collection whose settings changed
+    pub fee: Option<u16>,       // Fee override, None for the marketplace fee
+    pub min_price: Option<u64>, // Lowest listing price allowed
+    pub max_price: Option<u64>, // Highest listing price allowed
+    pub enabled: bool,          // Whether the collection can be listed and bought
+}
+
+#[event] // Emitted when the admin removes a collection from the marketplace
+pub struct CollectionRemoved {
+    pub marketplace: Pubkey, // Marketplace the collection was approved on
+    pub collection: Pubkey,  // Collection that was removed
+}
+
+#[event] // Emitted when a seller changes listing terms in place
+pub struct ListingUpdated {
+    pub marketplace: Pubkey,              // Marketplace the listing belongs to
+    pub mint: Pubkey,                     // Listed NFT mint
+    pub seller: Pubkey,                   // Seller who changed the listing
+    pub old_price: u64,                   // Price before the update
+    pub new_price: u64,                   // Price after the update
+    pub old_payment_mint: Option<Pubkey>, // Payment mint before the update
+    pub new_payment_mint: Option<Pubkey>, // Payment mint after the update
+    pub old_expires_at: Option<i64>,      // Expiry before the update, None for never
+    pub new_expires_at: Option<i64>,      // Expiry after the update, None for never
+}
+
+#[event] // Emitted when a buyer escrows an offer on an NFT
+pub struct OfferMade {
+    pub marketplace: Pubkey,          // Marketplace the offer was made on
+    pub mint: Pubkey,                 // NFT mint being bid on
+    pub buyer: Pubkey,                // Bidder
+    pub price: u64,                   // Escrowed bid
+    pub payment_mint: Option<Pubkey>, // Payment mint of the bid, None for SOL
+}
+
+#[event] // Emitted when a buyer withdraws their offer
+pub struct OfferCancelled {
+    pub marketplace: Pubkey, // Marketplace the offer was made on
+    pub mint: Pubkey,        // NFT mint that was bid on
+    pub buyer: Pubkey,       // Bidder who cancelled
+}
+
+#[event] // Emitted when an NFT holder accepts an offer, with the payment breakdown
+pub struct OfferAccepted {
+    pub marketplace: Pubkey,  // Marketplace the sale happened on
+    pub mint: Pubkey,         // NFT mint that was sold
+    pub buyer: Pubkey,        // Bidder who received the NFT
+    pub seller: Pubkey,       // Holder who accepted
+    pub price: u64,           // Offer price paid out of escrow
+    pub marketplace_fee: u64, // Amount sent to the treasury
+    pub royalty: u64,         // Amount sent to verified creators
+    pub seller_proceeds: u64, // Amount sent to the seller
+}
+
+#[event] // Emitted when a buyer escrows a bid on any NFT in a collection
+pub struct CollectionOfferMade {
+    pub marketplace: Pubkey,          // Marketplace the offer was made on
+    pub collection_mint: Pubkey,      // Collection being bid on
+    pub buyer: Pubkey,                // Bidder
+    pub price: u64,                   // Bid per NFT
+    pub quantity: u64,                // Number of NFTs wanted
+    pub payment_mint: Option<Pubkey>, // Payment mint of the bid, None for SOL
+}
+
+#[event] // Emitted when a buyer withdraws their collection offer
+pub struct CollectionOfferCancelled {
+    pub marketplace: Pubkey,     // Marketplace the offer was made on
+    pub collection_mint: Pubkey, // Collection that was bid on
+    pub buyer: Pubkey,           // Bidder who cancelled
+    pub unfilled_quantity: u64,  // NFTs that were never filled
+}
+
+#[event] // Emitted when a holder sells one NFT into a collection offer
+pub struct CollectionOfferFilled {
+    pub marketplace: Pubkey,     // Marketplace the sale happened on
+    pub collection_mint: Pubkey, // Collection the offer is for
+    pub mint: Pubkey,            // NFT mint that was sold
+    pub buyer: Pubkey,           // Bidder who received the NFT
+    pub seller: Pubkey,          // Holder who filled the offer
+    pub price: u64,              // Price paid out of escrow
+    pub remaining_quantity: u64, // NFTs still wanted after this fill
+    pub marketplace_fee: u64,    // Amount sent to the treasury
+    pub royalty: u64,            // Amount sent to verified creators
+    pub seller_proceeds: u64,    // Amount sent to the seller
+}
+
+#[event] // Emitted when a seller starts an auction
+pub struct AuctionCreated {
+    pub marketplace: Pubkey,        // Marketplace the auction runs on
+    pub mint: Pubkey,               // NFT mint being auctioned
+    pub seller: Pubkey,             // Seller who started the auction
+    pub reserve_price: u64,         // Lowest acceptable bid
+    pub start_time: i64,            // When bidding opens
+    pub end_time: i64,              // When bidding closes
+    pub extension_window: i64,      // Anti-sniping window in seconds
+    pub min_bid_increment_bps: u16, // Share of the high bid each new bid must add
+}
+
+#[event] // Emitted when a new high bid is escrowed
+pub struct BidPlaced {
+    pub marketplace: Pubkey, // Marketplace the auction runs on
+    pub mint: Pubkey,        // NFT mint being auctioned
+    pub bidder: Pubkey,      // New high bidder
+    pub amount: u64,         // New high bid
+    pub end_time: i64,       // End time after any anti-sniping extension
+}
+
+#[event] // Emitted when an auction settles, with the payment breakdown
+pub struct AuctionSettled {
+    pub marketplace: Pubkey,    // Marketplace the auction ran on
+    pub mint: Pubkey,           // NFT mint that was auctioned
+    pub seller: Pubkey,         // Seller who started the auction
+    pub winner: Option<Pubkey>, // Winning bidder, None if nobody bid
+    pub price: u64,             // Winning bid, zero if nobody bid
+    pub marketplace_fee: u64,   // Amount sent to the treasury
+    pub royalty: u64,           // Amount sent to verified creators
+    pub seller_proceeds: u64,   // Amount sent to the seller
+}
+
+#[event] // Emitted when a seller lists several NFTs as one lot
+pub struct BundleListed {
+    pub marketplace: Pubkey, // Marketplace the bundle is listed on
+    pub bundle: Pubkey,      // Bundle listing PDA
+    pub seller: Pubkey,      // Seller who listed the bundle
+    pub mints: Vec<Pubkey>,  // NFT mints in the bundle
+    pub price: u64,          // Price for the whole bundle
+}
+
+#[event] // Emitted when a seller takes a bundle down
+pub struct BundleDelisted {
+    pub marketplace: Pubkey, // Marketplace the bundle was listed on
+    pub bundle: Pubkey,      // Bundle listing PDA
+    pub seller: Pubkey,      // Seller who delisted
+}
+
+#[event] // Emitted when a bundle sells, with the payment breakdown
+pub struct BundleSold {
+    pub marketplace: Pubkey,  // Marketplace the sale happened on
+    pub bundle: Pubkey,       // Bundle listing PDA
+    pub buyer: Pubkey,        // Buyer who received the NFTs
+    pub seller: Pubkey,       // Seller who listed the bundle
+    pub price: u64,           // Bundle price paid by the buyer
+    pub marketplace_fee: u64, // Lamports sent to the treasury
+    pub royalty: u64,         // Lamports sent to verified creators
+    pub seller_proceeds: u64, // Lamports sent to the seller
+}
\ No newline at end of file
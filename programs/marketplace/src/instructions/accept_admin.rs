@@ -0,0 +1,39 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::AdminTransferred;
+use crate::Marketplace;
+
+#[derive(Accounts)] // Define accounts needed for accepting the admin role
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>, // Proposed admin taking over the marketplace
+
+    // Account which has the marketplace details
+    #[account(
+        mut, // Mutable because the admin is being replaced
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump, // Use stored bump to verify PDA
+        constraint = marketplace.pending_admin == Some(new_admin.key()) @ MarketplaceError::NotPendingAdmin // Only the proposed admin may accept
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace whose admin changes
+}
+
+impl<'info> AcceptAdmin<'info> {
+    /// Completes the admin handoff started by `update_marketplace`
+    pub fn accept(&mut self) -> Result<()> {
+        let old_admin = self.marketplace.admin; // Remember the outgoing admin for the event
+
+        self.marketplace.admin = self.new_admin.key(); // Hand over control
+        self.marketplace.pending_admin = None; // Handoff is complete
+
+        emit!(AdminTransferred {
+            marketplace: self.marketplace.key(),
+            old_admin,
+            new_admin: self.new_admin.key(),
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
@@ -0,0 +1,405 @@
+// Core Anchor framework imports
+use anchor_lang::{prelude::*, solana_program::sysvar};
+
+// SPL Token and Metaplex imports
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::Metadata,
+    token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface},
+};
+
+// Local state, event and error imports
+use crate::edition::NftEdition;
+use crate::error::MarketplaceError;
+use crate::events::CollectionOfferFilled;
+use crate::nft_metadata::NftMetadata;
+use crate::nft_transfer::{close_vault, NftTransfer, ProgrammableAccounts};
+use crate::settlement::{check_payment_accounts, settle_sale_with_royalties, Payer, Royalties};
+use crate::token_extensions::verify_group_member;
+use crate::{CollectionConfig, CollectionOffer, Listing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for filling a collection offer
+pub struct AcceptCollectionOffer<'info> {
+    #[account(mut)] // Mutable because seller receives payment and pays for buyer's ATA
+    pub seller: Signer<'info>, // Holder of an NFT from the collection
+
+    #[account(mut)] // Mutable because buyer receives the offer rent once it's filled
+    pub buyer: SystemAccount<'info>, // Bidder who made the collection offer
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>, // Marketplace configuration (fees, etc.)
+
+    pub collection_mint: Box<InterfaceAccount<'info, Mint>>, // Collection the offer is for
+
+    // Settings of the collection, which set the fee and can pause trading
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), collection_mint.key().as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>, // Collection's fee override, price range and status
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>, // NFT mint being sold into the offer
+
+    // Account which stores the offer details and escrows SOL bids
+    #[account(
+        mut, // Mutable because quantity drops and escrow is paid out
+        has_one = buyer, // Offer must belong to the buyer passed in
+        has_one = collection_mint, // Offer must be for this collection
+        seeds = [b"collection_offer", marketplace.key().as_ref(), collection_mint.key().as_ref(), buyer.key().as_ref()], // PDA: "collection_offer" + marketplace + collection + buyer
+        bump = collection_offer.bump // Use stored bump to verify PDA
+    )]
+    pub collection_offer: Box<Account<'info, CollectionOffer>>, // The offer being filled
+
+    // Buyer's token account for receiving the NFT
+    #[account(
+        init_if_needed, // Create buyer's ATA if it doesn't exist
+        payer = seller, // Seller pays since the buyer isn't signing
+        associated_token::mint = mint, // ATA for this specific NFT mint
+        associated_token::authority = buyer // Buyer owns this token account
+    )]
+    pub buyer_ata: Box<InterfaceAccount<'info, TokenAccount>>, // Buyer's token account to receive NFT
+
+    // Seller's token account, only passed when the NFT is in their wallet
+    #[account(
+        mut, // Mutable because the NFT leaves this account
+        associated_token::mint = mint, // ATA for this specific NFT mint
+        associated_token::authority = seller // Seller owns this token account
+    )]
+    pub seller_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Seller's NFT token account
+
+    // Listing, only passed when the NFT is currently listed
+    #[account(
+        mut, // Mutable because we're closing this account
+        close = seller, // Refund the listing rent to the seller
+        seeds = [marketplace.key().as_ref(), mint.key().as_ref()], // PDA: marketplace + NFT mint
+        bump = listing.bump, // Use stored bump to verify listing PDA
+        constraint = listing.maker == seller.key(), // Only the lister can fill for a listed NFT
+    )]
+    pub listing: Option<Box<Account<'info, Listing>>>, // Listing holding the NFT in escrow
+
+    // Vault, only passed when the NFT is currently listed
+    #[account(
+        mut, // Mutable because we're closing this vault after transfer
+        associated_token::mint = mint, // ATA for the NFT mint
+        associated_token::authority = listing // Listing PDA controls the vault
+    )]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Escrow vault holding the NFT
+
+    // Treasury account where marketplace fees are collected
+    #[account(
+        mut, // Mutable because treasury receives fee payments
+        seeds = [b"treasury", marketplace.key().as_ref()], // PDA: "treasury" + marketplace
+        bump = marketplace.treasury_bump // Use stored bump to verify treasury PDA
+    )]
+    pub treasury: SystemAccount<'info>, // Treasury account for marketplace fees
+
+    /// CHECK: Metaplex metadata PDA, checked by seeds and read when accepting
+    /// Uninitialized for Token-2022 NFTs without Metaplex metadata, which pay no
+    /// royalties, but always passed so it can't be hidden
+    #[account(
+        mut, // Mutable because Token Metadata writes to it when moving a pNFT
+        seeds = [ // Metaplex metadata PDA structure
+            b"metadata", // Metaplex metadata seed
+            metadata_program.key().as_ref(), // Metaplex program ID
+            mint.key().as_ref() // The NFT mint
+        ],
+        bump, // Canonical bump for metadata account
+        seeds::program = metadata_program.key(), // Verify this PDA belongs to Metaplex
+    )]
+    pub metadata: UncheckedAccount<'info>, // NFT metadata with collection, royalty and creator info, if the NFT has any
+
+    // SPL payment accounts, only passed when the offer is in a token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>, // Token the offer is in
+
+    // Escrow holding the token bid
+    #[account(
+        mut, // Mutable because one unit of the bid is paid out
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = collection_offer, // Offer PDA controls the escrow
+        associated_token::token_program = payment_token_program // Payment mint's token program
+    )]
+    pub escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Escrowed payment tokens
+
+    // Seller's token account receiving the proceeds
+    #[account(
+        init_if_needed, // Create seller's payment ATA if it doesn't exist
+        payer = seller, // Seller pays for their own account
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = seller, // Seller owns this token account
+        associated_token::token_program = payment_token_program // Payment mint's token program
+    )]
+    pub seller_payment_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Seller's payment tokens
+
+    // Treasury's token account collecting marketplace fees
+    #[account(
+        init_if_needed, // Create treasury's payment ATA if it doesn't exist
+        payer = seller, // Seller pays for account creation
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = treasury, // Treasury PDA owns this token account
+        associated_token::token_program = payment_token_program // Payment mint's token program
+    )]
+    pub treasury_payment_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Treasury's payment tokens
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>, // Token program of the payment mint
+
+    /// CHECK: Master or print edition, checked by seeds and parsed in verify_collection
+    #[account(
+        seeds=[ // Metaplex edition PDA structure
+            b"metadata", // Metaplex seed
+            metadata_program.key().as_ref(), // Metaplex program ID
+            mint.key().as_ref(), // NFT mint
+            b"edition" // Edition-specific seed
+        ],
+        bump, // Canonical bump for edition account
+        seeds::program = metadata_program.key() // Verify PDA belongs to Metaplex
+    )]
+    pub edition: Option<UncheckedAccount<'info>>, // Required with metadata; also moves programmable NFTs
+
+    // Programmable NFT accounts, only passed when the metadata token standard is programmable
+    /// CHECK: Token record of the vault or seller's token account, validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata updates or closes it
+    pub token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the buyer's token account, created and validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata creates or updates it
+    pub destination_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the pNFT, validated by Token Metadata
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token Auth Rules program, validated by Token Metadata
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = sysvar::instructions::ID)] // Must be the real instructions sysvar
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    // Program accounts
+    pub metadata_program: Program<'info, Metadata>, // Metaplex metadata program
+    pub system_program: Program<'info, System>, // For account operations
+    pub associated_token_program: Program<'info, AssociatedToken>, // For ATA creation
+    pub token_program: Interface<'info, TokenInterface>, // For NFT transfers
+}
+
+impl<'info> AcceptCollectionOffer<'info> {
+    /// Checks the NFT is a member of the offer's collection
+    /// Same rules as listing: metadata.collection must match and be verified, and
+    /// Token-2022 NFTs without metadata must be members of the collection's group
+    pub fn verify_collection(&self, metadata: Option<&NftMetadata<'info>>) -> Result<()> {
+        // Leaving out the metadata of a listed Metaplex NFT would skip its royalties
+        if let Some(listing) = &self.listing {
+            require!(
+                !listing.has_metadata || metadata.is_some(),
+                MarketplaceError::MissingMetadataAccount
+            );
+        }
+
+        let Some(metadata) = metadata else {
+            return verify_group_member(&self.mint, &self.collection_mint.key());
+        };
+
+        let collection = metadata
+            .collection
+            .as_ref()
+            .ok_or(MarketplaceError::InvalidCollection)?;
+
+        require_keys_eq!(
+            collection.key,
+            self.collection_mint.key(),
+            MarketplaceError::InvalidCollection
+        );
+        require!(collection.verified, MarketplaceError::UnverifedCollection);
+
+        // Same edition rule as a listing: master editions, or prints if allowed
+        NftEdition::check_listable(self.edition.as_deref(), self.marketplace.allow_print_editions)?;
+
+        Ok(())
+    }
+
+    /// Pays one unit of the offer out of escrow and decrements the remaining quantity
+    /// Uses the same fee and royalty split as a regular purchase
+    pub fn settle(
+        &mut self,
+        metadata: Option<&NftMetadata<'info>>,
+        creators: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        // Paused or removed collections can't be traded, offers included
+        self.collection_config.check_enabled()?;
+
+        check_payment_accounts(
+            self.collection_offer.payment_mint,
+            self.payment_mint.as_deref(),
+            self.escrow.is_some()
+                && self.seller_payment_ata.is_some()
+                && self.treasury_payment_ata.is_some()
+                && self.payment_token_program.is_some(),
+        )?;
+
+        // Create PDA signing seeds for collection offer authority over the escrow
+        let marketplace_key = self.marketplace.key();
+        let collection_key = self.collection_mint.key();
+        let buyer_key = self.buyer.key();
+        let seeds = &[
+            b"collection_offer".as_ref(), // Collection offer seed prefix
+            marketplace_key.as_ref(), // Marketplace address
+            collection_key.as_ref(), // Collection mint address
+            buyer_key.as_ref(), // Buyer address
+            &[self.collection_offer.bump], // Offer bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array structure for PDA signing
+
+        // SOL bids are paid straight out of the offer account, token bids out of escrow
+        let payer = match (&self.payment_mint, &self.escrow, &self.payment_token_program) {
+            (Some(payment_mint), Some(escrow), Some(payment_token_program)) => Payer::Token {
+                from: escrow.to_account_info(),
+                authority: self.collection_offer.to_account_info(),
+                mint: payment_mint.to_account_info(),
+                decimals: payment_mint.decimals,
+                token_program: payment_token_program.to_account_info(),
+                signer_seeds,
+            },
+            _ => Payer::Escrow {
+                from: self.collection_offer.to_account_info(),
+            },
+        };
+
+        // Seller and treasury receive into wallets, or token accounts for token offers
+        let seller = match &self.seller_payment_ata {
+            Some(seller_payment_ata) => seller_payment_ata.to_account_info(),
+            None => self.seller.to_account_info(),
+        };
+        let treasury = match &self.treasury_payment_ata {
+            Some(treasury_payment_ata) => treasury_payment_ata.to_account_info(),
+            None => self.treasury.to_account_info(),
+        };
+
+        let settlement = settle_sale_with_royalties(
+            &payer,
+            self.collection_offer.price,
+            self.collection_config.fee_bps(self.marketplace.fee),
+            &Royalties::from_optional_metadata(metadata),
+            creators,
+            seller,
+            treasury,
+        )?;
+
+        // One fewer NFT wanted; quantity is never zero while the offer is open
+        self.collection_offer.quantity -= 1;
+
+        // Last unit filled: close the escrow and the offer, returning rent to the buyer
+        if self.collection_offer.quantity == 0 {
+            if let (Some(escrow), Some(payment_token_program)) = (&self.escrow, &self.payment_token_program) {
+                let ctx = CpiContext::new_with_signer(
+                    payment_token_program.to_account_info(),
+                    CloseAccount {
+                        account: escrow.to_account_info(), // Escrow to close
+                        destination: self.buyer.to_account_info(), // Rent back to buyer
+                        authority: self.collection_offer.to_account_info(), // Offer PDA has authority
+                    },
+                    signer_seeds,
+                );
+                close_account(ctx)?;
+            }
+
+            self.collection_offer.close(self.buyer.to_account_info())?;
+        }
+
+        emit!(CollectionOfferFilled {
+            marketplace: marketplace_key,
+            collection_mint: collection_key,
+            mint: self.mint.key(),
+            buyer: buyer_key,
+            seller: self.seller.key(),
+            price: self.collection_offer.price,
+            remaining_quantity: self.collection_offer.quantity,
+            marketplace_fee: settlement.marketplace_fee,
+            royalty: settlement.royalty,
+            seller_proceeds: settlement.seller_proceeds,
+        });
+
+        Ok(())
+    }
+
+    /// Moves the NFT to the buyer, from the listing vault if listed or else the seller's wallet
+    /// Goes through Token Metadata for programmable NFTs; a listed NFT's vault is
+    /// closed here, the listing itself is closed by Anchor
+    ///
+    /// `extra_accounts` are the transfer hook accounts of a Token-2022 mint, if it has a hook
+    pub fn transfer_nft(
+        &mut self,
+        metadata: Option<&NftMetadata<'info>>,
+        extra_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let programmable = ProgrammableAccounts::from_optional(
+            &self.metadata_program,
+            metadata,
+            self.edition.as_ref(),
+            self.token_record.as_ref(),
+            self.destination_token_record.as_ref(),
+            self.authorization_rules.as_ref(),
+            self.authorization_rules_program.as_ref(),
+            self.sysvar_instructions.as_ref(),
+            &self.system_program,
+            &self.associated_token_program,
+        );
+
+        match (&self.listing, &self.vault) {
+            (Some(listing), Some(vault)) => {
+                // Create PDA signing seeds for listing authority
+                let seeds = &[
+                    &self.marketplace.key().to_bytes()[..], // Marketplace address as bytes
+                    &self.mint.key().to_bytes()[..], // NFT mint address as bytes
+                    &[listing.bump], // Listing bump seed
+                ];
+                let signer_seeds = &[&seeds[..]]; // Nested array structure for PDA signing
+
+                NftTransfer {
+                    from: vault.to_account_info(), // Source: marketplace vault
+                    from_owner: listing.to_account_info(), // Listing PDA owns the vault
+                    to: self.buyer_ata.to_account_info(), // Destination: buyer's token account
+                    to_owner: self.buyer.to_account_info(), // Buyer owns the destination account
+                    authority: listing.to_account_info(), // Listing PDA has authority over vault
+                    payer: self.seller.to_account_info(), // Seller pays for the buyer's token record
+                    mint: self.mint.to_account_info(), // NFT mint being transferred
+                    token_program: self.token_program.to_account_info(),
+                    signer_seeds,
+                    extra_accounts,
+                }
+                .transfer(1, self.mint.decimals, programmable.as_ref())?; // A single NFT
+
+                close_vault(
+                    vault.to_account_info(), // Empty listing vault
+                    self.seller.to_account_info(), // Vault rent back to the seller
+                    listing.to_account_info(), // Listing PDA has authority
+                    self.token_program.to_account_info(),
+                    signer_seeds,
+                )
+            }
+            (None, None) => {
+                let seller_ata = self
+                    .seller_ata
+                    .as_ref()
+                    .ok_or(MarketplaceError::MissingNftAccount)?;
+
+                NftTransfer {
+                    from: seller_ata.to_account_info(), // Source: seller's token account
+                    from_owner: self.seller.to_account_info(), // Seller owns the source account
+                    to: self.buyer_ata.to_account_info(), // Destination: buyer's token account
+                    to_owner: self.buyer.to_account_info(), // Buyer owns the destination account
+                    authority: self.seller.to_account_info(), // Seller authorizes the transfer
+                    payer: self.seller.to_account_info(), // Seller pays for the buyer's token record
+                    mint: self.mint.to_account_info(), // NFT mint being transferred
+                    token_program: self.token_program.to_account_info(),
+                    signer_seeds: &[], // Seller signs the transaction directly
+                    extra_accounts,
+                }
+                .transfer(1, self.mint.decimals, programmable.as_ref()) // A single NFT
+            }
+            _ => err!(MarketplaceError::MissingNftAccount), // Listing and vault go together
+        }
+    }
+}
\ No newline at end of file
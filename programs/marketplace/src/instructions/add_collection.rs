@@ -0,0 +1,68 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state and event imports
+use crate::events::CollectionConfigUpdated;
+use crate::{CollectionConfig, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for approving a collection
+#[instruction(collection: Pubkey)] // Access the collection in account constraints
+pub struct AddCollection<'info> {
+    #[account(mut)] // Mutable because the admin pays for the collection config
+    pub admin: Signer<'info>, // Marketplace admin authorizing the change
+
+    // Account which has the marketplace details
+    #[account(
+        has_one = admin, // Only the stored admin may approve collections
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace the collection is approved on
+
+    // Account which stores the collection settings
+    #[account(
+        init, // Create new collection config account
+        payer = admin, // Admin pays rent for the config
+        space = 8 + CollectionConfig::INIT_SPACE, // Account size: discriminator + config data
+        seeds = [b"collection", marketplace.key().as_ref(), collection.as_ref()], // PDA: "collection" + marketplace + collection
+        bump // Canonical bump for deterministic config address
+    )]
+    pub collection_config: Account<'info, CollectionConfig>, // Approved collection's settings
+
+    pub system_program: Program<'info, System>, // For account creation
+}
+
+impl<'info> AddCollection<'info> {
+    /// Approves a collection for listing, enabled straight away
+    pub fn add(
+        &mut self,
+        collection: Pubkey,
+        fee: Option<u16>,
+        min_price: Option<u64>,
+        max_price: Option<u64>,
+        bumps: &AddCollectionBumps,
+    ) -> Result<()> {
+        CollectionConfig::check_settings(fee, min_price, max_price, self.marketplace.max_fee)?;
+
+        self.collection_config.set_inner(CollectionConfig { // Write collection settings to account
+            marketplace: self.marketplace.key(), // Marketplace the collection is approved on
+            collection, // Collection mint, Core collection or token group
+            fee, // None charges the marketplace fee
+            min_price, // None allows any price down to zero
+            max_price, // None allows any price
+            enabled: true, // Listable as soon as it is added
+            bump: bumps.collection_config, // Store config PDA bump
+        });
+
+        emit!(CollectionConfigUpdated {
+            marketplace: self.marketplace.key(),
+            collection,
+            fee,
+            min_price,
+            max_price,
+            enabled: true,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
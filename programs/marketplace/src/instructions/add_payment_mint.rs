@@ -0,0 +1,52 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// SPL Token program imports
+use anchor_spl::token_interface::Mint;
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::PaymentMintUpdated;
+use crate::{Marketplace, MAX_PAYMENT_MINTS};
+
+#[derive(Accounts)] // Define accounts needed for accepting a new payment mint
+pub struct AddPaymentMint<'info> {
+    pub admin: Signer<'info>, // Marketplace admin authorizing the change
+
+    // Account which has the marketplace details
+    #[account(
+        mut, // Mutable because the allowlist grows
+        has_one = admin, // Only the stored admin may change the allowlist
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace whose allowlist changes
+
+    pub payment_mint: InterfaceAccount<'info, Mint>, // SPL or Token-2022 mint to accept
+}
+
+impl<'info> AddPaymentMint<'info> {
+    /// Adds the payment mint to the marketplace allowlist
+    pub fn add(&mut self) -> Result<()> {
+        let mint = self.payment_mint.key();
+
+        require!(
+            !self.marketplace.payment_mints.contains(&mint),
+            MarketplaceError::PaymentMintAlreadyAllowed
+        );
+        require!(
+            self.marketplace.payment_mints.len() < MAX_PAYMENT_MINTS,
+            MarketplaceError::TooManyPaymentMints
+        );
+
+        self.marketplace.payment_mints.push(mint); // Accept the mint for new listings
+
+        emit!(PaymentMintUpdated {
+            marketplace: self.marketplace.key(),
+            mint,
+            allowed: true,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::AdminTransferCancelled;
+use crate::Marketplace;
+
+#[derive(Accounts)] // Define accounts needed for withdrawing an admin proposal
+pub struct CancelAdminTransfer<'info> {
+    pub admin: Signer<'info>, // Current marketplace admin withdrawing the proposal
+
+    // Account which has the marketplace details
+    #[account(
+        mut, // Mutable because the pending admin is cleared
+        has_one = admin, // Only the stored admin may cancel the handoff
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace whose handoff is cancelled
+}
+
+impl<'info> CancelAdminTransfer<'info> {
+    /// Clears the admin proposed by `update_marketplace`, so it can no longer be accepted
+    pub fn cancel(&mut self) -> Result<()> {
+        let pending_admin = self
+            .marketplace
+            .pending_admin
+            .take()
+            .ok_or(MarketplaceError::NoPendingAdmin)?; // Nothing to cancel
+
+        emit!(AdminTransferCancelled {
+            marketplace: self.marketplace.key(),
+            pending_admin,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
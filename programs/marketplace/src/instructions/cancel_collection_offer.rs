@@ -0,0 +1,127 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// SPL Token program imports
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+// Local state, event and error imports
+use crate::events::CollectionOfferCancelled;
+use crate::settlement::check_payment_accounts;
+use crate::{CollectionOffer, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for cancelling a collection offer
+pub struct CancelCollectionOffer<'info> {
+    #[account(mut)] // Mutable because buyer receives the refund and rent
+    pub buyer: Signer<'info>, // Bidder withdrawing their offer
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace the offer was made on
+
+    pub collection_mint: InterfaceAccount<'info, Mint>, // Collection the offer was for
+
+    // Account which stores the offer details
+    #[account(
+        mut, // Mutable because we're closing this account
+        close = buyer, // Refund remaining SOL escrow and rent to the buyer
+        has_one = buyer, // Only the bidder may cancel
+        seeds = [b"collection_offer", marketplace.key().as_ref(), collection_mint.key().as_ref(), buyer.key().as_ref()], // PDA: "collection_offer" + marketplace + collection + buyer
+        bump = collection_offer.bump // Use stored bump to verify PDA
+    )]
+    pub collection_offer: Account<'info, CollectionOffer>, // The offer being cancelled
+
+    // SPL payment accounts, only passed when the offer is in a token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>, // Token the offer is in
+
+    // Buyer's token account receiving the refund
+    #[account(
+        mut, // Mutable because it receives the refund
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = buyer, // Buyer owns this token account
+        associated_token::token_program = payment_token_program // Payment mint's token program
+    )]
+    pub buyer_payment_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Buyer's payment tokens
+
+    // Escrow holding the unfilled token bid
+    #[account(
+        mut, // Mutable because we're emptying and closing it
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = collection_offer, // Offer PDA controls the escrow
+        associated_token::token_program = payment_token_program // Payment mint's token program
+    )]
+    pub escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Escrowed payment tokens
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>, // Token program of the payment mint
+}
+
+impl<'info> CancelCollectionOffer<'info> {
+    /// Returns the unfilled token bid from escrow to the buyer and closes the escrow
+    /// Unfilled SOL bids are refunded when Anchor closes the offer account
+    pub fn refund(&mut self) -> Result<()> {
+        check_payment_accounts(
+            self.collection_offer.payment_mint,
+            self.payment_mint.as_deref(),
+            self.buyer_payment_ata.is_some()
+                && self.escrow.is_some()
+                && self.payment_token_program.is_some(),
+        )?;
+
+        if let (Some(payment_mint), Some(buyer_payment_ata), Some(escrow), Some(payment_token_program)) = (
+            &self.payment_mint,
+            &self.buyer_payment_ata,
+            &self.escrow,
+            &self.payment_token_program,
+        ) {
+            // Create PDA signing seeds for collection offer authority
+            let marketplace_key = self.marketplace.key();
+            let collection_key = self.collection_mint.key();
+            let buyer_key = self.buyer.key();
+            let seeds = &[
+                b"collection_offer".as_ref(), // Collection offer seed prefix
+                marketplace_key.as_ref(), // Marketplace address
+                collection_key.as_ref(), // Collection mint address
+                buyer_key.as_ref(), // Buyer address
+                &[self.collection_offer.bump], // Offer bump seed
+            ];
+            let signer_seeds = &[&seeds[..]]; // Nested array structure for PDA signing
+
+            let ctx = CpiContext::new_with_signer(
+                payment_token_program.to_account_info(),
+                TransferChecked {
+                    from: escrow.to_account_info(), // Source: offer escrow
+                    to: buyer_payment_ata.to_account_info(), // Destination: buyer's payment ATA
+                    authority: self.collection_offer.to_account_info(), // Offer PDA has authority over escrow
+                    mint: payment_mint.to_account_info(), // Payment mint
+                },
+                signer_seeds,
+            );
+            transfer_checked(ctx, escrow.amount, payment_mint.decimals)?;
+
+            let ctx = CpiContext::new_with_signer(
+                payment_token_program.to_account_info(),
+                CloseAccount {
+                    account: escrow.to_account_info(), // Escrow to close
+                    destination: self.buyer.to_account_info(), // Rent back to buyer
+                    authority: self.collection_offer.to_account_info(), // Offer PDA has authority
+                },
+                signer_seeds,
+            );
+            close_account(ctx)?;
+        }
+
+        emit!(CollectionOfferCancelled {
+            marketplace: self.marketplace.key(),
+            collection_mint: self.collection_mint.key(),
+            buyer: self.buyer.key(),
+            unfilled_quantity: self.collection_offer.quantity,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
@@ -0,0 +1,126 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// SPL Token program imports
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+    TransferChecked,
+};
+
+// Local state, event and error imports
+use crate::events::OfferCancelled;
+use crate::settlement::check_payment_accounts;
+use crate::{Marketplace, Offer};
+
+#[derive(Accounts)] // Define accounts needed for cancelling an offer
+pub struct CancelOffer<'info> {
+    #[account(mut)] // Mutable because buyer receives the refund and rent
+    pub buyer: Signer<'info>, // Bidder withdrawing their offer
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace the offer was made on
+
+    pub mint: InterfaceAccount<'info, Mint>, // NFT mint the offer was for
+
+    // Account which stores the offer details
+    #[account(
+        mut, // Mutable because we're closing this account
+        close = buyer, // Refund escrowed SOL and rent to the buyer
+        has_one = buyer, // Only the bidder may cancel
+        seeds = [b"offer", marketplace.key().as_ref(), mint.key().as_ref(), buyer.key().as_ref()], // PDA: "offer" + marketplace + NFT mint + buyer
+        bump = offer.bump // Use stored bump to verify PDA
+    )]
+    pub offer: Account<'info, Offer>, // The offer being cancelled
+
+    // SPL payment accounts, only passed when the offer is in a token
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>, // Token the offer is in
+
+    // Buyer's token account receiving the refund
+    #[account(
+        mut, // Mutable because it receives the refund
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = buyer, // Buyer owns this token account
+        associated_token::token_program = payment_token_program // Payment mint's token program
+    )]
+    pub buyer_payment_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Buyer's payment tokens
+
+    // Escrow holding the token bid
+    #[account(
+        mut, // Mutable because we're emptying and closing it
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = offer, // Offer PDA controls the escrow
+        associated_token::token_program = payment_token_program // Payment mint's token program
+    )]
+    pub escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Escrowed payment tokens
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>, // Token program of the payment mint
+}
+
+impl<'info> CancelOffer<'info> {
+    /// Returns a token bid from escrow to the buyer and closes the escrow
+    /// SOL bids are refunded when Anchor closes the offer account
+    pub fn refund(&mut self) -> Result<()> {
+        check_payment_accounts(
+            self.offer.payment_mint,
+            self.payment_mint.as_deref(),
+            self.buyer_payment_ata.is_some()
+                && self.escrow.is_some()
+                && self.payment_token_program.is_some(),
+        )?;
+
+        if let (Some(payment_mint), Some(buyer_payment_ata), Some(escrow), Some(payment_token_program)) = (
+            &self.payment_mint,
+            &self.buyer_payment_ata,
+            &self.escrow,
+            &self.payment_token_program,
+        ) {
+            // Create PDA signing seeds for offer authority
+            let marketplace_key = self.marketplace.key();
+            let mint_key = self.mint.key();
+            let buyer_key = self.buyer.key();
+            let seeds = &[
+                b"offer".as_ref(), // Offer seed prefix
+                marketplace_key.as_ref(), // Marketplace address
+                mint_key.as_ref(), // NFT mint address
+                buyer_key.as_ref(), // Buyer address
+                &[self.offer.bump], // Offer bump seed
+            ];
+            let signer_seeds = &[&seeds[..]]; // Nested array structure for PDA signing
+
+            let ctx = CpiContext::new_with_signer(
+                payment_token_program.to_account_info(),
+                TransferChecked {
+                    from: escrow.to_account_info(), // Source: offer escrow
+                    to: buyer_payment_ata.to_account_info(), // Destination: buyer's payment ATA
+                    authority: self.offer.to_account_info(), // Offer PDA has authority over escrow
+                    mint: payment_mint.to_account_info(), // Payment mint
+                },
+                signer_seeds,
+            );
+            transfer_checked(ctx, escrow.amount, payment_mint.decimals)?;
+
+            let ctx = CpiContext::new_with_signer(
+                payment_token_program.to_account_info(),
+                CloseAccount {
+                    account: escrow.to_account_info(), // Escrow to close
+                    destination: self.buyer.to_account_info(), // Rent back to buyer
+                    authority: self.offer.to_account_info(), // Offer PDA has authority
+                },
+                signer_seeds,
+            );
+            close_account(ctx)?;
+        }
+
+        emit!(OfferCancelled {
+            marketplace: self.marketplace.key(),
+            mint: self.mint.key(),
+            buyer: self.buyer.key(),
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
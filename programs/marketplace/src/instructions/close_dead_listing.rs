@@ -0,0 +1,84 @@
+// Core Anchor framework imports
+use anchor_lang::{prelude::*, solana_program::program_option::COption};
+
+// SPL Token program imports
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::Delisted;
+use crate::{Custody, Listing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for closing a delegated listing that can't be bought
+pub struct CloseDeadListing<'info> {
+    pub closer: Signer<'info>, // Anyone can clean up a dead listing
+
+    #[account(mut)] // Mutable because seller receives refunded account rent
+    pub seller: SystemAccount<'info>, // Original seller who gets the rent back
+
+    // Account which stores the listing details
+    #[account(
+        mut, // Mutable because we're closing this account
+        close = seller, // When closing, send remaining lamports to seller
+        seeds = [marketplace.key().as_ref(), mint.key().as_ref()], // PDA: marketplace + NFT mint
+        constraint = listing.maker == seller.key(), // Rent can only go back to the seller
+        bump = listing.bump // Use stored bump to verify PDA
+    )]
+    pub listing: Account<'info, Listing>, // The dead listing to be closed
+
+    // NFT mint of the listing, whose token program decides the seller's ATA
+    #[account(mint::token_program = token_program)] // Wrong token program would point at an empty ATA
+    pub mint: InterfaceAccount<'info, Mint>, // The listed NFT token mint
+
+    /// CHECK: Seller's ATA the listing was delegated, parsed in close_dead_listing
+    /// since the seller may have closed it or handed it to someone else
+    #[account(
+        address = get_associated_token_address_with_program_id(&seller.key(), &mint.key(), &token_program.key()) // Seller's ATA for this mint
+    )]
+    pub seller_ata: UncheckedAccount<'info>,
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read marketplace configuration
+
+    // Program accounts
+    pub token_program: Interface<'info, TokenInterface>, // Token program of the NFT mint
+}
+
+impl<'info> CloseDeadListing<'info> {
+    /// Closes a delegated listing that purchase would reject with DelegationRevoked:
+    /// the seller revoked the delegation, moved the NFT, or closed or gave away the token account
+    pub fn close_dead_listing(&mut self) -> Result<()> {
+        require!(
+            matches!(self.listing.custody, Custody::Delegate { .. }),
+            MarketplaceError::NotDelegatedListing
+        );
+
+        // Same conditions purchase needs to move the NFT, plus the seller still owning the account
+        let quantity = self.listing.quantity;
+        let data = self.seller_ata.try_borrow_data()?;
+        let alive = self.seller_ata.owner == self.token_program.key // Closed accounts belong to the System Program
+            && TokenAccount::try_deserialize(&mut &data[..]).is_ok_and(|seller_ata| {
+                seller_ata.owner == self.listing.maker
+                    && seller_ata.delegate == COption::Some(self.listing.key())
+                    && seller_ata.delegated_amount >= quantity
+                    && seller_ata.amount >= quantity
+            });
+        require!(!alive, MarketplaceError::ListingStillDelegated);
+
+        emit!(Delisted {
+            marketplace: self.marketplace.key(),
+            mint: self.mint.key(),
+            seller: self.seller.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(()) // Listing closes to the seller
+    }
+}
\ No newline at end of file
@@ -0,0 +1,220 @@
+// Core Anchor framework imports
+use anchor_lang::{prelude::*, solana_program::sysvar};
+
+// SPL Token and Metaplex imports
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::Delisted;
+use crate::nft_transfer::{close_vault, DelegatedNft, NftTransfer, ProgrammableAccounts};
+use crate::{Custody, Listing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for cleaning up an expired listing
+pub struct CrankExpired<'info> {
+    #[account(mut)] // Mutable because the cranker pays if the seller's ATA must be re-created
+    pub cranker: Signer<'info>, // Anyone can clean up an expired listing
+
+    #[account(mut)] // Mutable because seller receives refunded account rent
+    pub seller: SystemAccount<'info>, // Original seller who gets the NFT and rent back
+
+    // Account which stores the listing details
+    #[account(
+        mut, // Mutable because we're closing this account
+        close = seller, // When closing, send remaining lamports to seller
+        seeds = [marketplace.key().as_ref(), mint.key().as_ref()], // PDA: marketplace + NFT mint
+        constraint = listing.maker == seller.key(), // Rent and NFT can only go back to the seller
+        bump = listing.bump // Use stored bump to verify PDA
+    )]
+    pub listing: Account<'info, Listing>, // The expired listing to be closed
+
+    // NFT mint which is kept for sale in listing
+    pub mint: InterfaceAccount<'info, Mint>, // The listed NFT token mint
+
+    // Seller's account for the NFT, which they may have closed while it was listed
+    #[account(
+        init_if_needed, // Re-create the seller's ATA if it was closed
+        payer = cranker, // Cranker pays so the seller doesn't need to sign
+        associated_token::mint = mint, // ATA for this specific NFT mint
+        associated_token::authority = seller // Seller owns this token account
+    )]
+    pub seller_ata: Box<InterfaceAccount<'info, TokenAccount>>, // Seller's token account to receive NFT back
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read marketplace configuration
+
+    // Account where the NFT is kept in hold, for escrowed listings
+    #[account(
+        mut, // Mutable because we're transferring from and closing this account
+        associated_token::mint = mint, // ATA for this NFT mint
+        associated_token::authority = listing // Listing PDA controls this vault
+    )]
+    pub vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Vault holding the NFT during listing
+
+    // Left out for Token-2022 NFTs without Metaplex metadata
+    #[account(
+        mut, // Mutable because Token Metadata writes to it when moving a pNFT
+        seeds = [ // Metaplex metadata PDA structure
+            b"metadata", // Metaplex metadata seed
+            metadata_program.key().as_ref(), // Metaplex program ID
+            mint.key().as_ref() // The NFT mint
+        ],
+        bump, // Canonical bump for metadata account
+        seeds::program = metadata_program.key(), // Verify this PDA belongs to Metaplex
+    )]
+    pub metadata: Option<Box<Account<'info, MetadataAccount>>>, // NFT metadata, read for the token standard
+
+    // Programmable NFT accounts, only passed when the metadata token standard is programmable
+    // Also needed to thaw the NFT of a frozen delegated listing
+    /// CHECK: Master edition of the NFT, validated by Token Metadata
+    pub edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the vault, validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata updates or closes it
+    pub token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the seller's token account, created and validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata creates or updates it
+    pub destination_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the pNFT, validated by Token Metadata
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token Auth Rules program, validated by Token Metadata
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = sysvar::instructions::ID)] // Must be the real instructions sysvar
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    // Program accounts
+    pub metadata_program: Program<'info, Metadata>, // Metaplex metadata program
+    pub system_program: Program<'info, System>, // For account operations
+    pub token_program: Interface<'info, TokenInterface>, // For token transfers and closures
+    pub associated_token_program: Program<'info, AssociatedToken>, // For ATA creation
+}
+
+impl<'info> CrankExpired<'info> {
+    /// Checks the listing has expired and gives the NFT back to the seller
+    /// Escrowed NFTs leave the vault with the listing PDA as authority, through
+    /// Token Metadata for programmable NFTs; delegated ones are thawed in place
+    ///
+    /// `extra_accounts` are the transfer hook accounts of a Token-2022 mint, if it has a hook
+    pub fn return_nft(&mut self, extra_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        // Live listings can only be closed by their seller
+        require!(
+            self.listing.is_expired(Clock::get()?.unix_timestamp),
+            MarketplaceError::ListingNotExpired
+        );
+
+        emit!(Delisted {
+            marketplace: self.marketplace.key(),
+            mint: self.mint.key(),
+            seller: self.seller.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        // Create PDA signing seeds for listing authority
+        let seeds = &[
+            &self.marketplace.key().to_bytes()[..], // Marketplace address as bytes
+            &self.mint.key().to_bytes()[..], // NFT mint address as bytes
+            &[self.listing.bump], // Listing bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array for PDA signing
+
+        // Delegated listings never moved the NFT out of the seller's wallet
+        if let Custody::Delegate { frozen } = self.listing.custody {
+            return self.thaw_delegated(frozen, signer_seeds);
+        }
+
+        let vault = self
+            .vault
+            .as_ref()
+            .ok_or(MarketplaceError::MissingNftAccount)?;
+
+        let programmable = ProgrammableAccounts::from_optional(
+            &self.metadata_program,
+            self.metadata.as_deref(),
+            self.edition.as_ref(),
+            self.token_record.as_ref(),
+            self.destination_token_record.as_ref(),
+            self.authorization_rules.as_ref(),
+            self.authorization_rules_program.as_ref(),
+            self.sysvar_instructions.as_ref(),
+            &self.system_program,
+            &self.associated_token_program,
+        );
+
+        NftTransfer {
+            from: vault.to_account_info(), // Source: vault holding the NFT
+            from_owner: self.listing.to_account_info(), // Listing PDA owns the vault
+            to: self.seller_ata.to_account_info(), // Destination: seller's token account
+            to_owner: self.seller.to_account_info(), // Seller owns the destination account
+            authority: self.listing.to_account_info(), // Listing PDA has authority over vault
+            payer: self.cranker.to_account_info(), // Cranker pays for anything created
+            mint: self.mint.to_account_info(), // Which NFT mint to transfer
+            token_program: self.token_program.to_account_info(),
+            signer_seeds,
+            extra_accounts,
+        }
+        // Return whatever is still listed (1 with 0 decimals for an NFT)
+        .transfer(self.listing.quantity, self.mint.decimals, programmable.as_ref())
+    }
+
+    /// Thaws the seller's NFT if the listing froze it
+    /// Only the seller can revoke the leftover delegation, which the listing PDA
+    /// can't use once the listing is closed
+    fn thaw_delegated(&self, frozen: bool, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        if !frozen {
+            return Ok(());
+        }
+
+        let edition = self
+            .edition
+            .as_ref()
+            .ok_or(MarketplaceError::MissingEditionAccount)?;
+
+        DelegatedNft {
+            metadata_program: self.metadata_program.to_account_info(),
+            delegate: self.listing.to_account_info(),
+            token_account: self.seller_ata.to_account_info(),
+            edition: edition.to_account_info(),
+            mint: self.mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            signer_seeds,
+        }
+        .thaw()
+    }
+
+    /// Closes the vault of an escrowed listing and refunds its rent to the seller
+    pub fn close_vault(&mut self) -> Result<()> {
+        // Delegated listings have no vault
+        let Some(vault) = self.vault.as_ref() else {
+            return Ok(());
+        };
+
+        // Create PDA signing seeds for listing authority (same as return_nft)
+        let seeds = &[
+            &self.marketplace.key().to_bytes()[..], // Marketplace address as bytes
+            &self.mint.key().to_bytes()[..], // NFT mint address as bytes
+            &[self.listing.bump], // Listing bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array for PDA signing
+
+        close_vault(
+            vault.to_account_info(), // Empty listing vault
+            self.seller.to_account_info(), // Send vault rent to seller, not the cranker
+            self.listing.to_account_info(), // Listing PDA has authority
+            self.token_program.to_account_info(),
+            signer_seeds,
+        )
+    }
+}
\ No newline at end of file
@@ -0,0 +1,81 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state, event and error imports
+use crate::core_asset::{transfer_core_asset, MPL_CORE_ID};
+use crate::error::MarketplaceError;
+use crate::events::Delisted;
+use crate::{Listing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for cleaning up an expired Core listing
+pub struct CrankExpiredCore<'info> {
+    #[account(mut)] // Mutable because the cranker pays for the Core transfer
+    pub cranker: Signer<'info>, // Anyone can clean up an expired listing
+
+    #[account(mut)] // Mutable because seller receives refunded account rent
+    pub seller: SystemAccount<'info>, // Original seller who gets the asset and rent back
+
+    // Account which stores the listing details and owns the asset
+    #[account(
+        mut, // Mutable because we're closing this account
+        close = seller, // When closing, send remaining lamports to seller
+        seeds = [marketplace.key().as_ref(), asset.key().as_ref()], // PDA: marketplace + asset
+        constraint = listing.maker == seller.key(), // Rent and asset can only go back to the seller
+        bump = listing.bump // Use stored bump to verify PDA
+    )]
+    pub listing: Account<'info, Listing>, // The expired listing to be closed
+
+    /// CHECK: Core asset owned by the listing PDA, validated by the Core program
+    #[account(mut)] // Mutable because its owner changes back to the seller
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: Core collection of the asset, validated by the Core program
+    pub collection: UncheckedAccount<'info>,
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read marketplace configuration
+
+    /// CHECK: Metaplex Core program, checked by address
+    #[account(address = MPL_CORE_ID)] // Must be the real Core program
+    pub core_program: UncheckedAccount<'info>,
+}
+
+impl<'info> CrankExpiredCore<'info> {
+    /// Checks the listing has expired and transfers the asset from the listing PDA back to the seller
+    pub fn return_asset(&mut self) -> Result<()> {
+        // Live listings can only be closed by their seller
+        require!(
+            self.listing.is_expired(Clock::get()?.unix_timestamp),
+            MarketplaceError::ListingNotExpired
+        );
+
+        emit!(Delisted {
+            marketplace: self.marketplace.key(),
+            mint: self.asset.key(),
+            seller: self.seller.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        // Create PDA signing seeds for listing authority
+        let seeds = &[
+            &self.marketplace.key().to_bytes()[..], // Marketplace address as bytes
+            &self.asset.key().to_bytes()[..], // Asset address as bytes
+            &[self.listing.bump], // Listing bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array for PDA signing
+
+        transfer_core_asset(
+            &self.core_program,
+            &self.asset,
+            &self.collection,
+            &self.cranker, // Cranker pays so the seller doesn't need to sign
+            &self.listing.to_account_info(), // Listing PDA owns the asset
+            &self.seller, // Seller gets it back
+            signer_seeds,
+        )
+    }
+}
\ No newline at end of file
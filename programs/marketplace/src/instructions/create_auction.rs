@@ -0,0 +1,250 @@
+// Core Anchor framework imports
+use anchor_lang::{prelude::*, solana_program::sysvar};
+
+// SPL Token and Metaplex imports
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::Metadata,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+// Local state, event and error imports
+use crate::edition::NftEdition;
+use crate::error::MarketplaceError;
+use crate::events::AuctionCreated;
+use crate::nft_metadata::NftMetadata;
+use crate::nft_transfer::{NftTransfer, ProgrammableAccounts};
+use crate::token_extensions::{check_listable_mint, verify_group_member};
+use crate::{
+    Auction, CollectionConfig, Marketplace, MAX_AUCTION_EXTENSIONS, MAX_BID_INCREMENT_BPS,
+};
+
+#[derive(Accounts)] // Define accounts needed for starting an auction
+pub struct CreateAuction<'info> {
+    #[account(mut)] // Mutable because seller pays for accounts and signs
+    pub seller: Signer<'info>, // Person auctioning their NFT
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>, // Marketplace the auction runs on
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>, // The NFT token mint being auctioned
+
+    // Account which stores the auction details and escrows the high bid
+    #[account(
+        init, // Create new auction account
+        payer = seller, // Seller pays rent for auction account
+        space = 8 + Auction::INIT_SPACE, // Account size: discriminator + auction data
+        seeds = [b"auction", marketplace.key().as_ref(), mint.key().as_ref()], // PDA: "auction" + marketplace + NFT mint
+        bump // Canonical bump for deterministic auction address
+    )]
+    pub auction: Box<Account<'info, Auction>>, // Store reserve, timing and high bid
+
+    // Account which is storing the NFT
+    #[account(
+        mut, // Mutable because the NFT leaves this account
+        associated_token::mint = mint, // ATA for the specific NFT mint
+        associated_token::authority = seller // Seller owns this token account
+    )]
+    pub seller_ata: Box<InterfaceAccount<'info, TokenAccount>>, // Seller's NFT token account
+
+    // Account where the NFT is kept until the auction settles
+    #[account(
+        init, // Create vault to hold NFT during the auction
+        payer = seller, // Seller pays for vault creation
+        associated_token::mint = mint, // ATA for the NFT mint
+        associated_token::authority = auction // Auction PDA controls the vault
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>, // Escrow account holding NFT
+
+    pub collection_mint: Box<InterfaceAccount<'info, Mint>>, // Collection this NFT belongs to
+
+    // Admin-approved settings of the collection, which must exist for it to be listed
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), collection_mint.key().as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>, // Collection's fee override, price range and status
+
+    /// CHECK: Metaplex metadata PDA, checked by seeds and read in create
+    /// Uninitialized for Token-2022 NFTs that use the group/member extensions
+    /// instead, but always passed so it can't be hidden
+    #[account(
+        mut, // Mutable because Token Metadata writes to it when moving a pNFT
+        seeds = [ // Metaplex metadata PDA structure
+            b"metadata", // Metaplex metadata seed
+            metadata_program.key().as_ref(), // Metaplex program ID
+            mint.key().as_ref() // The NFT mint
+        ],
+        bump, // Canonical bump for metadata account
+        seeds::program = metadata_program.key(), // Verify this PDA belongs to Metaplex
+    )]
+    pub metadata: UncheckedAccount<'info>, // NFT metadata with collection info, if the NFT has any
+
+    /// CHECK: Master or print edition, checked by seeds and parsed in create
+    #[account(
+        seeds=[ // Metaplex edition PDA structure
+            b"metadata", // Metaplex seed
+            metadata_program.key().as_ref(), // Metaplex program ID
+            mint.key().as_ref(), // NFT mint
+            b"edition" // Edition-specific seed
+        ],
+        bump, // Canonical bump for edition account
+        seeds::program = metadata_program.key() // Verify PDA belongs to Metaplex
+    )]
+    pub edition: Option<UncheckedAccount<'info>>, // Required with metadata; also moves programmable NFTs
+
+    // Programmable NFT accounts, only passed when the metadata token standard is programmable
+    /// CHECK: Token record of the seller's token account, validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata updates or closes it
+    pub token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the vault, created and validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata creates or updates it
+    pub destination_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the pNFT, validated by Token Metadata
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token Auth Rules program, validated by Token Metadata
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = sysvar::instructions::ID)] // Must be the real instructions sysvar
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    // Program accounts
+    pub metadata_program: Program<'info, Metadata>, // Metaplex metadata program
+    pub system_program: Program<'info, System>, // For account creation
+    pub token_program: Interface<'info, TokenInterface>, // For token operations
+    pub associated_token_program: Program<'info, AssociatedToken>, // For ATA creation
+}
+
+impl<'info> CreateAuction<'info> {
+    /// Creates the auction after checking collection membership and timing
+    #[allow(clippy::too_many_arguments)]
+    pub fn create(
+        &mut self,
+        metadata: Option<&NftMetadata<'info>>,
+        reserve_price: u64,
+        start_time: i64,
+        end_time: i64,
+        extension_window: i64,
+        min_bid_increment_bps: u16,
+        bumps: &CreateAuctionBumps,
+    ) -> Result<()> {
+        // Same mint and collection rules as listing
+        check_listable_mint(&self.mint)?;
+        match metadata {
+            Some(metadata) => {
+                let collection = metadata
+                    .collection
+                    .as_ref()
+                    .ok_or(MarketplaceError::InvalidCollection)?;
+                require_keys_eq!(
+                    collection.key,
+                    self.collection_mint.key(),
+                    MarketplaceError::InvalidCollection
+                );
+                require!(collection.verified, MarketplaceError::UnverifedCollection);
+
+                // Same edition rule as a listing: master editions, or prints if allowed
+                NftEdition::check_listable(self.edition.as_deref(), self.marketplace.allow_print_editions)?;
+            }
+            None => verify_group_member(&self.mint, &self.collection_mint.key())?,
+        }
+
+        // Collection must be approved, enabled and allow this reserve
+        self.collection_config.check_price(reserve_price)?;
+
+        // Auction must end after it starts, in the future, and a late bid can't
+        // push the end back by more than the auction's own length
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            start_time < end_time
+                && end_time > now
+                && extension_window >= 0
+                && extension_window <= end_time - start_time,
+            MarketplaceError::InvalidAuctionTimes
+        );
+        require!(
+            min_bid_increment_bps <= MAX_BID_INCREMENT_BPS,
+            MarketplaceError::InvalidBidIncrement
+        );
+
+        // Late bids can add at most MAX_AUCTION_EXTENSIONS windows in total
+        let max_end_time = extension_window
+            .checked_mul(MAX_AUCTION_EXTENSIONS)
+            .and_then(|extension| end_time.checked_add(extension))
+            .ok_or(MarketplaceError::MathOverflow)?;
+
+        self.auction.set_inner(Auction { // Write auction data to account
+            seller: self.seller.key(), // Who is selling the NFT
+            mint: self.mint.key(), // Which NFT is being auctioned
+            collection: self.collection_mint.key(), // Collection whose fee applies at settlement
+            has_metadata: metadata.is_some(), // Metaplex NFTs must pay royalties at settlement
+            reserve_price, // Lowest acceptable bid
+            start_time, // When bidding opens
+            end_time, // When bidding closes
+            extension_window, // Anti-sniping window in seconds
+            max_end_time, // Cap on anti-sniping extensions
+            min_bid_increment_bps, // How much each bid must beat the last by
+            highest_bid: 0, // No bids yet
+            highest_bidder: None, // No bidder yet
+            bump: bumps.auction, // Store auction PDA bump
+        });
+
+        emit!(AuctionCreated {
+            marketplace: self.marketplace.key(),
+            mint: self.mint.key(),
+            seller: self.seller.key(),
+            reserve_price,
+            start_time,
+            end_time,
+            extension_window,
+            min_bid_increment_bps,
+        });
+
+        Ok(()) // Return success
+    }
+
+    /// Moves the NFT from the seller into the auction vault
+    /// Programmable NFTs go through Token Metadata, everything else through the token program
+    ///
+    /// `extra_accounts` are the transfer hook accounts of a Token-2022 mint, if it has a hook
+    pub fn deposit_nft(
+        &mut self,
+        metadata: Option<&NftMetadata<'info>>,
+        extra_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let programmable = ProgrammableAccounts::from_optional(
+            &self.metadata_program,
+            metadata,
+            self.edition.as_ref(),
+            self.token_record.as_ref(),
+            self.destination_token_record.as_ref(),
+            self.authorization_rules.as_ref(),
+            self.authorization_rules_program.as_ref(),
+            self.sysvar_instructions.as_ref(),
+            &self.system_program,
+            &self.associated_token_program,
+        );
+
+        NftTransfer {
+            from: self.seller_ata.to_account_info(), // Source: seller's token account
+            from_owner: self.seller.to_account_info(), // Seller owns the source account
+            to: self.vault.to_account_info(), // Destination: auction vault
+            to_owner: self.auction.to_account_info(), // Auction PDA owns the vault
+            authority: self.seller.to_account_info(), // Seller authorizes the transfer
+            payer: self.seller.to_account_info(), // Seller pays for the vault token record
+            mint: self.mint.to_account_info(), // Which token mint to transfer
+            token_program: self.token_program.to_account_info(),
+            signer_seeds: &[], // Seller signs the transaction directly
+            extra_accounts,
+        }
+        .transfer(1, self.mint.decimals, programmable.as_ref()) // A single NFT
+    }
+}
\ No newline at end of file
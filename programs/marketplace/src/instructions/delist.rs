@@ -1,16 +1,18 @@
 // Core Anchor framework imports
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::sysvar};
 
-// SPL Token program imports
+// SPL Token and Metaplex imports
 use anchor_spl::{
-    token_2022::close_account,
-    token_interface::{
-        transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
-    },
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token_interface::{revoke, Mint, Revoke, TokenAccount, TokenInterface},
 };
 
-// Local state imports
-use crate::{Listing, Marketplace};
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::Delisted;
+use crate::nft_transfer::{close_vault, DelegatedNft, NftTransfer, ProgrammableAccounts};
+use crate::{Custody, Listing, Marketplace};
 
 #[derive(Accounts)] // Define accounts needed for delisting instruction
 pub struct Delist<'info> {
@@ -32,6 +34,7 @@ pub struct Delist<'info> {
 
     // Account which is storing the NFT
     #[account(
+        mut, // Mutable because the NFT comes back, or the delegation is revoked
         associated_token::mint = mint, // ATA for this specific NFT mint
         associated_token::authority = seller // Seller owns this token account
     )]
@@ -39,36 +42,74 @@ pub struct Delist<'info> {
 
     // Account which has the marketplace details
     #[account(
-        seeds = [b"marketplace", marketplace.name.as_str().as_bytes()], // PDA: "marketplace" + name
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
         bump // Verify this is the correct marketplace PDA
     )]
     pub marketplace: Account<'info, Marketplace>, // Read marketplace configuration
 
-    // Account where the NFT is kept in hold
+    // Account where the NFT is kept in hold, for escrowed listings
     #[account(
         mut, // Mutable because we're closing this vault
         associated_token::mint = mint, // ATA for this NFT mint
         associated_token::authority = listing // Listing PDA controls this vault
     )]
-    pub vault: InterfaceAccount<'info, TokenAccount>, // Vault holding the NFT during listing
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>, // Vault holding the NFT during listing
+
+    // Left out for Token-2022 NFTs without Metaplex metadata
+    #[account(
+        mut, // Mutable because Token Metadata writes to it when moving a pNFT
+        seeds = [ // Metaplex metadata PDA structure
+            b"metadata", // Metaplex metadata seed
+            metadata_program.key().as_ref(), // Metaplex program ID
+            mint.key().as_ref() // The NFT mint
+        ],
+        bump, // Canonical bump for metadata account
+        seeds::program = metadata_program.key(), // Verify this PDA belongs to Metaplex
+    )]
+    pub metadata: Option<Box<Account<'info, MetadataAccount>>>, // NFT metadata, read for the token standard
+
+    // Programmable NFT accounts, only passed when the metadata token standard is programmable
+    // Also needed to thaw the NFT of a frozen delegated listing
+    /// CHECK: Master edition of the NFT, validated by Token Metadata
+    pub edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the vault, validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata updates or closes it
+    pub token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the seller's token account, created and validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata creates or updates it
+    pub destination_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the pNFT, validated by Token Metadata
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token Auth Rules program, validated by Token Metadata
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = sysvar::instructions::ID)] // Must be the real instructions sysvar
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
 
     // Program accounts
+    pub metadata_program: Program<'info, Metadata>, // Metaplex metadata program
     pub system_program: Program<'info, System>, // For account operations
     pub token_program: Interface<'info, TokenInterface>, // For token transfers and closures
+    pub associated_token_program: Program<'info, AssociatedToken>, // For pNFT transfers
 }
 
 impl<'info> Delist<'info> {
     /// Withdraws the NFT from the marketplace vault back to the seller's token account
-    /// Uses the listing PDA as authority to authorize the transfer
-    pub fn withdraw_nft(&mut self) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info(); // Get token program for CPI
-
-        let cpi_accounts = TransferChecked { // Set up NFT transfer back to seller
-            from: self.vault.to_account_info(), // Source: vault holding the NFT
-            to: self.seller_ata.to_account_info(), // Destination: seller's token account
-            authority: self.listing.to_account_info(), // Listing PDA has authority over vault
-            mint: self.mint.to_account_info(), // Which NFT mint to transfer
-        };
+    /// Uses the listing PDA as authority, through Token Metadata for programmable NFTs
+    ///
+    /// `extra_accounts` are the transfer hook accounts of a Token-2022 mint, if it has a hook
+    pub fn withdraw_nft(&mut self, extra_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        emit!(Delisted {
+            marketplace: self.marketplace.key(),
+            mint: self.mint.key(),
+            seller: self.seller.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
         // Create PDA signing seeds for listing authority
         let seeds = &[
@@ -78,14 +119,83 @@ impl<'info> Delist<'info> {
         ];
         let signer_seeds = &[&seeds[..]]; // Create nested array structure for signing
 
-        let ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds); // Create CPI context with PDA signing
+        // Delegated listings only have to give the NFT back its freedom
+        if let Custody::Delegate { frozen } = self.listing.custody {
+            return self.release_delegation(frozen, signer_seeds);
+        }
+
+        let vault = self
+            .vault
+            .as_ref()
+            .ok_or(MarketplaceError::MissingNftAccount)?;
+
+        let programmable = ProgrammableAccounts::from_optional(
+            &self.metadata_program,
+            self.metadata.as_deref(),
+            self.edition.as_ref(),
+            self.token_record.as_ref(),
+            self.destination_token_record.as_ref(),
+            self.authorization_rules.as_ref(),
+            self.authorization_rules_program.as_ref(),
+            self.sysvar_instructions.as_ref(),
+            &self.system_program,
+            &self.associated_token_program,
+        );
 
-        transfer_checked(ctx, 1, 0) // Transfer 1 NFT back to seller (amount=1, decimals=0 for NFTs)
+        NftTransfer {
+            from: vault.to_account_info(), // Source: vault holding the NFT
+            from_owner: self.listing.to_account_info(), // Listing PDA owns the vault
+            to: self.seller_ata.to_account_info(), // Destination: seller's token account
+            to_owner: self.seller.to_account_info(), // Seller owns the destination account
+            authority: self.listing.to_account_info(), // Listing PDA has authority over vault
+            payer: self.seller.to_account_info(), // Seller pays for any token record
+            mint: self.mint.to_account_info(), // Which NFT mint to transfer
+            token_program: self.token_program.to_account_info(),
+            signer_seeds,
+            extra_accounts,
+        }
+        // Return whatever is still listed (1 with 0 decimals for an NFT)
+        .transfer(self.listing.quantity, self.mint.decimals, programmable.as_ref())
+    }
+
+    /// Thaws the seller's NFT if the listing froze it, then revokes the listing's delegation
+    fn release_delegation(&self, frozen: bool, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        if frozen {
+            let edition = self
+                .edition
+                .as_ref()
+                .ok_or(MarketplaceError::MissingEditionAccount)?;
+
+            DelegatedNft {
+                metadata_program: self.metadata_program.to_account_info(),
+                delegate: self.listing.to_account_info(),
+                token_account: self.seller_ata.to_account_info(),
+                edition: edition.to_account_info(),
+                mint: self.mint.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                signer_seeds,
+            }
+            .thaw()?;
+        }
+
+        let ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Revoke {
+                source: self.seller_ata.to_account_info(), // Seller's token account
+                authority: self.seller.to_account_info(), // Seller removes the delegation
+            },
+        );
+        revoke(ctx)
     }
 
     /// Closes the marketplace vault account and transfers remaining lamports to seller
     /// This completes the delisting process and cleans up the vault
     pub fn close_account(&mut self) -> Result<()> {
+        // Delegated listings have no vault
+        let Some(vault) = self.vault.as_ref() else {
+            return Ok(());
+        };
+
         // Create PDA signing seeds for listing authority (same as withdraw_nft)
         let seeds = &[
             &self.marketplace.key().to_bytes()[..], // Marketplace address as bytes
@@ -94,18 +204,12 @@ impl<'info> Delist<'info> {
         ];
         let signer_seeds = &[&seeds[..]]; // Nested array for PDA signing
 
-        let cpi_accounts = CloseAccount { // Set up vault closure accounts
-            account: self.vault.to_account_info(), // Vault account to close
-            authority: self.listing.to_account_info(), // Listing PDA has authority
-            destination: self.seller.to_account_info(), // Send remaining lamports to seller
-        };
-
-        let ctx = CpiContext::new_with_signer( // Create CPI context with signing
-            self.token_program.to_account_info(), // Token program for vault closure
-            cpi_accounts, // Accounts for closure
-            signer_seeds, // PDA signing seeds
-        );
-
-        close_account(ctx) // Close the vault and send lamports to seller
+        close_vault(
+            vault.to_account_info(), // Empty listing vault
+            self.seller.to_account_info(), // Vault rent back to the seller
+            self.listing.to_account_info(), // Listing PDA has authority
+            self.token_program.to_account_info(),
+            signer_seeds,
+        )
     }
 }
\ No newline at end of file
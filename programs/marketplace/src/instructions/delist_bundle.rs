@@ -0,0 +1,82 @@
+// Core Anchor framework imports
+use anchor_lang::{prelude::*, solana_program::sysvar};
+
+// SPL Token and Metaplex imports
+use anchor_spl::{associated_token::AssociatedToken, metadata::Metadata, token_interface::TokenInterface};
+
+// Local state and event imports
+use crate::bundle::{BundleRelease, RELEASE_ACCOUNTS_PER_NFT};
+use crate::error::MarketplaceError;
+use crate::events::BundleDelisted;
+use crate::{BundleListing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for delisting a bundle
+pub struct DelistBundle<'info> {
+    #[account(mut)] // Mutable because seller receives refunded account rent
+    pub seller: Signer<'info>, // Seller taking the bundle down
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read marketplace configuration
+
+    // Account which stores the bundle details
+    #[account(
+        mut, // Mutable because we're closing this account
+        close = seller, // When closing, send remaining lamports to seller
+        seeds = [b"bundle", marketplace.key().as_ref(), seller.key().as_ref(), bundle_listing.bundle_id.to_le_bytes().as_ref()], // PDA: "bundle" + marketplace + seller + id
+        bump = bundle_listing.bump, // Use stored bump to verify bundle PDA
+        constraint = bundle_listing.maker == seller.key(), // Verify this bundle belongs to the seller
+    )]
+    pub bundle_listing: Account<'info, BundleListing>, // The bundle to be closed
+
+    // Programmable NFT accounts shared by every NFT, only passed when the bundle holds pNFTs
+    /// CHECK: Token Auth Rules program, validated by Token Metadata
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = sysvar::instructions::ID)] // Must be the real instructions sysvar
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    // Program accounts
+    pub metadata_program: Program<'info, Metadata>, // Metaplex metadata program, which moves pNFTs
+    pub system_program: Program<'info, System>, // For ATA creation
+    pub associated_token_program: Program<'info, AssociatedToken>, // For seller ATA creation
+    pub token_program: Interface<'info, TokenInterface>, // For NFT transfers and vault closures
+}
+
+impl<'info> DelistBundle<'info> {
+    /// Returns every NFT to the seller and closes the vaults
+    ///
+    /// `remaining` holds RELEASE_ACCOUNTS_PER_NFT accounts per bundled NFT, the
+    /// recipient being the seller, followed by the transfer hook accounts of any
+    /// Token-2022 mint with a hook
+    pub fn delist_bundle(&mut self, remaining: &'info [AccountInfo<'info>]) -> Result<()> {
+        let nft_count = self.bundle_listing.mints.len() * RELEASE_ACCOUNTS_PER_NFT;
+        require!(remaining.len() >= nft_count, MarketplaceError::InvalidBundleAccount);
+        let (nft_accounts, extra_accounts) = remaining.split_at(nft_count);
+
+        BundleRelease {
+            payer: self.seller.to_account_info(),
+            recipient: self.seller.to_account_info(),
+            rent_destination: self.seller.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            metadata_program: self.metadata_program.to_account_info(),
+            authorization_rules_program: self.authorization_rules_program.as_ref().map(|a| a.to_account_info()),
+            sysvar_instructions: self.sysvar_instructions.as_ref().map(|a| a.to_account_info()),
+        }
+        .release(&self.bundle_listing, self.marketplace.key(), nft_accounts, extra_accounts)?;
+
+        emit!(BundleDelisted {
+            marketplace: self.marketplace.key(),
+            bundle: self.bundle_listing.key(),
+            seller: self.seller.key(),
+        });
+
+        Ok(())
+    }
+}
\ No newline at end of file
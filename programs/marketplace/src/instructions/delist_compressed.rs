@@ -0,0 +1,98 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state and event imports
+use crate::compressed_nft::{
+    CompressedTransfer, BUBBLEGUM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID,
+};
+use crate::events::Delisted;
+use crate::{CompressedListing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for delisting a compressed NFT
+pub struct DelistCompressed<'info> {
+    #[account(mut)] // Mutable because seller receives refunded account rent
+    pub seller: Signer<'info>, // Original seller who wants to cancel their listing
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read marketplace configuration
+
+    // Account which stores the listing details and owns the leaf
+    #[account(
+        mut, // Mutable because we're closing this account
+        close = seller, // When closing, send remaining lamports to seller
+        seeds = [b"compressed", marketplace.key().as_ref(), merkle_tree.key().as_ref(), compressed_listing.nonce.to_le_bytes().as_ref()], // PDA: "compressed" + marketplace + tree + nonce
+        constraint = compressed_listing.maker == seller.key(), // Verify this listing belongs to the seller
+        bump = compressed_listing.bump // Use stored bump to verify PDA
+    )]
+    pub compressed_listing: Account<'info, CompressedListing>, // The listing account to be closed
+
+    /// CHECK: Merkle tree holding the leaf, checked by the compression program
+    #[account(mut)] // Mutable because the transfer replaces the leaf
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum tree config PDA, checked by seeds
+    #[account(
+        seeds = [merkle_tree.key().as_ref()], // PDA: merkle tree
+        bump, // Canonical bump for tree config
+        seeds::program = bubblegum_program.key(), // Verify this PDA belongs to Bubblegum
+    )]
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program, checked by address
+    #[account(address = BUBBLEGUM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program, checked by address
+    #[account(address = SPL_NOOP_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program, checked by address
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    // Program accounts
+    pub system_program: Program<'info, System>, // Passed through to Bubblegum
+}
+
+impl<'info> DelistCompressed<'info> {
+    /// Transfers the leaf from the listing PDA back to the seller
+    /// `proof` holds the proof nodes not covered by the tree's canopy
+    pub fn withdraw_compressed(&mut self, root: [u8; 32], proof: &[AccountInfo<'info>]) -> Result<()> {
+        emit!(Delisted {
+            marketplace: self.marketplace.key(),
+            mint: self.compressed_listing.asset_id,
+            seller: self.seller.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        // Create PDA signing seeds for listing authority
+        let seeds = &[
+            &b"compressed"[..], // Compressed listing seed
+            &self.marketplace.key().to_bytes()[..], // Marketplace address as bytes
+            &self.merkle_tree.key().to_bytes()[..], // Merkle tree address as bytes
+            &self.compressed_listing.nonce.to_le_bytes()[..], // Leaf nonce as bytes
+            &[self.compressed_listing.bump], // Listing bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array for PDA signing
+
+        let listing = &self.compressed_listing;
+        CompressedTransfer {
+            bubblegum_program: self.bubblegum_program.to_account_info(),
+            tree_authority: self.tree_authority.to_account_info(),
+            leaf_owner: listing.to_account_info(), // Listing PDA owns the leaf
+            leaf_delegate: listing.to_account_info(), // Bubblegum reset the delegate on listing
+            new_leaf_owner: self.seller.to_account_info(), // Seller gets it back
+            merkle_tree: self.merkle_tree.to_account_info(),
+            log_wrapper: self.log_wrapper.to_account_info(),
+            compression_program: self.compression_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            proof,
+            signer_seeds,
+        }
+        .transfer(root, listing.data_hash, listing.creator_hash, listing.nonce, listing.index)
+    }
+}
\ No newline at end of file
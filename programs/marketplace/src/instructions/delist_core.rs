@@ -0,0 +1,71 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state and event imports
+use crate::core_asset::{transfer_core_asset, MPL_CORE_ID};
+use crate::events::Delisted;
+use crate::{Listing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for delisting a Metaplex Core asset
+pub struct DelistCore<'info> {
+    #[account(mut)] // Mutable because seller receives refunded account rent
+    pub seller: Signer<'info>, // Original seller who wants to cancel their listing
+
+    // Account which stores the listing details and owns the asset
+    #[account(
+        mut, // Mutable because we're closing this account
+        close = seller, // When closing, send remaining lamports to seller
+        seeds = [marketplace.key().as_ref(), asset.key().as_ref()], // PDA: marketplace + asset
+        constraint = listing.maker == seller.key(), // Verify this listing belongs to the seller
+        bump = listing.bump // Use stored bump to verify PDA
+    )]
+    pub listing: Account<'info, Listing>, // The listing account to be closed
+
+    /// CHECK: Core asset owned by the listing PDA, validated by the Core program
+    #[account(mut)] // Mutable because its owner changes back to the seller
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: Core collection of the asset, validated by the Core program
+    pub collection: UncheckedAccount<'info>,
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read marketplace configuration
+
+    /// CHECK: Metaplex Core program, checked by address
+    #[account(address = MPL_CORE_ID)] // Must be the real Core program
+    pub core_program: UncheckedAccount<'info>,
+}
+
+impl<'info> DelistCore<'info> {
+    /// Transfers the asset from the listing PDA back to the seller
+    pub fn withdraw_asset(&mut self) -> Result<()> {
+        emit!(Delisted {
+            marketplace: self.marketplace.key(),
+            mint: self.asset.key(),
+            seller: self.seller.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        // Create PDA signing seeds for listing authority
+        let seeds = &[
+            &self.marketplace.key().to_bytes()[..], // Marketplace address as bytes
+            &self.asset.key().to_bytes()[..], // Asset address as bytes
+            &[self.listing.bump], // Listing bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array for PDA signing
+
+        transfer_core_asset(
+            &self.core_program,
+            &self.asset,
+            &self.collection,
+            &self.seller, // Seller pays
+            &self.listing.to_account_info(), // Listing PDA owns the asset
+            &self.seller, // Seller gets it back
+            signer_seeds,
+        )
+    }
+}
\ No newline at end of file
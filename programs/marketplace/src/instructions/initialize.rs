@@ -2,10 +2,12 @@
 use anchor_lang::prelude::*;
 
 // SPL Token program imports
-use anchor_spl::{token::Token, token_interface::Mint};
+use anchor_spl::token_interface::{Mint, TokenInterface};
 
-// Local state imports
-use crate::Marketplace;
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::MarketplaceInitialized;
+use crate::{Marketplace, MAX_FEE_BPS, MAX_NAME_LEN};
 
 #[derive(Accounts)] // Tell Anchor this struct defines instruction accounts
 #[instruction(name:String)] // Access the 'name' parameter in account constraints
@@ -21,7 +23,7 @@ pub struct Initialize<'info> {
     #[account(
         init, // Create this account (doesn't exist yet)
         payer = admin, // Admin pays the rent for this account
-        seeds = [b"marketplace", name.as_str().as_bytes()], // PDA derived from "marketplace" + name
+        seeds = [b"marketplace", name.as_bytes()], // PDA derived from "marketplace" + name
         bump, // Use canonical bump seed for deterministic address
         space = 8 + Marketplace::INIT_SPACE // 8 bytes discriminator + custom data size
     )]
@@ -40,28 +42,58 @@ pub struct Initialize<'info> {
         seeds = [b"reward", marketplace.key().as_ref()], // PDA derived from "reward" + marketplace
         bump, // Canonical bump for reward mint address
         mint::decimals = 6, // Set mint to 6 decimal places (like USDC)
-        mint::authority = admin // Admin controls minting new reward tokens
+        mint::authority = marketplace // Marketplace PDA mints rewards on every SOL purchase
     )]
     pub reward_mint: InterfaceAccount<'info, Mint>, // Token mint for marketplace rewards
 
     // Program accounts
     pub system_program: Program<'info, System>, // Needed for account creation
-    pub token_program: Program<'info, Token>, // Needed for mint creation
+    pub token_program: Interface<'info, TokenInterface>, // Token program the reward mint is created under
 }
 
 impl<'info> Initialize<'info> {
     /// Initializes a new marketplace with the provided name and fee structure
-    /// Sets up all the core marketplace configuration data
-    pub fn init(&mut self, name: String, fee: u16, bumps: &InitializeBumps) -> Result<()> {
+    /// Validates the configuration up front so a bad fee can't reach purchase
+    pub fn init(
+        &mut self,
+        name: String,
+        fee: u16,
+        max_fee: u16,
+        bumps: &InitializeBumps,
+    ) -> Result<()> {
+        // Name must fit the space reserved by #[max_len] and can't be blank
+        require!(!name.is_empty(), MarketplaceError::EmptyName);
+        require!(name.len() <= MAX_NAME_LEN, MarketplaceError::NameTooLong);
+
+        // Cap can't exceed the program-wide ceiling, and fee can't exceed the cap
+        require!(max_fee <= MAX_FEE_BPS, MarketplaceError::FeeTooHigh);
+        require!(fee <= max_fee, MarketplaceError::FeeTooHigh);
+
         // Create an instance of the Marketplace struct and initialize with provided parameters
         self.marketplace.set_inner(Marketplace { // Write data to the marketplace account
             admin: self.admin.key(), // Store admin's public key
+            pending_admin: None, // No admin handoff in progress
             fee, // Store marketplace fee (basis points)
+            max_fee, // Store per-marketplace fee cap (basis points)
+            buyer_reward_rate: 0, // Rewards start disabled until the admin sets a rate
+            seller_reward_rate: 0, // Rewards start disabled until the admin sets a rate
+            payment_mints: Vec::new(), // Only SOL is accepted until the admin allows a mint
+            allow_print_editions: false, // Only originals until the admin allows prints
             bump: bumps.marketplace, // Store marketplace PDA bump
             treasury_bump: bumps.treasury, // Store treasury PDA bump
             rewards_bump: bumps.reward_mint, // Store reward mint PDA bump
             name, // Store marketplace name
         });
+
+        emit!(MarketplaceInitialized {
+            marketplace: self.marketplace.key(),
+            admin: self.admin.key(),
+            name: self.marketplace.name.clone(),
+            fee,
+            max_fee,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         Ok(()) // Return success
     }
 }
\ No newline at end of file
@@ -1,16 +1,21 @@
 // Core Anchor framework imports
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::sysvar};
 
 // SPL Token program imports
 use anchor_spl::{
     associated_token::AssociatedToken,
-    metadata::{Metadata, MetadataAccount},
-    token::{transfer_checked, TransferChecked},
+    metadata::Metadata,
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-// Local state imports
-use crate::{Listing, Marketplace};
+// Local state, event and error imports
+use crate::edition::NftEdition;
+use crate::error::MarketplaceError;
+use crate::events::Listed;
+use crate::nft_metadata::NftMetadata;
+use crate::nft_transfer::{NftTransfer, ProgrammableAccounts};
+use crate::token_extensions::{check_listable_mint, verify_group_member};
+use crate::{CollectionConfig, Custody, DecayCurve, DutchAuction, Listing, Marketplace};
 
 #[derive(Accounts)]  // Define accounts needed for listing instruction
 pub struct List<'info> {
@@ -39,7 +44,7 @@ pub struct List<'info> {
 
     // account whcih has the marketplace details
     #[account(
-        seeds = [b"marketplace", marketplace.name.as_str().as_bytes()], // PDA: "marketplace" + name
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
         bump // Verify this is the correct marketplace PDA
     )]
     pub marketplace: Account<'info, Marketplace>, // Read marketplace config (fees, admin, etc.)
@@ -56,7 +61,18 @@ pub struct List<'info> {
     // metadata account which is used to verify the nft
     pub collection_mint: InterfaceAccount<'info, Mint>, // Collection this NFT belongs to
 
+    // Admin-approved settings of the collection, which must exist for it to be listed
     #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), collection_mint.key().as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>, // Collection's fee override, price range and status
+
+    /// CHECK: Metaplex metadata PDA, checked by seeds and read in list
+    /// Uninitialized for Token-2022 NFTs that use the metadata pointer and
+    /// group/member extensions instead, but always passed so it can't be hidden
+    #[account(
+        mut, // Mutable because Token Metadata writes to it when moving a pNFT
         seeds = [ // Metaplex metadata PDA structure
             b"metadata", // Metaplex metadata seed
             metadata_program.key().as_ref(), // Metaplex program ID
@@ -64,13 +80,12 @@ pub struct List<'info> {
         ],
         bump, // Canonical bump for metadata account
         seeds::program = metadata_program.key(), // Verify this PDA belongs to Metaplex
-        constraint = metadata.collection.as_ref().unwrap().key.as_ref() == collection_mint.key().as_ref(), // Verify NFT belongs to specified collection
-        constraint = metadata.collection.as_ref().unwrap().verified == true, // Verify collection is properly verified
     )]
-    pub metadata: Account<'info, MetadataAccount>, // NFT metadata with collection info
+    pub metadata: UncheckedAccount<'info>, // NFT metadata with collection info, if the NFT has any
 
+    /// CHECK: Master or print edition, checked by seeds and parsed in list
     #[account(
-        seeds=[ // Metaplex master edition PDA structure
+        seeds=[ // Metaplex edition PDA structure
             b"metadata", // Metaplex seed
             metadata_program.key().as_ref(), // Metaplex program ID
             seller_mint.key().as_ref(), // NFT mint
@@ -79,7 +94,26 @@ pub struct List<'info> {
         bump, // Canonical bump for edition account
         seeds::program = metadata_program.key() // Verify PDA belongs to Metaplex
     )]
-    pub edition: Account<'info, MetadataAccount>, // Master edition (proves it's unique NFT)
+    pub edition: Option<UncheckedAccount<'info>>, // Master edition, or print edition if the marketplace allows them
+
+    // Programmable NFT accounts, only passed when the metadata token standard is programmable
+    /// CHECK: Token record of the seller's token account, validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata updates or closes it
+    pub token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the vault, created and validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata creates or updates it
+    pub destination_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the pNFT, validated by Token Metadata
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token Auth Rules program, validated by Token Metadata
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = sysvar::instructions::ID)] // Must be the real instructions sysvar
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
 
     // Program accounts
     pub metadata_program: Program<'info, Metadata>, // Metaplex metadata program
@@ -89,29 +123,151 @@ pub struct List<'info> {
 }
 
 impl<'info> List<'info> {
-    pub fn list(&mut self, price: u64, bumps: &ListBumps) -> Result<()> {
+    pub fn list(
+        &mut self,
+        metadata: Option<&NftMetadata<'info>>,
+        price: u64,
+        payment_mint: Option<Pubkey>,
+        expires_at: Option<i64>,
+        bumps: &ListBumps,
+    ) -> Result<()> {
+        // Token-2022 extensions that would let the NFT be clawed back or never move
+        check_listable_mint(&self.seller_mint)?;
+
+        // Metaplex NFTs must be verified members of the collection and have an edition;
+        // Token-2022 NFTs without metadata must be members of the collection's group
+        let edition_number = match metadata {
+            Some(metadata) => {
+                let collection = metadata
+                    .collection
+                    .as_ref()
+                    .ok_or(MarketplaceError::InvalidCollection)?; // NFT has no collection at all
+                require_keys_eq!(
+                    collection.key,
+                    self.collection_mint.key(),
+                    MarketplaceError::InvalidCollection
+                );
+                require!(collection.verified, MarketplaceError::UnverifedCollection);
+
+                // Edition must be a real master edition, or a print the marketplace allows
+                NftEdition::check_listable(self.edition.as_deref(), self.marketplace.allow_print_editions)?
+            }
+            None => {
+                verify_group_member(&self.seller_mint, &self.collection_mint.key())?;
+                None // Token-2022 NFTs have no Metaplex edition
+            }
+        };
+
+        // Collection must be approved, enabled and allow this price
+        self.collection_config.check_price(price)?;
+
+        // Token-priced listings are only allowed in mints the admin accepts
+        if let Some(payment_mint) = payment_mint {
+            require!(
+                self.marketplace.payment_mints.contains(&payment_mint),
+                MarketplaceError::PaymentMintNotAllowed
+            );
+        }
+
+        // A listing that is already expired could never be bought
+        if let Some(expires_at) = expires_at {
+            require!(
+                expires_at > Clock::get()?.unix_timestamp,
+                MarketplaceError::InvalidExpiry
+            );
+        }
+
         self.listing.set_inner(Listing { // Write listing data to account
             maker: self.seller.key(), // Who is selling the NFT
             maker_mint: self.seller_mint.key(), // Which NFT is being sold
-            price, // Sale price in lamports
+            collection: self.collection_mint.key(), // Collection the NFT was checked against
+            price, // Sale price in lamports or payment mint base units
+            quantity: 1, // A single NFT
+            payment_mint, // None for SOL, otherwise the SPL mint buyers pay in
+            dutch_auction: None, // Fixed price listing
+            expires_at, // None keeps the listing open until sold or delisted
+            custody: Custody::Escrow, // Held by the listing PDA
+            edition_number, // Print number, None for originals
+            has_metadata: metadata.is_some(), // Metaplex NFTs must pay royalties on purchase
             bump: bumps.listing, // Store listing PDA bump
         });
+
+        emit!(Listed {
+            marketplace: self.marketplace.key(),
+            mint: self.seller_mint.key(),
+            seller: self.seller.key(),
+            price,
+            quantity: 1,
+            payment_mint,
+            expires_at,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
         Ok(()) // Return success
     }
 
-    pub fn deposit_nft(&mut self) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info(); // Get token program for CPI
+    /// Creates a Dutch listing whose price falls from start_price to floor_price
+    /// between start_time and end_time; purchase computes the price from the clock
+    pub fn list_dutch(
+        &mut self,
+        metadata: Option<&NftMetadata<'info>>,
+        dutch_auction: DutchAuction,
+        payment_mint: Option<Pubkey>,
+        expires_at: Option<i64>,
+        bumps: &ListBumps,
+    ) -> Result<()> {
+        // Price must fall (or stay flat) over a real window
+        require!(
+            dutch_auction.start_price >= dutch_auction.floor_price
+                && dutch_auction.start_time < dutch_auction.end_time,
+            MarketplaceError::InvalidDutchAuction
+        );
+        if let DecayCurve::Exponential { half_life } = dutch_auction.curve {
+            require!(half_life > 0, MarketplaceError::InvalidDutchAuction);
+        }
 
-        let cpi_accounts = TransferChecked { // Set up token transfer accounts
-            from: self.seller_ata.to_account_info(), // Source: seller's token account
-            to: self.vault.to_account_info(), // Destination: vault token account  
-            authority: self.seller.to_account_info(), // Who authorizes the transfer
-            mint: self.seller_mint.to_account_info(), // Which token mint to transfer
-        };
+        // Same checks and account layout as a fixed price listing
+        self.list(metadata, dutch_auction.start_price, payment_mint, expires_at, bumps)?;
+        self.collection_config.check_price(dutch_auction.floor_price)?; // Floor must be in range too
+        self.listing.dutch_auction = Some(dutch_auction); // Switch to the declining schedule
+        Ok(()) // Return success
+    }
 
-        let ctx = CpiContext::new(cpi_program, cpi_accounts); // Create cross-program invocation context
+    /// Moves the NFT from the seller into the listing vault
+    /// Programmable NFTs go through Token Metadata, everything else through the token program
+    ///
+    /// `extra_accounts` are the transfer hook accounts of a Token-2022 mint, if it has a hook
+    pub fn deposit_nft(
+        &mut self,
+        metadata: Option<&NftMetadata<'info>>,
+        extra_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let programmable = ProgrammableAccounts::from_optional(
+            &self.metadata_program,
+            metadata,
+            self.edition.as_ref(),
+            self.token_record.as_ref(),
+            self.destination_token_record.as_ref(),
+            self.authorization_rules.as_ref(),
+            self.authorization_rules_program.as_ref(),
+            self.sysvar_instructions.as_ref(),
+            &self.system_program,
+            &self.associated_token_program,
+        );
 
-        transfer_checked(ctx, 1, 0)?; // Transfer 1 NFT (amount=1, decimals=0 for NFTs)
-        Ok(()) // Return success
+        NftTransfer {
+            from: self.seller_ata.to_account_info(), // Source: seller's token account
+            from_owner: self.seller.to_account_info(), // Seller owns the source account
+            to: self.vault.to_account_info(), // Destination: vault token account
+            to_owner: self.listing.to_account_info(), // Listing PDA owns the vault
+            authority: self.seller.to_account_info(), // Seller authorizes the transfer
+            payer: self.seller.to_account_info(), // Seller pays for the vault token record
+            mint: self.seller_mint.to_account_info(), // Which token mint to transfer
+            token_program: self.token_program.to_account_info(),
+            signer_seeds: &[], // Seller signs the transaction directly
+            extra_accounts,
+        }
+        // Escrow the listed quantity (1 with 0 decimals for an NFT)
+        .transfer(self.listing.quantity, self.seller_mint.decimals, programmable.as_ref())
     }
 }
\ No newline at end of file
@@ -0,0 +1,243 @@
+// Core Anchor framework imports
+use anchor_lang::{prelude::*, solana_program::sysvar};
+
+// SPL Token and Metaplex imports
+use anchor_spl::{
+    associated_token::{create, AssociatedToken, Create},
+    metadata::{
+        mpl_token_metadata::accounts::{MasterEdition, Metadata as MetadataPda},
+        Metadata,
+    },
+    token_interface::{Mint, TokenInterface},
+};
+
+// Local state, event and error imports
+use crate::bundle::{optional_account, vault_address};
+use crate::edition::NftEdition;
+use crate::error::MarketplaceError;
+use crate::events::BundleListed;
+use crate::nft_metadata::NftMetadata;
+use crate::nft_transfer::{NftTransfer, ProgrammableAccounts};
+use crate::settlement::Royalties;
+use crate::token_extensions::{check_listable_mint, verify_group_member};
+use crate::{BundleListing, CollectionConfig, Marketplace, MAX_BUNDLE_SIZE};
+
+/// Remaining accounts passed per NFT when listing a bundle:
+/// the NFT mint, its metadata, its edition, the seller's token account, its token
+/// record, the vault to create, the vault's token record and the NFT's rule set
+/// Token records and rule set are only read for programmable NFTs, and the edition
+/// only for NFTs with Metaplex metadata; pass the marketplace program ID in their
+/// place otherwise
+/// The metadata PDA is always passed, uninitialized for Token-2022 group members
+pub const LIST_ACCOUNTS_PER_NFT: usize = 8;
+
+#[derive(Accounts)] // Define accounts needed for listing a bundle
+#[instruction(bundle_id: u64)] // Access the bundle id in account constraints
+pub struct ListBundle<'info> {
+    #[account(mut)] // Mutable because seller pays for the bundle and vaults
+    pub seller: Signer<'info>, // Person listing their NFTs as one lot
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read marketplace configuration
+
+    // Account which stores the bundle details
+    #[account(
+        init, // Create new bundle listing account
+        payer = seller, // Seller pays rent for the bundle account
+        space = 8 + BundleListing::INIT_SPACE, // Account size: discriminator + bundle data
+        seeds = [b"bundle", marketplace.key().as_ref(), seller.key().as_ref(), bundle_id.to_le_bytes().as_ref()], // PDA: "bundle" + marketplace + seller + id
+        bump // Canonical bump for deterministic bundle address
+    )]
+    pub bundle_listing: Account<'info, BundleListing>, // Store bundle price, mints and seller
+
+    pub collection_mint: InterfaceAccount<'info, Mint>, // Collection every bundled NFT belongs to
+
+    // Admin-approved settings of the collection, which must exist for it to be listed
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), collection_mint.key().as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Account<'info, CollectionConfig>, // Collection's fee override, price range and status
+
+    // Programmable NFT accounts shared by every NFT, only passed when the bundle holds pNFTs
+    /// CHECK: Token Auth Rules program, validated by Token Metadata
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = sysvar::instructions::ID)] // Must be the real instructions sysvar
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    // Program accounts
+    pub metadata_program: Program<'info, Metadata>, // Metaplex metadata program
+    pub system_program: Program<'info, System>, // For account creation
+    pub token_program: Interface<'info, TokenInterface>, // For token operations
+    pub associated_token_program: Program<'info, AssociatedToken>, // For vault creation
+}
+
+impl<'info> ListBundle<'info> {
+    /// Creates the bundle listing and escrows each NFT in its own vault
+    ///
+    /// `remaining` must hold LIST_ACCOUNTS_PER_NFT accounts for each of the
+    /// `nft_count` NFTs, the vault being the bundle PDA's ATA, followed by the
+    /// transfer hook accounts of any Token-2022 mint with a hook; programmable
+    /// NFTs go through Token Metadata
+    pub fn list_bundle(
+        &mut self,
+        bundle_id: u64,
+        price: u64,
+        nft_count: u8,
+        remaining: &'info [AccountInfo<'info>],
+        bumps: &ListBundleBumps,
+    ) -> Result<()> {
+        let count = usize::from(nft_count);
+        require!(
+            (2..=MAX_BUNDLE_SIZE).contains(&count)
+                && remaining.len() >= count * LIST_ACCOUNTS_PER_NFT,
+            MarketplaceError::InvalidBundleSize
+        );
+        // Token-2022 finds each mint's hook accounts by address, so every transfer gets them all
+        let (nft_accounts, extra_accounts) = remaining.split_at(count * LIST_ACCOUNTS_PER_NFT);
+
+        // Collection must be approved, enabled and allow this price, which the
+        // range is checked against as the total for the whole lot
+        self.collection_config.check_price(price)?;
+
+        let mut mints = Vec::with_capacity(count);
+        let mut royalties: Option<Royalties> = None; // Terms of the first NFT, which purchase_bundle pays
+
+        for accounts in nft_accounts.chunks(LIST_ACCOUNTS_PER_NFT) {
+            let [mint, metadata, edition, seller_ata, seller_token_record, vault, vault_token_record, authorization_rules] =
+                accounts
+            else {
+                return err!(MarketplaceError::InvalidBundleSize); // Length checked above
+            };
+
+            // Each NFT can only be in the bundle once
+            require!(!mints.contains(mint.key), MarketplaceError::InvalidBundleAccount);
+
+            // Same mint rules as a single listing
+            require_keys_eq!(*mint.owner, self.token_program.key(), MarketplaceError::InvalidBundleAccount);
+            let mint_account = InterfaceAccount::<Mint>::try_from(mint)?;
+            check_listable_mint(&mint_account)?;
+
+            // Metadata is read whenever its PDA exists, so it can't be hidden
+            require_keys_eq!(
+                metadata.key(),
+                MetadataPda::find_pda(mint.key).0,
+                MarketplaceError::InvalidBundleAccount
+            );
+            let metadata = NftMetadata::try_from_account(metadata)?;
+
+            // The lot is settled once with the first NFT's royalties, so every NFT
+            // must have the same terms for each creator to get what they're owed
+            let nft_royalties = Royalties::from_optional_metadata(metadata.as_ref());
+            match &royalties {
+                Some(royalties) => require!(
+                    *royalties == nft_royalties,
+                    MarketplaceError::BundleRoyaltiesMismatch
+                ),
+                None => royalties = Some(nft_royalties),
+            }
+
+            // Same collection and edition checks as a single listing; Token-2022
+            // NFTs without metadata must be members of the collection's group
+            let edition = match &metadata {
+                Some(metadata) => {
+                    let collection = metadata
+                        .collection
+                        .as_ref()
+                        .ok_or(MarketplaceError::InvalidCollection)?;
+                    require_keys_eq!(
+                        collection.key,
+                        self.collection_mint.key(),
+                        MarketplaceError::InvalidCollection
+                    );
+                    require!(collection.verified, MarketplaceError::UnverifedCollection);
+
+                    // Master editions, or prints if allowed
+                    require_keys_eq!(
+                        edition.key(),
+                        MasterEdition::find_pda(mint.key).0,
+                        MarketplaceError::InvalidEdition
+                    );
+                    NftEdition::check_listable(Some(edition), self.marketplace.allow_print_editions)?;
+                    Some(edition.clone())
+                }
+                None => {
+                    verify_group_member(&mint_account, &self.collection_mint.key())?;
+                    None
+                }
+            };
+
+            // Vault must be the bundle PDA's ATA so release can find it again
+            require_keys_eq!(
+                vault.key(),
+                vault_address(&self.bundle_listing.key(), mint.key, &self.token_program.key()),
+                MarketplaceError::InvalidBundleAccount
+            );
+
+            create(CpiContext::new(
+                self.associated_token_program.to_account_info(),
+                Create {
+                    payer: self.seller.to_account_info(), // Seller pays for the vault
+                    associated_token: vault.clone(), // Vault to create
+                    authority: self.bundle_listing.to_account_info(), // Bundle PDA controls the vault
+                    mint: mint.clone(), // NFT mint
+                    system_program: self.system_program.to_account_info(),
+                    token_program: self.token_program.to_account_info(),
+                },
+            ))?;
+
+            let programmable = ProgrammableAccounts::from_optional(
+                &self.metadata_program,
+                metadata.as_ref(),
+                edition.as_ref(),
+                optional_account(seller_token_record).as_ref(),
+                optional_account(vault_token_record).as_ref(),
+                optional_account(authorization_rules).as_ref(),
+                self.authorization_rules_program.as_ref(),
+                self.sysvar_instructions.as_ref(),
+                &self.system_program,
+                &self.associated_token_program,
+            );
+
+            NftTransfer {
+                from: seller_ata.clone(), // Source: seller's token account
+                from_owner: self.seller.to_account_info(), // Seller owns the source account
+                to: vault.clone(), // Destination: bundle vault
+                to_owner: self.bundle_listing.to_account_info(), // Bundle PDA owns the vault
+                authority: self.seller.to_account_info(), // Seller authorizes the transfer
+                payer: self.seller.to_account_info(), // Seller pays for the vault token record
+                mint: mint.clone(), // NFT mint being transferred
+                token_program: self.token_program.to_account_info(),
+                signer_seeds: &[], // Seller signs the transaction directly
+                extra_accounts,
+            }
+            .transfer(1, mint_account.decimals, programmable.as_ref())?; // A single NFT
+
+            mints.push(mint.key());
+        }
+
+        emit!(BundleListed {
+            marketplace: self.marketplace.key(),
+            bundle: self.bundle_listing.key(),
+            seller: self.seller.key(),
+            mints: mints.clone(),
+            price,
+        });
+
+        self.bundle_listing.set_inner(BundleListing { // Write bundle data to account
+            maker: self.seller.key(), // Who is selling the bundle
+            bundle_id, // Seller-chosen id in the PDA seeds
+            collection_mint: self.collection_mint.key(), // Collection all NFTs were checked against
+            mints, // NFTs in the bundle, in vault order
+            price, // Price for the whole bundle in lamports
+            bump: bumps.bundle_listing, // Store bundle PDA bump
+        });
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
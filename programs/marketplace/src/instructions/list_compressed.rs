@@ -0,0 +1,163 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// SPL Token imports
+use anchor_spl::token_interface::Mint;
+
+// Local state, event and error imports
+use crate::compressed_nft::{
+    asset_id, CompressedTransfer, LeafData, BUBBLEGUM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID,
+};
+use crate::error::MarketplaceError;
+use crate::events::Listed;
+use crate::{CollectionConfig, CompressedListing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for listing a compressed NFT
+#[instruction(nonce: u64)] // Access the leaf nonce in account constraints
+pub struct ListCompressed<'info> {
+    #[account(mut)] // Mutable because seller pays for the listing and signs
+    pub seller: Signer<'info>, // Owner of the compressed NFT
+
+    /// CHECK: Current delegate of the leaf (the seller if none), checked by Bubblegum
+    pub leaf_delegate: UncheckedAccount<'info>,
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read marketplace configuration
+
+    // Account which stores the listing details, and owns the leaf while listed
+    #[account(
+        init, // Create new listing account
+        payer = seller, // Seller pays rent for listing account
+        space = 8 + CompressedListing::INIT_SPACE, // Account size: discriminator + listing data
+        seeds = [b"compressed", marketplace.key().as_ref(), merkle_tree.key().as_ref(), nonce.to_le_bytes().as_ref()], // PDA: "compressed" + marketplace + tree + nonce
+        bump // Canonical bump for deterministic listing address
+    )]
+    pub compressed_listing: Account<'info, CompressedListing>, // Store listing price and leaf data
+
+    pub collection_mint: InterfaceAccount<'info, Mint>, // Collection the NFT belongs to
+
+    // Admin-approved settings of the collection, which must exist for it to be listed
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), collection_mint.key().as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Account<'info, CollectionConfig>, // Collection's fee override, price range and status
+
+    /// CHECK: Merkle tree holding the leaf, checked by the compression program
+    #[account(mut)] // Mutable because the transfer replaces the leaf
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum tree config PDA, checked by seeds
+    #[account(
+        seeds = [merkle_tree.key().as_ref()], // PDA: merkle tree
+        bump, // Canonical bump for tree config
+        seeds::program = bubblegum_program.key(), // Verify this PDA belongs to Bubblegum
+    )]
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program, checked by address
+    #[account(address = BUBBLEGUM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program, checked by address
+    #[account(address = SPL_NOOP_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program, checked by address
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    // Program accounts
+    pub system_program: Program<'info, System>, // For account creation
+}
+
+impl<'info> ListCompressed<'info> {
+    /// Creates the listing and makes the listing PDA the owner of the leaf
+    ///
+    /// `metadata_args` are the NFT's Borsh-serialized Bubblegum MetadataArgs and
+    /// `proof` the proof nodes not covered by the tree's canopy
+    #[allow(clippy::too_many_arguments)]
+    pub fn list_compressed(
+        &mut self,
+        nonce: u64,
+        index: u32,
+        root: [u8; 32],
+        metadata_args: &[u8],
+        price: u64,
+        expires_at: Option<i64>,
+        proof: &[AccountInfo<'info>],
+        bumps: &ListCompressedBumps,
+    ) -> Result<()> {
+        let leaf = LeafData::from_metadata_args(metadata_args)?;
+
+        // Bubblegum verifies the hashes, so the collection read here is the leaf's own
+        let collection = leaf
+            .metadata
+            .collection
+            .as_ref()
+            .ok_or(MarketplaceError::InvalidCollection)?;
+        require_keys_eq!(
+            collection.key,
+            self.collection_mint.key(),
+            MarketplaceError::InvalidCollection
+        );
+        require!(collection.verified, MarketplaceError::UnverifedCollection);
+
+        // Collection must be approved, enabled and allow this price
+        self.collection_config.check_price(price)?;
+
+        // A listing that is already expired could never be bought
+        if let Some(expires_at) = expires_at {
+            require!(
+                expires_at > Clock::get()?.unix_timestamp,
+                MarketplaceError::InvalidExpiry
+            );
+        }
+
+        CompressedTransfer {
+            bubblegum_program: self.bubblegum_program.to_account_info(),
+            tree_authority: self.tree_authority.to_account_info(),
+            leaf_owner: self.seller.to_account_info(), // Seller owns the leaf
+            leaf_delegate: self.leaf_delegate.to_account_info(),
+            new_leaf_owner: self.compressed_listing.to_account_info(), // Listing PDA becomes the owner
+            merkle_tree: self.merkle_tree.to_account_info(),
+            log_wrapper: self.log_wrapper.to_account_info(),
+            compression_program: self.compression_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            proof,
+            signer_seeds: &[], // Seller signs the transaction directly
+        }
+        .transfer(root, leaf.data_hash, leaf.creator_hash, nonce, index)?;
+
+        self.compressed_listing.set_inner(CompressedListing { // Write listing data to account
+            maker: self.seller.key(), // Who is selling the NFT
+            asset_id: asset_id(&self.merkle_tree.key(), nonce), // Id indexers know the NFT by
+            merkle_tree: self.merkle_tree.key(), // Tree holding the leaf
+            nonce, // Leaf nonce
+            index, // Leaf index
+            data_hash: leaf.data_hash, // Needed to move the leaf again
+            creator_hash: leaf.creator_hash, // Needed to move the leaf again
+            collection_mint: self.collection_mint.key(), // Collection the NFT was checked against
+            price, // Sale price in lamports
+            expires_at, // None keeps the listing open until sold or delisted
+            bump: bumps.compressed_listing, // Store listing PDA bump
+        });
+
+        emit!(Listed {
+            marketplace: self.marketplace.key(),
+            mint: self.compressed_listing.asset_id,
+            seller: self.seller.key(),
+            price,
+            quantity: 1,
+            payment_mint: None,
+            expires_at,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
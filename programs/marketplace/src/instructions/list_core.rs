@@ -0,0 +1,127 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state, event and error imports
+use crate::core_asset::{transfer_core_asset, CoreAsset, CoreCollection, MPL_CORE_ID};
+use crate::error::MarketplaceError;
+use crate::events::Listed;
+use crate::{CollectionConfig, Custody, Listing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for listing a Metaplex Core asset
+pub struct ListCore<'info> {
+    #[account(mut)] // Mutable because seller pays for the listing and signs
+    pub seller: Signer<'info>, // Person listing their Core asset for sale
+
+    // Account which stores the listing details, and owns the asset while listed
+    #[account(
+        init, // Create new listing account
+        payer = seller, // Seller pays rent for listing account
+        space = 8 + Listing::INIT_SPACE, // Account size: discriminator + listing data
+        seeds = [marketplace.key().as_ref(), asset.key().as_ref()], // PDA: marketplace + asset
+        bump // Canonical bump for deterministic listing address
+    )]
+    pub listing: Account<'info, Listing>, // Store listing price and seller info
+
+    /// CHECK: Core asset, parsed and checked against the Core program in list_core
+    #[account(mut)] // Mutable because its owner changes to the listing PDA
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: Core collection, checked against the asset's update authority in list_core
+    pub collection: UncheckedAccount<'info>,
+
+    // Admin-approved settings of the collection, which must exist for it to be listed
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), collection.key().as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Account<'info, CollectionConfig>, // Collection's fee override, price range and status
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read marketplace config (fees, admin, etc.)
+
+    /// CHECK: Metaplex Core program, checked by address
+    #[account(address = MPL_CORE_ID)] // Must be the real Core program
+    pub core_program: UncheckedAccount<'info>,
+
+    // Program accounts
+    pub system_program: Program<'info, System>, // For account creation
+}
+
+impl<'info> ListCore<'info> {
+    /// Creates a SOL-priced listing for a Core asset in a Core collection
+    pub fn list_core(&mut self, price: u64, expires_at: Option<i64>, bumps: &ListCoreBumps) -> Result<()> {
+        let asset = CoreAsset::try_from_account(&self.asset)?;
+        require_keys_eq!(asset.owner, self.seller.key(), MarketplaceError::InvalidCoreAsset);
+
+        // Only the collection's authority can add an asset to it, so membership is verified
+        require_keys_eq!(*self.collection.owner, MPL_CORE_ID, MarketplaceError::InvalidCollection);
+        require!(
+            asset.collection == Some(self.collection.key()),
+            MarketplaceError::InvalidCollection
+        );
+
+        // Same rule as Token-2022 permanent delegates: these plugins could take the
+        // asset out of the listing PDA or freeze it there, and can only be added at creation
+        let collection = CoreCollection::try_from_account(&self.collection)?;
+        require!(
+            !asset.permanent_delegate && !collection.permanent_delegate,
+            MarketplaceError::PermanentDelegateAsset
+        );
+
+        // Collection must be approved, enabled and allow this price
+        self.collection_config.check_price(price)?;
+
+        // A listing that is already expired could never be bought
+        if let Some(expires_at) = expires_at {
+            require!(
+                expires_at > Clock::get()?.unix_timestamp,
+                MarketplaceError::InvalidExpiry
+            );
+        }
+
+        self.listing.set_inner(Listing { // Write listing data to account
+            maker: self.seller.key(), // Who is selling the asset
+            maker_mint: self.asset.key(), // Core assets have no mint, so store the asset
+            collection: self.collection.key(), // Core collection the asset belongs to
+            price, // Sale price in lamports
+            quantity: 1, // A single asset
+            payment_mint: None, // Core listings are priced in SOL
+            dutch_auction: None, // Fixed price listing
+            expires_at, // None keeps the listing open until sold or delisted
+            edition_number: None, // Core assets have no edition
+            has_metadata: false, // Core assets keep royalties in a plugin
+            custody: Custody::Escrow, // Listing PDA owns the asset
+            bump: bumps.listing, // Store listing PDA bump
+        });
+
+        emit!(Listed {
+            marketplace: self.marketplace.key(),
+            mint: self.asset.key(),
+            seller: self.seller.key(),
+            price,
+            quantity: 1,
+            payment_mint: None,
+            expires_at,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(()) // Return success
+    }
+
+    /// Escrows the asset by making the listing PDA its owner
+    pub fn deposit_asset(&mut self) -> Result<()> {
+        transfer_core_asset(
+            &self.core_program,
+            &self.asset,
+            &self.collection,
+            &self.seller, // Seller pays
+            &self.seller, // Seller owns the asset
+            &self.listing.to_account_info(), // Listing PDA becomes the owner
+            &[], // Seller signs the transaction directly
+        )
+    }
+}
\ No newline at end of file
@@ -0,0 +1,213 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// SPL Token and Metaplex imports
+use anchor_spl::{
+    metadata::{Metadata, MetadataAccount},
+    token_interface::{approve_checked, ApproveChecked, Mint, TokenAccount, TokenInterface},
+};
+
+// Local state, event and error imports
+use crate::edition::NftEdition;
+use crate::error::MarketplaceError;
+use crate::events::Listed;
+use crate::nft_transfer::{is_programmable, DelegatedNft};
+use crate::token_extensions::check_listable_mint;
+use crate::{CollectionConfig, Custody, Listing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for listing an NFT without escrow
+pub struct ListDelegated<'info> {
+    #[account(mut)] // Mutable because seller pays for the listing and signs
+    pub seller: Signer<'info>, // Person listing their NFT for sale
+
+    // Account which stores the listing details
+    #[account(
+        init, // Create new listing account
+        payer = seller, // Seller pays rent for listing account
+        space = 8 + Listing::INIT_SPACE, // Account size: discriminator + listing data
+        seeds = [marketplace.key().as_ref(), seller_mint.key().as_ref()], // PDA: marketplace + NFT mint
+        bump // Canonical bump for deterministic listing address
+    )]
+    pub listing: Account<'info, Listing>, // Store listing price and seller info
+
+    // NFT mint which is kept for sale in listing
+    pub seller_mint: InterfaceAccount<'info, Mint>, // The NFT token mint being sold
+
+    // Account which keeps holding the NFT while it is listed
+    #[account(
+        mut, // Mutable because the listing PDA is approved on it, and it may be frozen
+        associated_token::mint = seller_mint, // ATA for the specific NFT mint
+        associated_token::authority = seller // Seller owns this token account
+    )]
+    pub seller_ata: InterfaceAccount<'info, TokenAccount>, // Seller's NFT token account
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read marketplace config (fees, admin, etc.)
+
+    pub collection_mint: InterfaceAccount<'info, Mint>, // Collection this NFT belongs to
+
+    // Admin-approved settings of the collection, which must exist for it to be listed
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), collection_mint.key().as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Account<'info, CollectionConfig>, // Collection's fee override, price range and status
+
+    #[account(
+        seeds = [ // Metaplex metadata PDA structure
+            b"metadata", // Metaplex metadata seed
+            metadata_program.key().as_ref(), // Metaplex program ID
+            seller_mint.key().as_ref() // The NFT mint
+        ],
+        bump, // Canonical bump for metadata account
+        seeds::program = metadata_program.key(), // Verify this PDA belongs to Metaplex
+    )]
+    pub metadata: Account<'info, MetadataAccount>, // NFT metadata with collection info
+
+    /// CHECK: Master or print edition, checked by seeds and parsed in list_delegated
+    /// Also the mint's freeze authority when the listing freezes the NFT
+    #[account(
+        seeds = [ // Metaplex edition PDA structure
+            b"metadata", // Metaplex seed
+            metadata_program.key().as_ref(), // Metaplex program ID
+            seller_mint.key().as_ref(), // NFT mint
+            b"edition" // Edition-specific seed
+        ],
+        bump, // Canonical bump for edition account
+        seeds::program = metadata_program.key() // Verify PDA belongs to Metaplex
+    )]
+    pub edition: UncheckedAccount<'info>,
+
+    // Program accounts
+    pub metadata_program: Program<'info, Metadata>, // Metaplex metadata program
+    pub system_program: Program<'info, System>, // For account creation
+    pub token_program: Interface<'info, TokenInterface>, // For token operations
+}
+
+impl<'info> ListDelegated<'info> {
+    /// Creates a listing that leaves the NFT in the seller's wallet
+    /// With `freeze`, the seller can't move the NFT or revoke the listing until it ends
+    pub fn list_delegated(
+        &mut self,
+        price: u64,
+        payment_mint: Option<Pubkey>,
+        expires_at: Option<i64>,
+        freeze: bool,
+        bumps: &ListDelegatedBumps,
+    ) -> Result<()> {
+        // pNFTs are always frozen and use Token Metadata delegates, not token ones
+        require!(
+            !is_programmable(&self.metadata),
+            MarketplaceError::ProgrammableNotDelegable
+        );
+        require!(self.seller_ata.amount == 1, MarketplaceError::InsufficientQuantity);
+
+        // A permanent delegate could take the NFT from under the listing
+        check_listable_mint(&self.seller_mint)?;
+
+        // Same collection check as an escrowed listing
+        let collection = self
+            .metadata
+            .collection
+            .as_ref()
+            .ok_or(MarketplaceError::InvalidCollection)?;
+        require_keys_eq!(
+            collection.key,
+            self.collection_mint.key(),
+            MarketplaceError::InvalidCollection
+        );
+        require!(collection.verified, MarketplaceError::UnverifedCollection);
+
+        // Print editions only where the marketplace allows them
+        let edition_number = NftEdition::try_from_account(&self.edition)?
+            .listable_number(self.marketplace.allow_print_editions)?;
+
+        // Collection must be approved, enabled and allow this price
+        self.collection_config.check_price(price)?;
+
+        // Token-priced listings are only allowed in mints the admin accepts
+        if let Some(payment_mint) = payment_mint {
+            require!(
+                self.marketplace.payment_mints.contains(&payment_mint),
+                MarketplaceError::PaymentMintNotAllowed
+            );
+        }
+
+        // A listing that is already expired could never be bought
+        if let Some(expires_at) = expires_at {
+            require!(
+                expires_at > Clock::get()?.unix_timestamp,
+                MarketplaceError::InvalidExpiry
+            );
+        }
+
+        self.listing.set_inner(Listing { // Write listing data to account
+            maker: self.seller.key(), // Who is selling the NFT
+            maker_mint: self.seller_mint.key(), // Which NFT is being sold
+            collection: self.collection_mint.key(), // Collection the NFT was checked against
+            price, // Sale price in lamports or payment mint base units
+            quantity: 1, // A single NFT
+            payment_mint, // None for SOL, otherwise the SPL mint buyers pay in
+            dutch_auction: None, // Fixed price listing
+            expires_at, // None keeps the listing open until sold or delisted
+            custody: Custody::Delegate { frozen: freeze }, // Stays in the seller's token account
+            edition_number, // Print number, None for originals
+            has_metadata: true, // Metadata is required to list delegated
+            bump: bumps.listing, // Store listing PDA bump
+        });
+
+        emit!(Listed {
+            marketplace: self.marketplace.key(),
+            mint: self.seller_mint.key(),
+            seller: self.seller.key(),
+            price,
+            quantity: 1,
+            payment_mint,
+            expires_at,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(()) // Return success
+    }
+
+    /// Approves the listing PDA as delegate of the NFT, and freezes it if asked to
+    pub fn delegate_nft(&mut self) -> Result<()> {
+        let ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            ApproveChecked {
+                to: self.seller_ata.to_account_info(), // Seller's token account
+                mint: self.seller_mint.to_account_info(), // NFT mint
+                delegate: self.listing.to_account_info(), // Listing PDA may move the NFT
+                authority: self.seller.to_account_info(), // Seller approves the delegation
+            },
+        );
+        approve_checked(ctx, 1, self.seller_mint.decimals)?; // Delegate the single NFT
+
+        if self.listing.custody != (Custody::Delegate { frozen: true }) {
+            return Ok(());
+        }
+
+        // Create PDA signing seeds for listing authority
+        let seeds = &[
+            &self.marketplace.key().to_bytes()[..], // Marketplace address as bytes
+            &self.seller_mint.key().to_bytes()[..], // NFT mint address as bytes
+            &[self.listing.bump], // Listing bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array for PDA signing
+
+        DelegatedNft {
+            metadata_program: self.metadata_program.to_account_info(),
+            delegate: self.listing.to_account_info(),
+            token_account: self.seller_ata.to_account_info(),
+            edition: self.edition.to_account_info(),
+            mint: self.seller_mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            signer_seeds,
+        }
+        .freeze()
+    }
+}
\ No newline at end of file
@@ -0,0 +1,192 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// SPL Token and Metaplex imports
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{mpl_token_metadata::types::TokenStandard, Metadata, MetadataAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::Listed;
+use crate::nft_transfer::NftTransfer;
+use crate::token_extensions::check_listable_mint;
+use crate::{CollectionConfig, Custody, Listing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for listing a quantity of a token
+pub struct ListQuantity<'info> {
+    #[account(mut)] // Mutable because seller pays for accounts and signs
+    pub seller: Signer<'info>, // Person listing their tokens for sale
+
+    // Account which stores the listing details
+    #[account(
+        init, // Create new listing account
+        payer = seller, // Seller pays rent for listing account
+        space = 8 + Listing::INIT_SPACE, // Account size: discriminator + listing data
+        seeds = [marketplace.key().as_ref(), seller_mint.key().as_ref()], // PDA: marketplace + mint
+        bump // Canonical bump for deterministic listing address
+    )]
+    pub listing: Account<'info, Listing>, // Store listing price, quantity and seller info
+
+    // Fungible or semi-fungible mint, with any number of decimals (checked in list_quantity)
+    pub seller_mint: InterfaceAccount<'info, Mint>, // The token mint being sold
+
+    // Account which is storing the tokens
+    #[account(
+        mut, // Mutable because the listed quantity leaves this account
+        associated_token::mint = seller_mint, // ATA for the token mint
+        associated_token::authority = seller // Seller owns this token account
+    )]
+    pub seller_ata: InterfaceAccount<'info, TokenAccount>, // Seller's token account
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read marketplace config (fees, admin, etc.)
+
+    // Account where the tokens are kept in hold
+    #[account(
+        init, // Create vault to hold the tokens during listing
+        payer = seller, // Seller pays for vault creation
+        associated_token::mint = seller_mint, // ATA for the token mint
+        associated_token::authority = listing // Listing PDA controls the vault
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>, // Escrow account holding the tokens
+
+    pub collection_mint: InterfaceAccount<'info, Mint>, // Collection this token belongs to
+
+    // Admin-approved settings of the collection, which must exist for it to be listed
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), collection_mint.key().as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Account<'info, CollectionConfig>, // Collection's fee override, price range and status
+
+    // Same verified collection check as an NFT listing, without a master edition
+    #[account(
+        seeds = [ // Metaplex metadata PDA structure
+            b"metadata", // Metaplex metadata seed
+            metadata_program.key().as_ref(), // Metaplex program ID
+            seller_mint.key().as_ref() // The token mint
+        ],
+        bump, // Canonical bump for metadata account
+        seeds::program = metadata_program.key(), // Verify this PDA belongs to Metaplex
+    )]
+    pub metadata: Account<'info, MetadataAccount>, // Token metadata with collection info
+
+    // Program accounts
+    pub metadata_program: Program<'info, Metadata>, // Metaplex metadata program
+    pub system_program: Program<'info, System>, // For account creation
+    pub token_program: Interface<'info, TokenInterface>, // For token operations
+    pub associated_token_program: Program<'info, AssociatedToken>, // For ATA creation
+}
+
+impl<'info> ListQuantity<'info> {
+    /// Creates a listing for `quantity` base units at `price` per whole token
+    /// Buyers can take part of it; the listing closes once everything is sold
+    pub fn list_quantity(
+        &mut self,
+        price: u64,
+        quantity: u64,
+        payment_mint: Option<Pubkey>,
+        expires_at: Option<i64>,
+        bumps: &ListQuantityBumps,
+    ) -> Result<()> {
+        require!(quantity > 0, MarketplaceError::InvalidQuantity);
+
+        // Token-2022 extensions that would let the tokens be clawed back or never move
+        check_listable_mint(&self.seller_mint)?;
+
+        // Only fungible and semi-fungible tokens; NFTs and print editions go
+        // through listing, which checks their edition
+        require!(
+            matches!(
+                self.metadata.token_standard,
+                Some(TokenStandard::Fungible | TokenStandard::FungibleAsset)
+            ),
+            MarketplaceError::NotFungibleToken
+        );
+
+        // Token must be a verified member of the collection, as for NFT listings
+        let collection = self
+            .metadata
+            .collection
+            .as_ref()
+            .ok_or(MarketplaceError::InvalidCollection)?;
+        require_keys_eq!(
+            collection.key,
+            self.collection_mint.key(),
+            MarketplaceError::InvalidCollection
+        );
+        require!(collection.verified, MarketplaceError::UnverifedCollection);
+
+        // Collection must be approved, enabled and allow this price per token
+        self.collection_config.check_price(price)?;
+
+        // Token-priced listings are only allowed in mints the admin accepts
+        if let Some(payment_mint) = payment_mint {
+            require!(
+                self.marketplace.payment_mints.contains(&payment_mint),
+                MarketplaceError::PaymentMintNotAllowed
+            );
+        }
+
+        // A listing that is already expired could never be bought
+        if let Some(expires_at) = expires_at {
+            require!(
+                expires_at > Clock::get()?.unix_timestamp,
+                MarketplaceError::InvalidExpiry
+            );
+        }
+
+        self.listing.set_inner(Listing { // Write listing data to account
+            maker: self.seller.key(), // Who is selling the tokens
+            maker_mint: self.seller_mint.key(), // Which token is being sold
+            collection: self.collection_mint.key(), // Collection the token was checked against
+            price, // Price per whole token in lamports or payment mint base units
+            quantity, // Base units for sale
+            payment_mint, // None for SOL, otherwise the SPL mint buyers pay in
+            dutch_auction: None, // Fixed price listing
+            expires_at, // None keeps the listing open until sold out or delisted
+            edition_number: None, // Fungible and semi-fungible tokens have no edition
+            has_metadata: true, // Metadata is required to list a quantity
+            custody: Custody::Escrow, // Held by the listing PDA
+            bump: bumps.listing, // Store listing PDA bump
+        });
+
+        emit!(Listed {
+            marketplace: self.marketplace.key(),
+            mint: self.seller_mint.key(),
+            seller: self.seller.key(),
+            price,
+            quantity,
+            payment_mint,
+            expires_at,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(()) // Return success
+    }
+
+    /// Moves the listed quantity from the seller into the listing vault
+    /// `extra_accounts` are the transfer hook accounts of a Token-2022 mint, if it has a hook
+    pub fn deposit_tokens(&mut self, extra_accounts: &[AccountInfo<'info>]) -> Result<()> {
+        NftTransfer {
+            from: self.seller_ata.to_account_info(), // Source: seller's token account
+            from_owner: self.seller.to_account_info(), // Seller owns the source account
+            to: self.vault.to_account_info(), // Destination: vault token account
+            to_owner: self.listing.to_account_info(), // Listing PDA owns the vault
+            authority: self.seller.to_account_info(), // Seller authorizes the transfer
+            payer: self.seller.to_account_info(), // Nothing to create for fungible tokens
+            mint: self.seller_mint.to_account_info(), // Which token mint to transfer
+            token_program: self.token_program.to_account_info(),
+            signer_seeds: &[], // Seller signs the transaction directly
+            extra_accounts,
+        }
+        .transfer(self.listing.quantity, self.seller_mint.decimals, None) // Escrow the listed quantity
+    }
+}
\ No newline at end of file
@@ -0,0 +1,137 @@
+// Core Anchor framework imports
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+// SPL Token program imports
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::OfferMade;
+use crate::settlement::check_payment_accounts;
+use crate::{Marketplace, Offer};
+
+#[derive(Accounts)] // Define accounts needed for making an offer
+pub struct MakeOffer<'info> {
+    #[account(mut)] // Mutable because buyer funds the escrow and pays rent
+    pub buyer: Signer<'info>, // Person bidding on the NFT
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read accepted payment mints
+
+    pub mint: InterfaceAccount<'info, Mint>, // NFT mint being bid on (listed or not)
+
+    // Account which stores the offer details and escrows SOL bids
+    #[account(
+        init, // Create new offer account
+        payer = buyer, // Buyer pays rent for offer account
+        space = 8 + Offer::INIT_SPACE, // Account size: discriminator + offer data
+        seeds = [b"offer", marketplace.key().as_ref(), mint.key().as_ref(), buyer.key().as_ref()], // PDA: "offer" + marketplace + NFT mint + buyer
+        bump // Canonical bump for deterministic offer address
+    )]
+    pub offer: Account<'info, Offer>, // Store bid price and buyer info
+
+    // SPL payment accounts, only passed when the offer is in a token
+    #[account(
+        mint::token_program = payment_token_program // Mint must belong to the payment token program
+    )]
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>, // Token the offer is in
+
+    // Buyer's token account funding the bid
+    #[account(
+        mut, // Mutable because tokens leave this account
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = buyer, // Buyer owns this token account
+        associated_token::token_program = payment_token_program // Payment mint's token program
+    )]
+    pub buyer_payment_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Buyer's payment tokens
+
+    // Escrow holding token bids until accepted or cancelled
+    #[account(
+        init, // Create escrow for the bid
+        payer = buyer, // Buyer pays for escrow creation
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = offer, // Offer PDA controls the escrow
+        associated_token::token_program = payment_token_program // Payment mint's token program
+    )]
+    pub escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Escrowed payment tokens
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>, // Token program of the payment mint
+
+    // Program accounts
+    pub system_program: Program<'info, System>, // For account creation and SOL transfers
+    pub associated_token_program: Program<'info, AssociatedToken>, // For ATA creation
+}
+
+impl<'info> MakeOffer<'info> {
+    /// Creates the offer and escrows the bid
+    /// SOL bids sit in the offer account itself, token bids in the escrow ATA
+    pub fn make(&mut self, price: u64, payment_mint: Option<Pubkey>, bumps: &MakeOfferBumps) -> Result<()> {
+        // Token bids only in mints the admin accepts, same as listings
+        if let Some(payment_mint) = payment_mint {
+            require!(
+                self.marketplace.payment_mints.contains(&payment_mint),
+                MarketplaceError::PaymentMintNotAllowed
+            );
+        }
+        check_payment_accounts(
+            payment_mint,
+            self.payment_mint.as_deref(),
+            self.buyer_payment_ata.is_some()
+                && self.escrow.is_some()
+                && self.payment_token_program.is_some(),
+        )?;
+
+        self.offer.set_inner(Offer { // Write offer data to account
+            buyer: self.buyer.key(), // Who is bidding
+            mint: self.mint.key(), // Which NFT is being bid on
+            price, // Bid in lamports or payment mint base units
+            payment_mint, // None for SOL, otherwise the SPL mint of the bid
+            bump: bumps.offer, // Store offer PDA bump
+        });
+
+        match (&self.payment_mint, &self.buyer_payment_ata, &self.escrow, &self.payment_token_program) {
+            (Some(payment_mint), Some(buyer_payment_ata), Some(escrow), Some(payment_token_program)) => {
+                let ctx = CpiContext::new(
+                    payment_token_program.to_account_info(),
+                    TransferChecked {
+                        from: buyer_payment_ata.to_account_info(), // Source: buyer's payment ATA
+                        to: escrow.to_account_info(), // Destination: offer escrow
+                        authority: self.buyer.to_account_info(), // Buyer authorizes the transfer
+                        mint: payment_mint.to_account_info(), // Payment mint
+                    },
+                );
+                transfer_checked(ctx, price, payment_mint.decimals)?;
+            }
+            _ => {
+                let ctx = CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.buyer.to_account_info(), // Source: buyer's wallet
+                        to: self.offer.to_account_info(), // Destination: offer PDA
+                    },
+                );
+                transfer(ctx, price)?;
+            }
+        }
+
+        emit!(OfferMade {
+            marketplace: self.marketplace.key(),
+            mint: self.mint.key(),
+            buyer: self.buyer.key(),
+            price,
+            payment_mint,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
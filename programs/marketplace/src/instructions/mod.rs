@@ -1,9 +1,79 @@
+pub mod accept_admin;
+pub mod accept_collection_offer;
+pub mod accept_offer;
+pub mod add_collection;
+pub mod add_payment_mint;
+pub mod cancel_admin_transfer;
+pub mod cancel_collection_offer;
+pub mod cancel_offer;
+pub mod close_dead_listing;
+pub mod crank_expired;
+pub mod crank_expired_compressed;
+pub mod crank_expired_core;
+pub mod create_auction;
 pub mod delist;
+pub mod delist_bundle;
+pub mod delist_compressed;
+pub mod delist_core;
 pub mod initialize;
 pub mod list;
+pub mod list_bundle;
+pub mod list_compressed;
+pub mod list_core;
+pub mod list_delegated;
+pub mod list_quantity;
+pub mod make_collection_offer;
+pub mod make_offer;
+pub mod place_bid;
 pub mod purchase;
+pub mod purchase_bundle;
+pub mod purchase_compressed;
+pub mod purchase_core;
+pub mod remove_collection;
+pub mod remove_payment_mint;
+pub mod settle_auction;
+pub mod update_collection;
+pub mod update_listing;
+pub mod update_marketplace;
+pub mod withdraw_treasury;
+pub mod withdraw_treasury_tokens;
 
+pub use accept_admin::*;
+pub use accept_collection_offer::*;
+pub use accept_offer::*;
+pub use add_collection::*;
+pub use add_payment_mint::*;
+pub use cancel_admin_transfer::*;
+pub use cancel_collection_offer::*;
+pub use cancel_offer::*;
+pub use close_dead_listing::*;
+pub use crank_expired::*;
+pub use crank_expired_compressed::*;
+pub use crank_expired_core::*;
+pub use create_auction::*;
 pub use delist::*;
+pub use delist_bundle::*;
+pub use delist_compressed::*;
+pub use delist_core::*;
 pub use initialize::*;
 pub use list::*;
-pub use purchase::*;
\ No newline at end of file
+pub use list_bundle::*;
+pub use list_compressed::*;
+pub use list_core::*;
+pub use list_delegated::*;
+pub use list_quantity::*;
+pub use make_collection_offer::*;
+pub use make_offer::*;
+pub use place_bid::*;
+pub use purchase::*;
+pub use purchase_bundle::*;
+pub use purchase_compressed::*;
+pub use purchase_core::*;
+pub use remove_collection::*;
+pub use remove_payment_mint::*;
+pub use settle_auction::*;
+pub use update_collection::*;
+pub use update_listing::*;
+pub use update_marketplace::*;
+pub use withdraw_treasury::*;
+pub use withdraw_treasury_tokens::*;
\ No newline at end of file
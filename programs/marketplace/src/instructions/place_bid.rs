@@ -0,0 +1,103 @@
+// Core Anchor framework imports
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+// SPL Token program imports
+use anchor_spl::token_interface::Mint;
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::BidPlaced;
+use crate::settlement::Payer;
+use crate::{Auction, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for bidding on an auction
+pub struct PlaceBid<'info> {
+    #[account(mut)] // Mutable because bidder escrows their bid
+    pub bidder: Signer<'info>, // Person placing the bid
+
+    // Previous high bidder, only passed when there is one to refund
+    #[account(mut)] // Mutable because they receive their bid back
+    pub previous_bidder: Option<SystemAccount<'info>>, // Outbid bidder
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace the auction runs on
+
+    pub mint: InterfaceAccount<'info, Mint>, // NFT mint being auctioned
+
+    // Account which stores the auction details and escrows the high bid
+    #[account(
+        mut, // Mutable because the high bid changes
+        seeds = [b"auction", marketplace.key().as_ref(), mint.key().as_ref()], // PDA: "auction" + marketplace + NFT mint
+        bump = auction.bump // Use stored bump to verify PDA
+    )]
+    pub auction: Account<'info, Auction>, // The auction being bid on
+
+    // Program accounts
+    pub system_program: Program<'info, System>, // For SOL transfers
+}
+
+impl<'info> PlaceBid<'info> {
+    /// Escrows a new high bid, refunds the previous one and extends the auction if needed
+    pub fn bid(&mut self, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= self.auction.start_time, MarketplaceError::AuctionNotStarted);
+        require!(now < self.auction.end_time, MarketplaceError::AuctionEnded);
+
+        // Must meet the reserve and beat the current high bid by the minimum increment
+        require!(amount >= self.auction.min_next_bid()?, MarketplaceError::BidTooLow);
+
+        // Escrow the new bid in the auction account
+        let ctx = CpiContext::new(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.bidder.to_account_info(), // Source: bidder's wallet
+                to: self.auction.to_account_info(), // Destination: auction PDA
+            },
+        );
+        transfer(ctx, amount)?;
+
+        // Refund whoever was outbid straight out of escrow
+        if let Some(highest_bidder) = self.auction.highest_bidder {
+            let previous_bidder = self
+                .previous_bidder
+                .as_ref()
+                .ok_or(MarketplaceError::InvalidBidder)?;
+            require_keys_eq!(previous_bidder.key(), highest_bidder, MarketplaceError::InvalidBidder);
+
+            Payer::Escrow {
+                from: self.auction.to_account_info(),
+            }
+            .pay(previous_bidder.to_account_info(), self.auction.highest_bid)?;
+        }
+
+        self.auction.highest_bid = amount; // Record the new high bid
+        self.auction.highest_bidder = Some(self.bidder.key()); // Record the new high bidder
+
+        // Anti-sniping: a late bid pushes the end back by the extension window,
+        // but never past max_end_time, so bidders can't keep the auction open forever
+        let extended_end = now
+            .checked_add(self.auction.extension_window)
+            .ok_or(MarketplaceError::MathOverflow)?
+            .min(self.auction.max_end_time);
+        if extended_end > self.auction.end_time {
+            self.auction.end_time = extended_end;
+        }
+
+        emit!(BidPlaced {
+            marketplace: self.marketplace.key(),
+            mint: self.mint.key(),
+            bidder: self.bidder.key(),
+            amount,
+            end_time: self.auction.end_time,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
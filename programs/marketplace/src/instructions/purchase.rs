@@ -1,21 +1,22 @@
 // Core Anchor framework imports
 use anchor_lang::{
     prelude::*,
-    system_program::{transfer, Transfer},
+    solana_program::{native_token::LAMPORTS_PER_SOL, program_option::COption, sysvar},
 };
 
 // SPL Token program imports
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token_2022::transfer_checked,
-    token_interface::{
-        close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
-    },
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    metadata::{Metadata, MetadataAccount},
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface},
 };
 
-// Local state and error imports
-use crate::{Listing, Marketplace};
+// Local state, event and error imports
+use crate::{CollectionConfig, Custody, Listing, Marketplace};
 use crate::error::MarketplaceError;
+use crate::events::Sold;
+use crate::nft_transfer::{close_vault, DelegatedNft, NftTransfer, ProgrammableAccounts};
+use crate::settlement::{check_payment_accounts, settle_sale_with_royalties, Payer, Royalties};
 
 #[derive(Accounts)]
 pub struct Purchase<'info> {
@@ -23,7 +24,7 @@ pub struct Purchase<'info> {
     pub buyer: Signer<'info>, // Person purchasing the NFT from the marketplace
 
     #[account(
-        seeds = [b"marketplace", marketplace.name.as_str().as_bytes()], // PDA: "marketplace" + name
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
         bump // Verify this is the correct marketplace PDA
     )]
     pub marketplace: Account<'info, Marketplace>, // Marketplace configuration (fees, admin, etc.)
@@ -35,11 +36,19 @@ pub struct Purchase<'info> {
 
     // Account which holds the details about the listing
     #[account(
+        mut, // Mutable because the quantity drops, and the listing closes once it is sold out
         seeds = [marketplace.key().as_ref(), seller_mint.key().as_ref()], // PDA: marketplace + NFT mint
         bump = listing.bump, // Use stored bump to verify listing PDA
         constraint = listing.maker == seller.key(), // Verify this listing belongs to the seller for security
     )]
-    pub listing: Account<'info, Listing>, // Listing data (price, seller, etc.)
+    pub listing: Account<'info, Listing>, // Listing data (price, seller, etc.), closed when sold out
+
+    // Settings of the listing's collection, which set the fee and can pause trading
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), listing.collection.as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>, // Collection's fee override, price range and status
 
     // Buyer's token account for receiving the NFT
     #[account(
@@ -58,77 +67,271 @@ pub struct Purchase<'info> {
     )]
     pub treasury: SystemAccount<'info>, // Treasury account for marketplace fees
 
-    // Vault account holding the NFT during the listing period
+    // Vault account holding the NFT during the listing period, for escrowed listings
     #[account(
         mut, // Mutable because we're closing this vault after transfer
         associated_token::mint = seller_mint, // ATA for the NFT mint
         associated_token::authority = listing // Listing PDA controls the vault
     )]
-    pub vault: InterfaceAccount<'info, TokenAccount>, // Escrow vault holding the NFT
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>, // Escrow vault holding the NFT
+
+    // Seller's token account still holding the NFT, for delegated listings
+    #[account(
+        mut, // Mutable because the NFT leaves it, and it may be thawed first
+        associated_token::mint = seller_mint, // ATA for the NFT mint
+        associated_token::authority = seller // Seller owns this token account
+    )]
+    pub seller_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Seller's NFT token account
+
+    // Marketplace reward mint, minted from on every SOL purchase
+    #[account(
+        mut, // Mutable because supply grows when rewards are minted
+        seeds = [b"reward", marketplace.key().as_ref()], // PDA: "reward" + marketplace
+        bump = marketplace.rewards_bump, // Use stored bump to verify reward mint PDA
+        mint::token_program = reward_token_program // Reward mint may not share the NFT's token program
+    )]
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>, // Loyalty token mint
+
+    // Buyer's token account for receiving reward tokens, only created once rewards are minted
+    /// CHECK: Buyer's reward ATA, checked by seeds and created in mint_rewards
+    #[account(
+        mut, // Mutable because it may be created and receives reward tokens
+        seeds = [ // Associated token account PDA structure
+            buyer.key().as_ref(), // Buyer owns this token account
+            reward_token_program.key().as_ref(), // Reward mint's token program
+            reward_mint.key().as_ref() // ATA for the reward mint
+        ],
+        bump, // Canonical bump for the ATA
+        seeds::program = associated_token_program.key() // Verify this PDA belongs to the ATA program
+    )]
+    pub buyer_reward_ata: UncheckedAccount<'info>, // Buyer's reward tokens
+
+    // Seller's token account for receiving reward tokens, only created once rewards are minted
+    /// CHECK: Seller's reward ATA, checked by seeds and created in mint_rewards
+    #[account(
+        mut, // Mutable because it may be created and receives reward tokens
+        seeds = [ // Associated token account PDA structure
+            seller.key().as_ref(), // Seller owns this token account
+            reward_token_program.key().as_ref(), // Reward mint's token program
+            reward_mint.key().as_ref() // ATA for the reward mint
+        ],
+        bump, // Canonical bump for the ATA
+        seeds::program = associated_token_program.key() // Verify this PDA belongs to the ATA program
+    )]
+    pub seller_reward_ata: UncheckedAccount<'info>, // Seller's reward tokens
+
+    // Left out for Token-2022 NFTs without Metaplex metadata, which pay no royalties
+    #[account(
+        mut, // Mutable because Token Metadata writes to it when moving a pNFT
+        seeds = [ // Metaplex metadata PDA structure
+            b"metadata", // Metaplex metadata seed
+            metadata_program.key().as_ref(), // Metaplex program ID
+            seller_mint.key().as_ref() // The NFT mint
+        ],
+        bump, // Canonical bump for metadata account
+        seeds::program = metadata_program.key(), // Verify this PDA belongs to Metaplex
+    )]
+    pub metadata: Option<Box<Account<'info, MetadataAccount>>>, // NFT metadata with royalty and creator info
+
+    // SPL payment accounts, only passed when the listing is priced in a token
+    #[account(
+        mint::token_program = payment_token_program // Mint must belong to the payment token program
+    )]
+    pub payment_mint: Option<Box<InterfaceAccount<'info, Mint>>>, // Token the listing is priced in
+
+    // Buyer's token account paying for the NFT
+    #[account(
+        mut, // Mutable because tokens leave this account
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = buyer, // Buyer owns this token account
+        associated_token::token_program = payment_token_program // Payment mint's token program
+    )]
+    pub buyer_payment_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Buyer's payment tokens
+
+    // Seller's token account receiving the proceeds
+    #[account(
+        init_if_needed, // Create seller's payment ATA if it doesn't exist
+        payer = buyer, // Buyer pays so the seller doesn't need to sign
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = seller, // Seller owns this token account
+        associated_token::token_program = payment_token_program // Payment mint's token program
+    )]
+    pub seller_payment_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Seller's payment tokens
+
+    // Treasury's token account collecting marketplace fees
+    #[account(
+        init_if_needed, // Create treasury's payment ATA if it doesn't exist
+        payer = buyer, // Buyer pays for account creation
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = treasury, // Treasury PDA owns this token account
+        associated_token::token_program = payment_token_program // Payment mint's token program
+    )]
+    pub treasury_payment_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Treasury's payment tokens
+
+    pub payment_token_program: Option<Interface<'info, TokenInterface>>, // Token program of the payment mint
+
+    // Programmable NFT accounts, only passed when the metadata token standard is programmable
+    // Also needed to thaw the NFT of a frozen delegated listing
+    /// CHECK: Master edition of the NFT, validated by Token Metadata
+    pub edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the vault, validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata updates or closes it
+    pub token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the buyer's token account, created and validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata creates or updates it
+    pub destination_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the pNFT, validated by Token Metadata
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token Auth Rules program, validated by Token Metadata
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = sysvar::instructions::ID)] // Must be the real instructions sysvar
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
 
     // Program accounts
+    pub metadata_program: Program<'info, Metadata>, // Metaplex metadata program
     pub system_program: Program<'info, System>, // For SOL transfers and account operations
     pub associated_token_program: Program<'info, AssociatedToken>, // For ATA creation
     pub token_program: Interface<'info, TokenInterface>, // For token operations
+    pub reward_token_program: Interface<'info, TokenInterface>, // For minting rewards
 }
 
 impl<'info> Purchase<'info> {
-    /// Transfers SOL payment from buyer to seller and marketplace treasury
-    /// Calculates marketplace fee and ensures proper payment distribution
-    pub fn transfer_amounts(&mut self) -> Result<()> {
-        // Validate fee percentage is reasonable (max 50% to prevent abuse)
+    /// Transfers payment from buyer to seller, creators and marketplace treasury
+    /// Pays in SOL, or in the listing's payment mint when it is priced in a token
+    ///
+    /// `creators` must hold one writable account per verified creator in the
+    /// metadata `creators` array, in the same order (their token accounts for
+    /// token-priced listings)
+    ///
+    /// Buys `quantity` base units of the listed token and returns the total paid
+    pub fn transfer_amounts(
+        &mut self,
+        expected_price: u64,
+        expected_seller: Pubkey,
+        quantity: u64,
+        creators: &[AccountInfo<'info>],
+    ) -> Result<u64> {
+        // Expired listings can only be cranked back to the seller
         require!(
-            self.marketplace.fee <= 5000, // Max 50% fee (5000 basis points)
-            MarketplaceError::FeeTooHigh
+            !self.listing.is_expired(Clock::get()?.unix_timestamp),
+            MarketplaceError::ListingExpired
         );
 
-        // Calculate marketplace fee using safe arithmetic to prevent overflow
-        let fees = (self.marketplace.fee as u64)
-            .checked_mul(self.listing.price)
-            .ok_or(MarketplaceError::MathOverflow)? // Handle multiplication overflow
-            .checked_div(10000) // Divide by 10000 for basis points (1 basis point = 0.01%)
-            .ok_or(MarketplaceError::MathOverflow)?; // Handle division overflow
-
-        // Calculate amount seller receives after marketplace fee
-        let seller_amount = self.listing.price
-            .checked_sub(fees)
-            .ok_or(MarketplaceError::MathOverflow)?; // Handle subtraction overflow
-
-        // Transfer payment to seller (listing price minus marketplace fee)
-        let seller_transfer_ctx = CpiContext::new(
-            self.system_program.to_account_info(),
-            Transfer {
+        // Make sure the payment accounts match how the listing is priced
+        check_payment_accounts(
+            self.listing.payment_mint,
+            self.payment_mint.as_deref(),
+            self.buyer_payment_ata.is_some()
+                && self.seller_payment_ata.is_some()
+                && self.treasury_payment_ata.is_some()
+                && self.payment_token_program.is_some(),
+        )?;
+
+        // Buyer pays from their wallet, or from their payment ATA for token listings
+        let payer = match (&self.payment_mint, &self.buyer_payment_ata, &self.payment_token_program) {
+            (Some(payment_mint), Some(buyer_payment_ata), Some(payment_token_program)) => Payer::Token {
+                from: buyer_payment_ata.to_account_info(),
+                authority: self.buyer.to_account_info(),
+                mint: payment_mint.to_account_info(),
+                decimals: payment_mint.decimals,
+                token_program: payment_token_program.to_account_info(),
+                signer_seeds: &[], // Buyer signs the transaction directly
+            },
+            _ => Payer::Wallet {
                 from: self.buyer.to_account_info(),
-                to: self.seller.to_account_info(),
+                system_program: self.system_program.to_account_info(),
             },
+        };
+
+        // Seller and treasury receive into wallets, or token accounts for token listings
+        let seller = match &self.seller_payment_ata {
+            Some(seller_payment_ata) => seller_payment_ata.to_account_info(),
+            None => self.seller.to_account_info(),
+        };
+        let treasury = match &self.treasury_payment_ata {
+            Some(treasury_payment_ata) => treasury_payment_ata.to_account_info(),
+            None => self.treasury.to_account_info(),
+        };
+
+        // A relist by someone else must not go through on the buyer's signature
+        require_keys_eq!(
+            self.listing.maker,
+            expected_seller,
+            MarketplaceError::SellerMismatch
         );
-        transfer(seller_transfer_ctx, seller_amount)?;
 
-        // Transfer marketplace fee to treasury
-        let treasury_transfer_ctx = CpiContext::new(
-            self.system_program.to_account_info(),
-            Transfer {
-                from: self.buyer.to_account_info(),
-                to: self.treasury.to_account_info(),
-            },
+        // Fixed prices must match what the buyer saw; Dutch prices keep falling
+        // after the buyer looks, so for them the expected price is a cap
+        let price = self.sale_price()?;
+        match self.listing.dutch_auction {
+            Some(_) => require!(price <= expected_price, MarketplaceError::PriceAboveMax),
+            None => require!(price == expected_price, MarketplaceError::PriceMismatch),
+        }
+
+        // Partial fills leave the rest listed
+        require!(quantity > 0, MarketplaceError::InvalidQuantity);
+        require!(
+            quantity <= self.listing.quantity,
+            MarketplaceError::InsufficientQuantity
         );
-        transfer(treasury_transfer_ctx, fees)?;
 
-        Ok(())
-    }
+        // Price is per whole token, so scale it to the quantity bought
+        let total = Listing::cost_of(price, quantity, self.seller_mint.decimals)?;
 
-    /// Transfers the NFT from the marketplace vault to the buyer's token account
-    /// Uses the listing PDA as authority to authorize the transfer
-    pub fn transfer_nft(&mut self) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info();
+        // Paused or removed collections can't be bought, only delisted
+        self.collection_config.check_enabled()?;
 
-        let cpi_accounts = TransferChecked {
-            from: self.vault.to_account_info(), // Source: marketplace vault
-            to: self.buyer_ata.to_account_info(), // Destination: buyer's token account
-            authority: self.listing.to_account_info(), // Listing PDA has authority over vault
-            mint: self.seller_mint.to_account_info(), // NFT mint being transferred
-        };
+        // Leaving out the metadata of a Metaplex NFT would skip its royalties
+        if self.listing.has_metadata {
+            require!(self.metadata.is_some(), MarketplaceError::MissingMetadataAccount);
+        }
+
+        let settlement = settle_sale_with_royalties(
+            &payer,
+            total,
+            self.collection_config.fee_bps(self.marketplace.fee),
+            &Royalties::from_optional_metadata(self.metadata.as_deref()),
+            creators,
+            seller,
+            treasury,
+        )?;
+
+        emit!(Sold {
+            marketplace: self.marketplace.key(),
+            mint: self.seller_mint.key(),
+            buyer: self.buyer.key(),
+            seller: self.seller.key(),
+            price: total,
+            quantity,
+            marketplace_fee: settlement.marketplace_fee,
+            royalty: settlement.royalty,
+            seller_proceeds: settlement.seller_proceeds,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(total)
+    }
+
+    /// Price per whole token the buyer pays right now
+    /// Fixed price listings use Listing.price, Dutch listings follow their schedule
+    pub fn sale_price(&self) -> Result<u64> {
+        match &self.listing.dutch_auction {
+            Some(dutch_auction) => dutch_auction.price_at(Clock::get()?.unix_timestamp),
+            None => Ok(self.listing.price),
+        }
+    }
 
+    /// Transfers the bought quantity from the marketplace vault to the buyer's token account
+    /// Uses the listing PDA as authority, through Token Metadata for programmable NFTs
+    ///
+    /// `extra_accounts` are the transfer hook accounts of a Token-2022 mint, if it has a hook
+    pub fn transfer_nft(&mut self, quantity: u64, extra_accounts: &[AccountInfo<'info>]) -> Result<()> {
         // Create PDA signing seeds for listing authority
         let seeds = &[
             &self.marketplace.key().to_bytes()[..], // Marketplace address as bytes
@@ -137,15 +340,118 @@ impl<'info> Purchase<'info> {
         ];
         let signer_seeds = &[&seeds[..]]; // Nested array structure for PDA signing
 
-        let ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+        // Delegated listings never moved the NFT out of the seller's wallet
+        if let Custody::Delegate { frozen } = self.listing.custody {
+            self.transfer_delegated(frozen, quantity, signer_seeds, extra_accounts)?;
+            self.listing.quantity -= quantity; // Checked against the listing in transfer_amounts
+            return Ok(());
+        }
+
+        let vault = self
+            .vault
+            .as_ref()
+            .ok_or(MarketplaceError::MissingNftAccount)?;
+
+        let programmable = ProgrammableAccounts::from_optional(
+            &self.metadata_program,
+            self.metadata.as_deref(),
+            self.edition.as_ref(),
+            self.token_record.as_ref(),
+            self.destination_token_record.as_ref(),
+            self.authorization_rules.as_ref(),
+            self.authorization_rules_program.as_ref(),
+            self.sysvar_instructions.as_ref(),
+            &self.system_program,
+            &self.associated_token_program,
+        );
 
-        // Transfer 1 NFT (amount=1, decimals=0 for NFTs)
-        transfer_checked(ctx, 1, 0)
+        NftTransfer {
+            from: vault.to_account_info(), // Source: marketplace vault
+            from_owner: self.listing.to_account_info(), // Listing PDA owns the vault
+            to: self.buyer_ata.to_account_info(), // Destination: buyer's token account
+            to_owner: self.buyer.to_account_info(), // Buyer owns the destination account
+            authority: self.listing.to_account_info(), // Listing PDA has authority over vault
+            payer: self.buyer.to_account_info(), // Buyer pays for their token record
+            mint: self.seller_mint.to_account_info(), // NFT mint being transferred
+            token_program: self.token_program.to_account_info(),
+            signer_seeds,
+            extra_accounts,
+        }
+        // Transfer the bought quantity (1 with 0 decimals for an NFT)
+        .transfer(quantity, self.seller_mint.decimals, programmable.as_ref())?;
+
+        // Whatever is left stays listed at the same price
+        self.listing.quantity -= quantity; // Checked against the listing in transfer_amounts
+        Ok(())
+    }
+
+    /// Moves the NFT out of the seller's token account as the listing's delegate
+    /// Thaws it first when the listing froze it
+    fn transfer_delegated(
+        &self,
+        frozen: bool,
+        quantity: u64,
+        signer_seeds: &[&[&[u8]]],
+        extra_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let seller_ata = self
+            .seller_ata
+            .as_ref()
+            .ok_or(MarketplaceError::MissingNftAccount)?;
+
+        // Revoking the delegation or moving the NFT away ends the listing
+        require!(
+            seller_ata.delegate == COption::Some(self.listing.key())
+                && seller_ata.delegated_amount >= quantity
+                && seller_ata.amount >= quantity,
+            MarketplaceError::DelegationRevoked
+        );
+
+        if frozen {
+            let edition = self
+                .edition
+                .as_ref()
+                .ok_or(MarketplaceError::MissingEditionAccount)?;
+
+            DelegatedNft {
+                metadata_program: self.metadata_program.to_account_info(),
+                delegate: self.listing.to_account_info(),
+                token_account: seller_ata.to_account_info(),
+                edition: edition.to_account_info(),
+                mint: self.seller_mint.to_account_info(),
+                token_program: self.token_program.to_account_info(),
+                signer_seeds,
+            }
+            .thaw()?;
+        }
+
+        NftTransfer {
+            from: seller_ata.to_account_info(), // Source: seller's token account
+            from_owner: self.seller.to_account_info(), // Seller owns the source account
+            to: self.buyer_ata.to_account_info(), // Destination: buyer's token account
+            to_owner: self.buyer.to_account_info(), // Buyer owns the destination account
+            authority: self.listing.to_account_info(), // Listing PDA is the approved delegate
+            payer: self.buyer.to_account_info(), // Nothing to create, pNFTs are never delegated
+            mint: self.seller_mint.to_account_info(), // NFT mint being transferred
+            token_program: self.token_program.to_account_info(),
+            signer_seeds,
+            extra_accounts,
+        }
+        .transfer(quantity, self.seller_mint.decimals, None)
     }
 
-    /// Closes the empty vault account after NFT transfer
-    /// Returns remaining lamports to the seller as compensation
-    pub fn close_vault(&mut self) -> Result<()> {
+    /// Closes the vault and the listing once everything has been bought
+    /// Returns their rent to the seller, so the same mint can be listed again later
+    pub fn close_if_sold_out(&mut self) -> Result<()> {
+        if self.listing.quantity > 0 {
+            return Ok(()); // Still partially listed
+        }
+
+        // Delegated listings have no vault to close
+        let Some(vault) = self.vault.as_ref() else {
+            return self.listing.close(self.seller.to_account_info());
+        };
+
         // Create PDA signing seeds for listing authority (same as transfer_nft)
         let seeds = &[
             &self.marketplace.key().to_bytes()[..], // Marketplace address as bytes
@@ -154,18 +460,98 @@ impl<'info> Purchase<'info> {
         ];
         let signer_seeds = &[&seeds[..]]; // Nested array for PDA signing
 
-        let cpi_accounts = CloseAccount {
-            account: self.vault.to_account_info(), // Vault account to close
-            authority: self.listing.to_account_info(), // Listing PDA has authority
-            destination: self.seller.to_account_info(), // Send remaining lamports to seller
-        };
+        close_vault(
+            vault.to_account_info(), // Empty listing vault
+            self.seller.to_account_info(), // Vault rent back to the seller
+            self.listing.to_account_info(), // Listing PDA has authority
+            self.token_program.to_account_info(),
+            signer_seeds,
+        )?;
+
+        self.listing.close(self.seller.to_account_info()) // Refund the listing rent to the seller
+    }
+
+    /// Mints loyalty tokens to the buyer and seller based on the amount paid
+    /// Rates are reward base units per SOL of volume, so a zero rate mints nothing
+    /// and leaves the reward token accounts uncreated
+    pub fn mint_rewards(&mut self, price: u64) -> Result<()> {
+        // Rates are denominated in SOL, so token-priced sales earn no rewards
+        if self.listing.payment_mint.is_some() {
+            return Ok(());
+        }
+
+        let buyer_reward = Self::reward_for(price, self.marketplace.buyer_reward_rate)?;
+        let seller_reward = Self::reward_for(price, self.marketplace.seller_reward_rate)?;
+
+        // Create PDA signing seeds for the marketplace (the reward mint authority)
+        let seeds = &[
+            b"marketplace".as_ref(), // Marketplace seed prefix
+            self.marketplace.name.as_bytes(), // Marketplace name
+            &[self.marketplace.bump], // Marketplace bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array structure for PDA signing
+
+        if buyer_reward > 0 {
+            self.mint_reward(
+                self.buyer_reward_ata.to_account_info(), // Buyer's reward ATA
+                self.buyer.to_account_info(), // Buyer owns it
+                buyer_reward,
+                signer_seeds,
+            )?;
+        }
+
+        if seller_reward > 0 {
+            self.mint_reward(
+                self.seller_reward_ata.to_account_info(), // Seller's reward ATA
+                self.seller.to_account_info(), // Seller owns it
+                seller_reward,
+                signer_seeds,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Creates `owner`'s reward ATA if it doesn't exist yet and mints `amount` into it
+    fn mint_reward(
+        &self,
+        ata: AccountInfo<'info>,
+        owner: AccountInfo<'info>,
+        amount: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        create_idempotent(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            Create {
+                payer: self.buyer.to_account_info(), // Buyer pays so the seller doesn't need to sign
+                associated_token: ata.clone(), // ATA address checked by seeds and the ATA program
+                authority: owner, // Owner of the reward tokens
+                mint: self.reward_mint.to_account_info(), // Reward mint
+                system_program: self.system_program.to_account_info(),
+                token_program: self.reward_token_program.to_account_info(),
+            },
+        ))?;
 
         let ctx = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
-            cpi_accounts,
+            self.reward_token_program.to_account_info(),
+            MintTo {
+                mint: self.reward_mint.to_account_info(), // Reward mint
+                to: ata, // Owner's reward ATA
+                authority: self.marketplace.to_account_info(), // Marketplace PDA is mint authority
+            },
             signer_seeds,
         );
+        mint_to(ctx, amount)
+    }
+
+    /// Converts a sale price in lamports into reward base units for a given rate
+    fn reward_for(price: u64, rate: u64) -> Result<u64> {
+        let reward = (price as u128)
+            .checked_mul(rate as u128)
+            .ok_or(MarketplaceError::MathOverflow)? // Handle multiplication overflow
+            .checked_div(LAMPORTS_PER_SOL as u128) // Rate is per whole SOL of volume
+            .ok_or(MarketplaceError::MathOverflow)?;
 
-        close_account(ctx) // Close vault and transfer remaining lamports to seller
+        u64::try_from(reward).map_err(|_| MarketplaceError::MathOverflow.into())
     }
 }
\ No newline at end of file
@@ -0,0 +1,166 @@
+// Core Anchor framework imports
+use anchor_lang::{prelude::*, solana_program::sysvar};
+
+// SPL Token and Metaplex imports
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::Metadata,
+    token_interface::TokenInterface,
+};
+
+// Local state, event and error imports
+use crate::bundle::{BundleRelease, RELEASE_ACCOUNTS_PER_NFT};
+use crate::error::MarketplaceError;
+use crate::events::BundleSold;
+use crate::nft_metadata::NftMetadata;
+use crate::settlement::{settle_sale_with_royalties, Payer, Royalties};
+use crate::{BundleListing, CollectionConfig, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for buying a bundle
+pub struct PurchaseBundle<'info> {
+    #[account(mut)] // Mutable because buyer pays for the bundle and any missing ATAs
+    pub buyer: Signer<'info>, // Person buying the bundle
+
+    #[account(mut)] // Mutable because seller receives payment and refunded rent
+    pub seller: SystemAccount<'info>, // Seller who listed the bundle
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>, // Marketplace configuration (fees, etc.)
+
+    // Account which stores the bundle details
+    #[account(
+        mut, // Mutable because we're closing this account after the sale
+        close = seller, // Refund the bundle rent to the seller
+        seeds = [b"bundle", marketplace.key().as_ref(), seller.key().as_ref(), bundle_listing.bundle_id.to_le_bytes().as_ref()], // PDA: "bundle" + marketplace + seller + id
+        bump = bundle_listing.bump, // Use stored bump to verify bundle PDA
+        constraint = bundle_listing.maker == seller.key(), // Verify this bundle belongs to the seller
+    )]
+    pub bundle_listing: Account<'info, BundleListing>, // Bundle being bought
+
+    // Settings of the listing's collection, which set the fee and can pause trading
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), bundle_listing.collection_mint.as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Account<'info, CollectionConfig>, // Collection's fee override, price range and status
+
+    // Treasury account where marketplace fees are collected
+    #[account(
+        mut, // Mutable because treasury receives fee payments
+        seeds = [b"treasury", marketplace.key().as_ref()], // PDA: "treasury" + marketplace
+        bump = marketplace.treasury_bump // Use stored bump to verify treasury PDA
+    )]
+    pub treasury: SystemAccount<'info>, // Treasury account for marketplace fees
+
+    /// CHECK: Metaplex metadata PDA of the first NFT, checked by seeds and read in purchase_bundle
+    /// Royalties for the whole bundle follow it, list_bundle having checked every NFT
+    /// has the same terms; uninitialized for Token-2022 group members, which pay none
+    #[account(
+        seeds = [ // Metaplex metadata PDA structure
+            b"metadata", // Metaplex metadata seed
+            metadata_program.key().as_ref(), // Metaplex program ID
+            bundle_listing.mints[0].as_ref() // First NFT in the bundle
+        ],
+        bump, // Canonical bump for metadata account
+        seeds::program = metadata_program.key(), // Verify this PDA belongs to Metaplex
+    )]
+    pub metadata: UncheckedAccount<'info>, // NFT metadata with royalty and creator info, if the NFT has any
+
+    // Programmable NFT accounts shared by every NFT, only passed when the bundle holds pNFTs
+    /// CHECK: Token Auth Rules program, validated by Token Metadata
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = sysvar::instructions::ID)] // Must be the real instructions sysvar
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    // Program accounts
+    pub metadata_program: Program<'info, Metadata>, // Metaplex metadata program
+    pub system_program: Program<'info, System>, // For SOL transfers and ATA creation
+    pub associated_token_program: Program<'info, AssociatedToken>, // For buyer ATA creation
+    pub token_program: Interface<'info, TokenInterface>, // For NFT transfers
+}
+
+impl<'info> PurchaseBundle<'info> {
+    /// Pays for the bundle once and moves every NFT to the buyer
+    ///
+    /// `remaining` holds RELEASE_ACCOUNTS_PER_NFT accounts per bundled NFT, the
+    /// recipient being the buyer, followed by the verified creator accounts from
+    /// the first NFT's metadata, in order, then the transfer hook accounts of any
+    /// Token-2022 mint with a hook
+    pub fn purchase_bundle(
+        &mut self,
+        expected_price: u64,
+        expected_seller: Pubkey,
+        remaining: &'info [AccountInfo<'info>],
+    ) -> Result<()> {
+        // Same protection as a single purchase against a changed or relisted lot
+        require_keys_eq!(
+            self.bundle_listing.maker,
+            expected_seller,
+            MarketplaceError::SellerMismatch
+        );
+        require!(
+            self.bundle_listing.price == expected_price,
+            MarketplaceError::PriceMismatch
+        );
+
+        let nft_count = self.bundle_listing.mints.len() * RELEASE_ACCOUNTS_PER_NFT;
+        require!(remaining.len() >= nft_count, MarketplaceError::InvalidBundleAccount);
+        let (nft_accounts, remaining) = remaining.split_at(nft_count);
+
+        let metadata = NftMetadata::try_from_account(&self.metadata)?;
+        let royalties = Royalties::from_optional_metadata(metadata.as_ref());
+        let (creators, extra_accounts) = royalties.split_accounts(remaining);
+
+        // Buyer pays once from their wallet
+        let payer = Payer::Wallet {
+            from: self.buyer.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        // Paused or removed collections can't be bought, only delisted
+        self.collection_config.check_enabled()?;
+
+        let settlement = settle_sale_with_royalties(
+            &payer,
+            self.bundle_listing.price,
+            self.collection_config.fee_bps(self.marketplace.fee),
+            &royalties,
+            creators,
+            self.seller.to_account_info(),
+            self.treasury.to_account_info(),
+        )?;
+
+        // Every NFT goes to the buyer, vault rent goes back to the seller
+        BundleRelease {
+            payer: self.buyer.to_account_info(),
+            recipient: self.buyer.to_account_info(),
+            rent_destination: self.seller.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            associated_token_program: self.associated_token_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            metadata_program: self.metadata_program.to_account_info(),
+            authorization_rules_program: self.authorization_rules_program.as_ref().map(|a| a.to_account_info()),
+            sysvar_instructions: self.sysvar_instructions.as_ref().map(|a| a.to_account_info()),
+        }
+        .release(&self.bundle_listing, self.marketplace.key(), nft_accounts, extra_accounts)?;
+
+        emit!(BundleSold {
+            marketplace: self.marketplace.key(),
+            bundle: self.bundle_listing.key(),
+            buyer: self.buyer.key(),
+            seller: self.seller.key(),
+            price: self.bundle_listing.price,
+            marketplace_fee: settlement.marketplace_fee,
+            royalty: settlement.royalty,
+            seller_proceeds: settlement.seller_proceeds,
+        });
+
+        Ok(())
+    }
+}
\ No newline at end of file
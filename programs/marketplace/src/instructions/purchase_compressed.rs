@@ -0,0 +1,178 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state, event and error imports
+use crate::compressed_nft::{
+    CompressedTransfer, LeafData, BUBBLEGUM_ID, SPL_ACCOUNT_COMPRESSION_ID, SPL_NOOP_ID,
+};
+use crate::error::MarketplaceError;
+use crate::events::Sold;
+use crate::settlement::{settle_sale_with_royalties, Payer};
+use crate::{CollectionConfig, CompressedListing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for buying a compressed NFT
+pub struct PurchaseCompressed<'info> {
+    #[account(mut)] // Mutable because buyer pays for the NFT
+    pub buyer: Signer<'info>, // Person buying the NFT
+
+    #[account(mut)] // Mutable because seller receives payment and refunded rent
+    pub seller: SystemAccount<'info>, // Seller who listed the NFT
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>, // Marketplace configuration (fees, etc.)
+
+    // Account which stores the listing details and owns the leaf
+    #[account(
+        mut, // Mutable because we're closing this account after the sale
+        close = seller, // Refund the listing rent to the seller
+        seeds = [b"compressed", marketplace.key().as_ref(), merkle_tree.key().as_ref(), compressed_listing.nonce.to_le_bytes().as_ref()], // PDA: "compressed" + marketplace + tree + nonce
+        bump = compressed_listing.bump, // Use stored bump to verify listing PDA
+        constraint = compressed_listing.maker == seller.key(), // Verify this listing belongs to the seller
+    )]
+    pub compressed_listing: Box<Account<'info, CompressedListing>>, // Listing being bought
+
+    // Settings of the listing's collection, which set the fee and can pause trading
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), compressed_listing.collection_mint.as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Box<Account<'info, CollectionConfig>>, // Collection's fee override, price range and status
+
+    // Treasury account where marketplace fees are collected
+    #[account(
+        mut, // Mutable because treasury receives fee payments
+        seeds = [b"treasury", marketplace.key().as_ref()], // PDA: "treasury" + marketplace
+        bump = marketplace.treasury_bump // Use stored bump to verify treasury PDA
+    )]
+    pub treasury: SystemAccount<'info>, // Treasury account for marketplace fees
+
+    /// CHECK: Merkle tree holding the leaf, checked by the compression program
+    #[account(mut)] // Mutable because the transfer replaces the leaf
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum tree config PDA, checked by seeds
+    #[account(
+        seeds = [merkle_tree.key().as_ref()], // PDA: merkle tree
+        bump, // Canonical bump for tree config
+        seeds::program = bubblegum_program.key(), // Verify this PDA belongs to Bubblegum
+    )]
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Bubblegum program, checked by address
+    #[account(address = BUBBLEGUM_ID)]
+    pub bubblegum_program: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Noop program, checked by address
+    #[account(address = SPL_NOOP_ID)]
+    pub log_wrapper: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Account Compression program, checked by address
+    #[account(address = SPL_ACCOUNT_COMPRESSION_ID)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    // Program accounts
+    pub system_program: Program<'info, System>, // For SOL transfers
+}
+
+impl<'info> PurchaseCompressed<'info> {
+    /// Pays for a compressed listing and moves the leaf to the buyer
+    ///
+    /// `metadata_args` are the NFT's Borsh-serialized Bubblegum MetadataArgs, which
+    /// royalties are read from. `remaining` holds one writable account per verified
+    /// creator, in order, followed by the proof nodes not covered by the tree's canopy
+    pub fn purchase_compressed(
+        &mut self,
+        root: [u8; 32],
+        metadata_args: &[u8],
+        expected_price: u64,
+        expected_seller: Pubkey,
+        remaining: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let listing = &self.compressed_listing;
+
+        // Expired listings can only be returned to the seller
+        require!(
+            !listing.is_expired(Clock::get()?.unix_timestamp),
+            MarketplaceError::ListingExpired
+        );
+
+        // Same protection as a token purchase against a changed or relisted NFT
+        require_keys_eq!(listing.maker, expected_seller, MarketplaceError::SellerMismatch);
+        require!(listing.price == expected_price, MarketplaceError::PriceMismatch);
+
+        // Royalties are only trusted from the metadata the leaf was listed with
+        let leaf = LeafData::from_metadata_args(metadata_args)?;
+        require!(
+            leaf.data_hash == listing.data_hash && leaf.creator_hash == listing.creator_hash,
+            MarketplaceError::InvalidCompressedNft
+        );
+
+        let royalties = leaf.royalties();
+        require!(
+            remaining.len() >= royalties.creators.len(),
+            MarketplaceError::MissingCreatorAccount
+        );
+        let (creators, proof) = remaining.split_at(royalties.creators.len());
+
+        // Buyer pays from their wallet
+        let payer = Payer::Wallet {
+            from: self.buyer.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        // Paused or removed collections can't be bought, only delisted
+        self.collection_config.check_enabled()?;
+
+        let settlement = settle_sale_with_royalties(
+            &payer,
+            listing.price,
+            self.collection_config.fee_bps(self.marketplace.fee),
+            &royalties,
+            creators,
+            self.seller.to_account_info(),
+            self.treasury.to_account_info(),
+        )?;
+
+        emit!(Sold {
+            marketplace: self.marketplace.key(),
+            mint: listing.asset_id,
+            buyer: self.buyer.key(),
+            seller: self.seller.key(),
+            price: listing.price,
+            quantity: 1,
+            marketplace_fee: settlement.marketplace_fee,
+            royalty: settlement.royalty,
+            seller_proceeds: settlement.seller_proceeds,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        // Create PDA signing seeds for listing authority
+        let seeds = &[
+            &b"compressed"[..], // Compressed listing seed
+            &self.marketplace.key().to_bytes()[..], // Marketplace address as bytes
+            &self.merkle_tree.key().to_bytes()[..], // Merkle tree address as bytes
+            &listing.nonce.to_le_bytes()[..], // Leaf nonce as bytes
+            &[listing.bump], // Listing bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array for PDA signing
+
+        CompressedTransfer {
+            bubblegum_program: self.bubblegum_program.to_account_info(),
+            tree_authority: self.tree_authority.to_account_info(),
+            leaf_owner: listing.to_account_info(), // Listing PDA owns the leaf
+            leaf_delegate: listing.to_account_info(), // Bubblegum reset the delegate on listing
+            new_leaf_owner: self.buyer.to_account_info(), // Buyer receives it
+            merkle_tree: self.merkle_tree.to_account_info(),
+            log_wrapper: self.log_wrapper.to_account_info(),
+            compression_program: self.compression_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            proof,
+            signer_seeds,
+        }
+        .transfer(root, listing.data_hash, listing.creator_hash, listing.nonce, listing.index)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,158 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state, event and error imports
+use crate::core_asset::{transfer_core_asset, CoreAsset, CoreCollection, MPL_CORE_ID};
+use crate::error::MarketplaceError;
+use crate::events::Sold;
+use crate::settlement::{settle_sale_with_royalties, Payer};
+use crate::{CollectionConfig, Listing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for buying a Metaplex Core asset
+pub struct PurchaseCore<'info> {
+    #[account(mut)] // Mutable because buyer pays for the asset
+    pub buyer: Signer<'info>, // Person buying the asset
+
+    #[account(mut)] // Mutable because seller receives payment and refunded rent
+    pub seller: SystemAccount<'info>, // Seller who listed the asset
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>, // Marketplace configuration (fees, etc.)
+
+    // Account which stores the listing details and owns the asset
+    #[account(
+        mut, // Mutable because we're closing this account after the sale
+        close = seller, // Refund the listing rent to the seller
+        seeds = [marketplace.key().as_ref(), asset.key().as_ref()], // PDA: marketplace + asset
+        bump = listing.bump, // Use stored bump to verify listing PDA
+        constraint = listing.maker == seller.key(), // Verify this listing belongs to the seller
+    )]
+    pub listing: Account<'info, Listing>, // Listing being bought
+
+    // Settings of the listing's collection, which set the fee and can pause trading
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), listing.collection.as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Account<'info, CollectionConfig>, // Collection's fee override, price range and status
+
+    /// CHECK: Core asset owned by the listing PDA, parsed in purchase_core
+    #[account(mut)] // Mutable because its owner changes to the buyer
+    pub asset: UncheckedAccount<'info>,
+
+    /// CHECK: Core collection, checked against the asset in purchase_core
+    pub collection: UncheckedAccount<'info>,
+
+    // Treasury account where marketplace fees are collected
+    #[account(
+        mut, // Mutable because treasury receives fee payments
+        seeds = [b"treasury", marketplace.key().as_ref()], // PDA: "treasury" + marketplace
+        bump = marketplace.treasury_bump // Use stored bump to verify treasury PDA
+    )]
+    pub treasury: SystemAccount<'info>, // Treasury account for marketplace fees
+
+    /// CHECK: Metaplex Core program, checked by address
+    #[account(address = MPL_CORE_ID)] // Must be the real Core program
+    pub core_program: UncheckedAccount<'info>,
+
+    // Program accounts
+    pub system_program: Program<'info, System>, // For SOL transfers
+}
+
+impl<'info> PurchaseCore<'info> {
+    /// Pays for a Core listing through the same settlement as token listings
+    ///
+    /// `creators` holds one writable account per creator in the asset's royalties
+    /// plugin, or the collection's when the asset has none, in order
+    pub fn purchase_core(
+        &mut self,
+        expected_price: u64,
+        expected_seller: Pubkey,
+        creators: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        // Expired listings can only be returned to the seller
+        require!(
+            !self.listing.is_expired(Clock::get()?.unix_timestamp),
+            MarketplaceError::ListingExpired
+        );
+
+        // Same protection as a token purchase against a changed or relisted asset
+        require_keys_eq!(self.listing.maker, expected_seller, MarketplaceError::SellerMismatch);
+        require!(
+            self.listing.price == expected_price,
+            MarketplaceError::PriceMismatch
+        );
+
+        // Royalties come from the collection passed in, so it must be the asset's
+        let asset = CoreAsset::try_from_account(&self.asset)?;
+        require!(
+            asset.collection == Some(self.collection.key()),
+            MarketplaceError::InvalidCollection
+        );
+
+        // Asset-level royalties override the collection's, as in Core itself
+        let royalties = match asset.royalties {
+            Some(royalties) => royalties,
+            None => CoreCollection::try_from_account(&self.collection)?.royalties.unwrap_or_default(),
+        };
+
+        // Buyer pays from their wallet
+        let payer = Payer::Wallet {
+            from: self.buyer.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+        };
+
+        // Paused or removed collections can't be bought, only delisted
+        self.collection_config.check_enabled()?;
+
+        let settlement = settle_sale_with_royalties(
+            &payer,
+            self.listing.price,
+            self.collection_config.fee_bps(self.marketplace.fee),
+            &royalties,
+            creators,
+            self.seller.to_account_info(),
+            self.treasury.to_account_info(),
+        )?;
+
+        emit!(Sold {
+            marketplace: self.marketplace.key(),
+            mint: self.asset.key(),
+            buyer: self.buyer.key(),
+            seller: self.seller.key(),
+            price: self.listing.price,
+            quantity: 1,
+            marketplace_fee: settlement.marketplace_fee,
+            royalty: settlement.royalty,
+            seller_proceeds: settlement.seller_proceeds,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Transfers the asset from the listing PDA to the buyer
+    pub fn transfer_asset(&mut self) -> Result<()> {
+        // Create PDA signing seeds for listing authority
+        let seeds = &[
+            &self.marketplace.key().to_bytes()[..], // Marketplace address as bytes
+            &self.asset.key().to_bytes()[..], // Asset address as bytes
+            &[self.listing.bump], // Listing bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array for PDA signing
+
+        transfer_core_asset(
+            &self.core_program,
+            &self.asset,
+            &self.collection,
+            &self.buyer, // Buyer pays
+            &self.listing.to_account_info(), // Listing PDA owns the asset
+            &self.buyer, // Buyer receives it
+            signer_seeds,
+        )
+    }
+}
\ No newline at end of file
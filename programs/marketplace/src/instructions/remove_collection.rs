@@ -0,0 +1,41 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state and event imports
+use crate::events::CollectionRemoved;
+use crate::{CollectionConfig, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for removing a collection
+pub struct RemoveCollection<'info> {
+    #[account(mut)] // Mutable because the admin receives the config's rent
+    pub admin: Signer<'info>, // Marketplace admin authorizing the change
+
+    // Account which has the marketplace details
+    #[account(
+        has_one = admin, // Only the stored admin may remove collections
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace the collection was approved on
+
+    // Account which stores the collection settings
+    #[account(
+        mut, // Mutable because we're closing this account
+        close = admin, // Refund the rent to the admin
+        seeds = [b"collection", marketplace.key().as_ref(), collection_config.collection.as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Account<'info, CollectionConfig>, // Settings being removed
+}
+
+impl<'info> RemoveCollection<'info> {
+    /// Removes the collection; its live listings can only be delisted from now on
+    pub fn remove(&mut self) -> Result<()> {
+        emit!(CollectionRemoved {
+            marketplace: self.marketplace.key(),
+            collection: self.collection_config.collection,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
@@ -0,0 +1,44 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::PaymentMintUpdated;
+use crate::Marketplace;
+
+#[derive(Accounts)] // Define accounts needed for removing an accepted payment mint
+pub struct RemovePaymentMint<'info> {
+    pub admin: Signer<'info>, // Marketplace admin authorizing the change
+
+    // Account which has the marketplace details
+    #[account(
+        mut, // Mutable because the allowlist shrinks
+        has_one = admin, // Only the stored admin may change the allowlist
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace whose allowlist changes
+}
+
+impl<'info> RemovePaymentMint<'info> {
+    /// Removes the payment mint from the marketplace allowlist
+    /// Existing listings keep their payment mint, only new listings are affected
+    pub fn remove(&mut self, mint: Pubkey) -> Result<()> {
+        let position = self
+            .marketplace
+            .payment_mints
+            .iter()
+            .position(|accepted| *accepted == mint)
+            .ok_or(MarketplaceError::PaymentMintNotAllowed)?;
+
+        self.marketplace.payment_mints.remove(position); // Stop accepting the mint
+
+        emit!(PaymentMintUpdated {
+            marketplace: self.marketplace.key(),
+            mint,
+            allowed: false,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
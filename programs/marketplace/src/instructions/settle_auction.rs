@@ -0,0 +1,274 @@
+// Core Anchor framework imports
+use anchor_lang::{prelude::*, solana_program::sysvar};
+
+// SPL Token and Metaplex imports
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    metadata::{Metadata, MetadataAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::AuctionSettled;
+use crate::nft_transfer::{close_vault, NftTransfer, ProgrammableAccounts};
+use crate::settlement::{settle_sale_with_royalties, Payer, Royalties, Settlement};
+use crate::{Auction, CollectionConfig, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for settling an auction
+pub struct SettleAuction<'info> {
+    #[account(mut)] // Mutable because they pay for the receiving token account if needed
+    pub payer: Signer<'info>, // Anyone can settle once the auction has ended
+
+    #[account(mut)] // Mutable because seller receives proceeds and rent
+    pub seller: SystemAccount<'info>, // Seller who created the auction
+
+    // Winning bidder, only passed when the auction received bids
+    pub winner: Option<SystemAccount<'info>>, // Highest bidder receiving the NFT
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Box<Account<'info, Marketplace>>, // Marketplace configuration (fees, etc.)
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>, // NFT mint being auctioned
+
+    // Account which stores the auction details and escrows the high bid
+    #[account(
+        mut, // Mutable because we're paying out and closing this account
+        close = seller, // Refund the auction rent to the seller
+        has_one = seller, // Proceeds must go to the auction's seller
+        seeds = [b"auction", marketplace.key().as_ref(), mint.key().as_ref()], // PDA: "auction" + marketplace + NFT mint
+        bump = auction.bump // Use stored bump to verify PDA
+    )]
+    pub auction: Box<Account<'info, Auction>>, // The auction being settled
+
+    /// CHECK: Settings of the auctioned NFT's collection, checked by seeds and read in settle
+    /// for the fee; the admin may have removed the collection since the auction started
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), auction.collection.as_ref()], // PDA: "collection" + marketplace + collection
+        bump // Canonical bump, since a removed config has no stored one
+    )]
+    pub collection_config: UncheckedAccount<'info>,
+
+    // Account where the NFT is kept until the auction settles
+    #[account(
+        mut, // Mutable because we're closing this vault after transfer
+        associated_token::mint = mint, // ATA for the NFT mint
+        associated_token::authority = auction // Auction PDA controls the vault
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>, // Escrow vault holding the NFT
+
+    // Winner's token account, only passed when the auction received bids
+    #[account(
+        init_if_needed, // Create winner's ATA if it doesn't exist
+        payer = payer, // Whoever settles pays for account creation
+        associated_token::mint = mint, // ATA for this specific NFT mint
+        associated_token::authority = winner // Winner owns this token account
+    )]
+    pub winner_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Winner's token account to receive NFT
+
+    // Seller's token account, only passed when the auction got no bids
+    #[account(
+        init_if_needed, // Create seller's ATA if it was closed during the auction
+        payer = payer, // Whoever settles pays for account creation
+        associated_token::mint = mint, // ATA for this specific NFT mint
+        associated_token::authority = seller // Seller owns this token account
+    )]
+    pub seller_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>, // Seller's token account to get the NFT back
+
+    // Treasury account where marketplace fees are collected
+    #[account(
+        mut, // Mutable because treasury receives fee payments
+        seeds = [b"treasury", marketplace.key().as_ref()], // PDA: "treasury" + marketplace
+        bump = marketplace.treasury_bump // Use stored bump to verify treasury PDA
+    )]
+    pub treasury: SystemAccount<'info>, // Treasury account for marketplace fees
+
+    // Left out for Token-2022 NFTs without Metaplex metadata, which pay no royalties
+    #[account(
+        mut, // Mutable because Token Metadata writes to it when moving a pNFT
+        seeds = [ // Metaplex metadata PDA structure
+            b"metadata", // Metaplex metadata seed
+            metadata_program.key().as_ref(), // Metaplex program ID
+            mint.key().as_ref() // The NFT mint
+        ],
+        bump, // Canonical bump for metadata account
+        seeds::program = metadata_program.key(), // Verify this PDA belongs to Metaplex
+    )]
+    pub metadata: Option<Box<Account<'info, MetadataAccount>>>, // NFT metadata with royalty and creator info
+
+    // Programmable NFT accounts, only passed when the metadata token standard is programmable
+    /// CHECK: Master edition of the NFT, validated by Token Metadata
+    pub edition: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the vault, validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata updates or closes it
+    pub token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token record of the winner's or seller's token account, created and validated by Token Metadata
+    #[account(mut)] // Mutable because Token Metadata creates or updates it
+    pub destination_token_record: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Rule set of the pNFT, validated by Token Metadata
+    pub authorization_rules: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Token Auth Rules program, validated by Token Metadata
+    pub authorization_rules_program: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Instructions sysvar, checked by address
+    #[account(address = sysvar::instructions::ID)] // Must be the real instructions sysvar
+    pub sysvar_instructions: Option<UncheckedAccount<'info>>,
+
+    // Program accounts
+    pub metadata_program: Program<'info, Metadata>, // Metaplex metadata program
+    pub system_program: Program<'info, System>, // For account operations
+    pub associated_token_program: Program<'info, AssociatedToken>, // For ATA creation
+    pub token_program: Interface<'info, TokenInterface>, // For NFT transfers
+}
+
+impl<'info> SettleAuction<'info> {
+    /// Pays out the winning bid and hands the NFT to the winner
+    /// With no bids the NFT simply goes back to the seller
+    ///
+    /// `extra_accounts` are the transfer hook accounts of a Token-2022 mint, if it has a hook
+    pub fn settle(
+        &mut self,
+        creators: &[AccountInfo<'info>],
+        extra_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= self.auction.end_time, MarketplaceError::AuctionNotEnded);
+
+        // Leaving out the metadata of a Metaplex NFT would skip its royalties
+        if self.auction.has_metadata {
+            require!(self.metadata.is_some(), MarketplaceError::MissingMetadataAccount);
+        }
+
+        // Winner gets the NFT, or the seller gets it back if nobody bid
+        let (recipient, recipient_owner, settlement) = match self.auction.highest_bidder {
+            Some(highest_bidder) => {
+                let winner = self.winner.as_ref().ok_or(MarketplaceError::InvalidBidder)?;
+                require_keys_eq!(winner.key(), highest_bidder, MarketplaceError::InvalidBidder);
+                let winner_ata = self
+                    .winner_ata
+                    .as_ref()
+                    .ok_or(MarketplaceError::MissingNftAccount)?;
+
+                // Bidders' SOL is escrowed, so a paused or removed collection
+                // doesn't hold up the sale
+                // Winning bid is paid out of escrow with the same split as purchase
+                let settlement = settle_sale_with_royalties(
+                    &Payer::Escrow {
+                        from: self.auction.to_account_info(),
+                    },
+                    self.auction.highest_bid,
+                    self.fee_bps()?,
+                    &Royalties::from_optional_metadata(self.metadata.as_deref()),
+                    creators,
+                    self.seller.to_account_info(),
+                    self.treasury.to_account_info(),
+                )?;
+
+                (winner_ata.to_account_info(), winner.to_account_info(), settlement)
+            }
+            None => {
+                let seller_ata = self
+                    .seller_ata
+                    .as_ref()
+                    .ok_or(MarketplaceError::MissingNftAccount)?;
+
+                let settlement = Settlement {
+                    marketplace_fee: 0,
+                    royalty: 0,
+                    seller_proceeds: 0,
+                };
+
+                (seller_ata.to_account_info(), self.seller.to_account_info(), settlement)
+            }
+        };
+
+        self.transfer_nft(recipient, recipient_owner, extra_accounts)?;
+
+        emit!(AuctionSettled {
+            marketplace: self.marketplace.key(),
+            mint: self.mint.key(),
+            seller: self.seller.key(),
+            winner: self.auction.highest_bidder,
+            price: self.auction.highest_bid,
+            marketplace_fee: settlement.marketplace_fee,
+            royalty: settlement.royalty,
+            seller_proceeds: settlement.seller_proceeds,
+        });
+
+        Ok(())
+    }
+
+    /// Fee on the winning bid: the collection's override while it is approved,
+    /// the marketplace fee once the admin has removed it
+    fn fee_bps(&self) -> Result<u16> {
+        if self.collection_config.data_is_empty() {
+            return Ok(self.marketplace.fee);
+        }
+        let data = self.collection_config.try_borrow_data()?;
+        let config = CollectionConfig::try_deserialize(&mut &data[..])?;
+        Ok(config.fee_bps(self.marketplace.fee))
+    }
+
+    /// Moves the NFT out of the vault and closes it, refunding rent to the seller
+    /// Programmable NFTs go through Token Metadata, everything else through the token program
+    fn transfer_nft(
+        &self,
+        to: AccountInfo<'info>,
+        to_owner: AccountInfo<'info>,
+        extra_accounts: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        // Create PDA signing seeds for auction authority
+        let marketplace_key = self.marketplace.key();
+        let mint_key = self.mint.key();
+        let seeds = &[
+            b"auction".as_ref(), // Auction seed prefix
+            marketplace_key.as_ref(), // Marketplace address
+            mint_key.as_ref(), // NFT mint address
+            &[self.auction.bump], // Auction bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array structure for PDA signing
+
+        let programmable = ProgrammableAccounts::from_optional(
+            &self.metadata_program,
+            self.metadata.as_deref(),
+            self.edition.as_ref(),
+            self.token_record.as_ref(),
+            self.destination_token_record.as_ref(),
+            self.authorization_rules.as_ref(),
+            self.authorization_rules_program.as_ref(),
+            self.sysvar_instructions.as_ref(),
+            &self.system_program,
+            &self.associated_token_program,
+        );
+
+        NftTransfer {
+            from: self.vault.to_account_info(), // Source: auction vault
+            from_owner: self.auction.to_account_info(), // Auction PDA owns the vault
+            to, // Destination: winner or seller
+            to_owner, // Winner or seller owns the destination account
+            authority: self.auction.to_account_info(), // Auction PDA has authority over vault
+            payer: self.payer.to_account_info(), // Whoever settles pays for the token record
+            mint: self.mint.to_account_info(), // NFT mint being transferred
+            token_program: self.token_program.to_account_info(),
+            signer_seeds,
+            extra_accounts,
+        }
+        .transfer(1, self.mint.decimals, programmable.as_ref())?; // A single NFT
+
+        close_vault(
+            self.vault.to_account_info(), // Empty auction vault
+            self.seller.to_account_info(), // Vault rent back to the seller
+            self.auction.to_account_info(), // Auction PDA has authority
+            self.token_program.to_account_info(),
+            signer_seeds,
+        )
+    }
+}
\ No newline at end of file
@@ -0,0 +1,57 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state and event imports
+use crate::events::CollectionConfigUpdated;
+use crate::{CollectionConfig, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for changing a collection's settings
+pub struct UpdateCollection<'info> {
+    pub admin: Signer<'info>, // Marketplace admin authorizing the change
+
+    // Account which has the marketplace details
+    #[account(
+        has_one = admin, // Only the stored admin may change collection settings
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace the collection is approved on
+
+    // Account which stores the collection settings
+    #[account(
+        mut, // Mutable because the settings change
+        seeds = [b"collection", marketplace.key().as_ref(), collection_config.collection.as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Account<'info, CollectionConfig>, // Settings being updated
+}
+
+impl<'info> UpdateCollection<'info> {
+    /// Replaces every setting of the collection
+    /// Listings already live keep their price; the new range applies to new listings and price updates
+    pub fn update(
+        &mut self,
+        fee: Option<u16>,
+        min_price: Option<u64>,
+        max_price: Option<u64>,
+        enabled: bool,
+    ) -> Result<()> {
+        CollectionConfig::check_settings(fee, min_price, max_price, self.marketplace.max_fee)?;
+
+        self.collection_config.fee = fee; // None charges the marketplace fee
+        self.collection_config.min_price = min_price; // None removes the minimum
+        self.collection_config.max_price = max_price; // None removes the maximum
+        self.collection_config.enabled = enabled; // False pauses listing and buying
+
+        emit!(CollectionConfigUpdated {
+            marketplace: self.marketplace.key(),
+            collection: self.collection_config.collection,
+            fee,
+            min_price,
+            max_price,
+            enabled,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
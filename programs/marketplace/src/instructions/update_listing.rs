@@ -0,0 +1,94 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state, event and error imports
+use crate::core_asset::MPL_CORE_ID;
+use crate::error::MarketplaceError;
+use crate::events::ListingUpdated;
+use crate::{CollectionConfig, Listing, Marketplace};
+
+#[derive(Accounts)] // Define accounts needed for changing listing terms
+pub struct UpdateListing<'info> {
+    pub seller: Signer<'info>, // Original seller changing their listing
+
+    // Account which stores the listing details
+    #[account(
+        mut, // Mutable because the listing terms change
+        seeds = [marketplace.key().as_ref(), mint.key().as_ref()], // PDA: marketplace + NFT mint
+        constraint = listing.maker == seller.key(), // Verify this listing belongs to the seller
+        bump = listing.bump // Use stored bump to verify PDA
+    )]
+    pub listing: Account<'info, Listing>, // The listing being updated
+
+    /// CHECK: Listed NFT mint or Core asset, only used in the listing PDA seeds
+    pub mint: UncheckedAccount<'info>, // The listed NFT token mint or Core asset
+
+    // Account which has the marketplace details
+    #[account(
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Read accepted payment mints
+
+    // Settings of the listing's collection, which bound the new price
+    #[account(
+        seeds = [b"collection", marketplace.key().as_ref(), listing.collection.as_ref()], // PDA: "collection" + marketplace + collection
+        bump = collection_config.bump // Use stored bump to verify PDA
+    )]
+    pub collection_config: Account<'info, CollectionConfig>, // Collection's fee override, price range and status
+}
+
+impl<'info> UpdateListing<'info> {
+    /// Changes the price, payment mint and expiry of a listing in place
+    /// The NFT stays in the vault, so no accounts are closed or re-created.
+    /// A Dutch listing becomes a fixed price listing at the new price
+    pub fn update(
+        &mut self,
+        price: u64,
+        payment_mint: Option<Pubkey>,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        // Same rules as listing: the price must be in the collection's range,
+        // and token prices only in mints the admin accepts
+        self.collection_config.check_price(price)?;
+        if let Some(payment_mint) = payment_mint {
+            // Core listings are bought with purchase_core, which only takes SOL
+            require!(*self.mint.owner != MPL_CORE_ID, MarketplaceError::CoreListingSolOnly);
+            require!(
+                self.marketplace.payment_mints.contains(&payment_mint),
+                MarketplaceError::PaymentMintNotAllowed
+            );
+        }
+
+        // A listing that is already expired could never be bought
+        if let Some(expires_at) = expires_at {
+            require!(
+                expires_at > Clock::get()?.unix_timestamp,
+                MarketplaceError::InvalidExpiry
+            );
+        }
+
+        let old_price = self.listing.price; // Remember the old terms for the event
+        let old_payment_mint = self.listing.payment_mint;
+        let old_expires_at = self.listing.expires_at;
+
+        self.listing.price = price; // Store the new price
+        self.listing.payment_mint = payment_mint; // Store the new payment mint
+        self.listing.expires_at = expires_at; // None keeps the listing open until sold or delisted
+        self.listing.dutch_auction = None; // New price is fixed
+
+        emit!(ListingUpdated {
+            marketplace: self.marketplace.key(),
+            mint: self.mint.key(),
+            seller: self.seller.key(),
+            old_price,
+            new_price: price,
+            old_payment_mint,
+            new_payment_mint: payment_mint,
+            old_expires_at,
+            new_expires_at: expires_at,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
@@ -0,0 +1,76 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::MarketplaceUpdated;
+use crate::Marketplace;
+
+#[derive(Accounts)] // Define accounts needed for updating marketplace settings
+pub struct UpdateMarketplace<'info> {
+    pub admin: Signer<'info>, // Current marketplace admin authorizing the change
+
+    // Account which has the marketplace details
+    #[account(
+        mut, // Mutable because we're rewriting the settings
+        has_one = admin, // Only the stored admin may update the marketplace
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace configuration being updated
+}
+
+impl<'info> UpdateMarketplace<'info> {
+    /// Updates the marketplace fee, reward rates and print edition policy and/or proposes a new admin
+    /// A proposed admin only takes over once they call `accept_admin`
+    pub fn update(
+        &mut self,
+        fee: Option<u16>,
+        new_admin: Option<Pubkey>,
+        buyer_reward_rate: Option<u64>,
+        seller_reward_rate: Option<u64>,
+        allow_print_editions: Option<bool>,
+    ) -> Result<()> {
+        let old_fee = self.marketplace.fee; // Remember the fee for the event
+        let old_buyer_reward_rate = self.marketplace.buyer_reward_rate;
+        let old_seller_reward_rate = self.marketplace.seller_reward_rate;
+        let old_allow_print_editions = self.marketplace.allow_print_editions;
+
+        if let Some(fee) = fee {
+            // Can't exceed the cap chosen when the marketplace was created
+            require!(fee <= self.marketplace.max_fee, MarketplaceError::FeeTooHigh);
+            self.marketplace.fee = fee; // Store the new fee (basis points)
+        }
+
+        if let Some(new_admin) = new_admin {
+            self.marketplace.pending_admin = Some(new_admin); // Start the two-step handoff
+        }
+
+        if let Some(rate) = buyer_reward_rate {
+            self.marketplace.buyer_reward_rate = rate; // Reward units per SOL for buyers
+        }
+
+        if let Some(rate) = seller_reward_rate {
+            self.marketplace.seller_reward_rate = rate; // Reward units per SOL for sellers
+        }
+
+        if let Some(allow) = allow_print_editions {
+            self.marketplace.allow_print_editions = allow; // Applies to new listings only
+        }
+
+        emit!(MarketplaceUpdated {
+            marketplace: self.marketplace.key(),
+            old_fee,
+            new_fee: self.marketplace.fee,
+            old_buyer_reward_rate,
+            new_buyer_reward_rate: self.marketplace.buyer_reward_rate,
+            old_seller_reward_rate,
+            new_seller_reward_rate: self.marketplace.seller_reward_rate,
+            pending_admin: self.marketplace.pending_admin,
+            old_allow_print_editions,
+            new_allow_print_editions: self.marketplace.allow_print_editions,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
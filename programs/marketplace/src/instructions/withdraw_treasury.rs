@@ -0,0 +1,82 @@
+// Core Anchor framework imports
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::TreasuryWithdrawn;
+use crate::Marketplace;
+
+#[derive(Accounts)] // Define accounts needed for withdrawing collected fees
+pub struct WithdrawTreasury<'info> {
+    pub admin: Signer<'info>, // Marketplace admin authorizing the withdrawal
+
+    // Account which has the marketplace details
+    #[account(
+        has_one = admin, // Only the stored admin may withdraw fees
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace owning the treasury
+
+    // Treasury account where marketplace fees are collected
+    #[account(
+        mut, // Mutable because lamports leave the treasury
+        seeds = [b"treasury", marketplace.key().as_ref()], // PDA: "treasury" + marketplace
+        bump = marketplace.treasury_bump // Use stored bump to verify treasury PDA
+    )]
+    pub treasury: SystemAccount<'info>, // Treasury holding collected fees
+
+    #[account(mut)] // Mutable because it receives the withdrawn lamports
+    pub destination: SystemAccount<'info>, // Where the admin wants the fees sent
+
+    // Program accounts
+    pub system_program: Program<'info, System>, // For SOL transfers
+}
+
+impl<'info> WithdrawTreasury<'info> {
+    /// Moves collected fees out of the treasury PDA
+    /// Withdraws everything above the rent-exempt minimum when no amount is given
+    pub fn withdraw(&mut self, amount: Option<u64>) -> Result<()> {
+        // Treasury must stay rent exempt so the PDA keeps existing
+        let rent_exempt = Rent::get()?.minimum_balance(0);
+        let available = self.treasury.lamports().saturating_sub(rent_exempt);
+
+        let amount = amount.unwrap_or(available); // Default to everything available
+        require!(
+            amount <= available,
+            MarketplaceError::InsufficientTreasuryBalance
+        );
+
+        // Create PDA signing seeds for treasury authority
+        let marketplace_key = self.marketplace.key();
+        let seeds = &[
+            b"treasury".as_ref(), // Treasury seed prefix
+            marketplace_key.as_ref(), // Marketplace address
+            &[self.marketplace.treasury_bump], // Treasury bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array structure for PDA signing
+
+        let ctx = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            Transfer {
+                from: self.treasury.to_account_info(), // Source: treasury PDA
+                to: self.destination.to_account_info(), // Destination chosen by admin
+            },
+            signer_seeds,
+        );
+        transfer(ctx, amount)?;
+
+        emit!(TreasuryWithdrawn {
+            marketplace: marketplace_key,
+            mint: None,
+            destination: self.destination.key(),
+            amount,
+            remaining: self.treasury.lamports(),
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
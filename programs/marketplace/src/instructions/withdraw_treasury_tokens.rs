@@ -0,0 +1,98 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// SPL Token program imports
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+// Local state, event and error imports
+use crate::error::MarketplaceError;
+use crate::events::TreasuryWithdrawn;
+use crate::Marketplace;
+
+#[derive(Accounts)] // Define accounts needed for withdrawing collected token fees
+pub struct WithdrawTreasuryTokens<'info> {
+    pub admin: Signer<'info>, // Marketplace admin authorizing the withdrawal
+
+    // Account which has the marketplace details
+    #[account(
+        has_one = admin, // Only the stored admin may withdraw fees
+        seeds = [b"marketplace", marketplace.name.as_bytes()], // PDA: "marketplace" + name
+        bump = marketplace.bump // Use stored bump to verify PDA
+    )]
+    pub marketplace: Account<'info, Marketplace>, // Marketplace owning the treasury
+
+    // Treasury PDA which owns the fee token accounts
+    #[account(
+        seeds = [b"treasury", marketplace.key().as_ref()], // PDA: "treasury" + marketplace
+        bump = marketplace.treasury_bump // Use stored bump to verify treasury PDA
+    )]
+    pub treasury: SystemAccount<'info>, // Authority over the treasury token accounts
+
+    pub payment_mint: InterfaceAccount<'info, Mint>, // Mint of the fees being withdrawn
+
+    // Treasury's token account holding collected fees
+    #[account(
+        mut, // Mutable because tokens leave this account
+        associated_token::mint = payment_mint, // ATA for the payment mint
+        associated_token::authority = treasury, // Treasury PDA owns this token account
+        associated_token::token_program = token_program // Payment mint's token program
+    )]
+    pub treasury_ata: InterfaceAccount<'info, TokenAccount>, // Treasury's payment tokens
+
+    // Token account receiving the fees
+    #[account(
+        mut, // Mutable because it receives the withdrawn tokens
+        token::mint = payment_mint, // Must hold the same mint
+        token::token_program = token_program // Payment mint's token program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>, // Where the admin wants the fees sent
+
+    // Program accounts
+    pub token_program: Interface<'info, TokenInterface>, // For token transfers
+}
+
+impl<'info> WithdrawTreasuryTokens<'info> {
+    /// Moves collected token fees out of the treasury's token account
+    /// Withdraws the whole balance when no amount is given
+    pub fn withdraw(&mut self, amount: Option<u64>) -> Result<()> {
+        let available = self.treasury_ata.amount; // Token accounts have no rent floor to keep
+        let amount = amount.unwrap_or(available); // Default to everything available
+        require!(
+            amount <= available,
+            MarketplaceError::InsufficientTreasuryBalance
+        );
+
+        // Create PDA signing seeds for treasury authority
+        let marketplace_key = self.marketplace.key();
+        let seeds = &[
+            b"treasury".as_ref(), // Treasury seed prefix
+            marketplace_key.as_ref(), // Marketplace address
+            &[self.marketplace.treasury_bump], // Treasury bump seed
+        ];
+        let signer_seeds = &[&seeds[..]]; // Nested array structure for PDA signing
+
+        let ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            TransferChecked {
+                from: self.treasury_ata.to_account_info(), // Source: treasury token account
+                to: self.destination.to_account_info(), // Destination chosen by admin
+                authority: self.treasury.to_account_info(), // Treasury PDA signs
+                mint: self.payment_mint.to_account_info(), // Payment mint
+            },
+            signer_seeds,
+        );
+        transfer_checked(ctx, amount, self.payment_mint.decimals)?;
+
+        emit!(TreasuryWithdrawn {
+            marketplace: marketplace_key,
+            mint: Some(self.payment_mint.key()),
+            destination: self.destination.key(),
+            amount,
+            remaining: available - amount,
+        });
+
+        Ok(()) // Return success
+    }
+}
\ No newline at end of file
@@ -1,12 +1,23 @@
 #![allow(unexpected_cfgs)] // Suppress warnings about unexpected configuration flags
 #![allow(deprecated)] // Suppress warnings about deprecated features
 
+pub mod bundle; // Module containing shared bundle escrow logic
+pub mod compressed_nft; // Module containing Bubblegum compressed NFT parsing and transfers
 pub mod constants; // Module containing program constants
+pub mod core_asset; // Module containing Metaplex Core asset parsing and transfers
+pub mod edition; // Module containing Metaplex master and print edition parsing
 pub mod error; // Module containing custom error definitions
+pub mod events; // Module containing emitted event definitions
 pub mod instructions; // Module containing all instruction handlers
+pub mod nft_metadata; // Module containing Metaplex metadata reads for NFTs that may not have any
+pub mod nft_transfer; // Module containing shared NFT transfer logic, including pNFTs
+pub mod settlement; // Module containing shared sale payment logic
 pub mod state; // Module containing account state structures
+pub mod token_extensions; // Module containing Token-2022 mint extension checks
 
 use anchor_lang::prelude::*; // Import essential Anchor framework components
+use nft_metadata::NftMetadata; // Reads Metaplex metadata that Token-2022 NFTs may not have
+use settlement::Royalties; // Splits remaining accounts into creators and transfer hook accounts
 
 pub use constants::*; // Re-export all constants for easy access
 pub use instructions::*; // Re-export all instruction structs
@@ -31,14 +42,20 @@ pub mod marketplace {
     // - ctx: Contains all accounts needed for initialization
     // - name: Human-readable name for the marketplace (e.g., "SuperNFT Market")
     // - fee: Percentage fee charged on sales (in basis points, e.g., 250 = 2.5%)
+    // - max_fee: Highest fee the admin may ever set (basis points, at most 5000)
     //
     // Returns: Result indicating success or failure
     // ========================================================================
-    pub fn init_marketplace(ctx: Context<Initialize>, name: String, fee: u16) -> Result<()> {
+    pub fn init_marketplace(
+        ctx: Context<Initialize>,
+        name: String,
+        fee: u16,
+        max_fee: u16,
+    ) -> Result<()> {
         // Delegate to the Initialize struct's init method
         // ctx.accounts contains all the accounts defined in the Initialize struct
         // &ctx.bumps contains the bump seeds for any PDAs created
-        ctx.accounts.init(name, fee, &ctx.bumps)
+        ctx.accounts.init(name, fee, max_fee, &ctx.bumps)
     }
 
     // ========================================================================
@@ -49,18 +66,126 @@ pub mod marketplace {
     //
     // Parameters:
     // - ctx: Contains seller account, NFT mint, marketplace, vault, etc.
-    // - price: Sale price in lamports (1 SOL = 1,000,000,000 lamports)
+    //   Token-2022 NFTs without Metaplex metadata leave out the edition, still pass
+    //   the (empty) metadata PDA, and pass their transfer hook accounts as remaining accounts
+    // - price: Sale price in lamports (1 SOL = 1,000,000,000 lamports),
+    //   or in base units of the payment mint
+    // - payment_mint: Accepted SPL mint to price in, or None for SOL
+    // - expires_at: Unix timestamp the listing stops being buyable, or None
     //
     // Returns: Result indicating success or failure
     // ========================================================================
-    pub fn listing(ctx: Context<List>, price: u64) -> Result<()> {
+    pub fn listing<'info>(
+        ctx: Context<'_, '_, 'info, 'info, List<'info>>,
+        price: u64,
+        payment_mint: Option<Pubkey>,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        // Metaplex metadata, if the NFT has any (Token-2022 group members may not)
+        let metadata = NftMetadata::try_from_account(&ctx.accounts.metadata)?;
+
         // First, create the listing account with price and seller information
         // This must succeed before transferring the NFT to ensure atomicity
-        ctx.accounts.list(price, &ctx.bumps)?; // ? operator propagates errors
+        ctx.accounts.list(metadata.as_ref(), price, payment_mint, expires_at, &ctx.bumps)?; // ? operator propagates errors
 
         // Then transfer the NFT from seller to the marketplace vault
         // The vault is controlled by the listing PDA for security
-        ctx.accounts.deposit_nft()
+        ctx.accounts.deposit_nft(metadata.as_ref(), ctx.remaining_accounts)
+    }
+
+    // ========================================================================
+    // LIST NFT AS DUTCH AUCTION INSTRUCTION
+    // ========================================================================
+    // Same as listing, but the price falls from a start price to a floor
+    // over a time window, linearly or exponentially
+    //
+    // Parameters:
+    // - ctx: Same accounts as listing
+    // - dutch_auction: Start/floor price, start/end time and decay curve
+    // - payment_mint: Accepted SPL mint to price in, or None for SOL
+    // - expires_at: Unix timestamp the listing stops being buyable, or None
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn list_dutch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, List<'info>>,
+        dutch_auction: DutchAuction,
+        payment_mint: Option<Pubkey>,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        // Metaplex metadata, if the NFT has any (Token-2022 group members may not)
+        let metadata = NftMetadata::try_from_account(&ctx.accounts.metadata)?;
+
+        // First, create the listing account with the declining price schedule
+        ctx.accounts.list_dutch(metadata.as_ref(), dutch_auction, payment_mint, expires_at, &ctx.bumps)?;
+
+        // Then transfer the NFT from seller to the marketplace vault
+        ctx.accounts.deposit_nft(metadata.as_ref(), ctx.remaining_accounts)
+    }
+
+    // ========================================================================
+    // LIST QUANTITY INSTRUCTION
+    // ========================================================================
+    // Lists a quantity of a fungible or semi-fungible token (SFT, Token-2022
+    // multi-supply) at a price per whole token
+    // Buyers can purchase part of it; the listing closes once it is sold out
+    // The metadata token standard must be Fungible or FungibleAsset; NFTs,
+    // including print editions, are listed with `listing`
+    //
+    // Parameters:
+    // - ctx: Contains seller account, mint, marketplace, vault, metadata, etc.
+    // - price: Price per whole token in lamports or payment mint base units
+    // - quantity: Base units of the token to list
+    // - payment_mint: Accepted SPL mint to price in, or None for SOL
+    // - expires_at: Unix timestamp the listing stops being buyable, or None
+    // - remaining accounts: Transfer hook accounts of a Token-2022 mint, if any
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn list_quantity<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ListQuantity<'info>>,
+        price: u64,
+        quantity: u64,
+        payment_mint: Option<Pubkey>,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        // First, create the listing account with price, quantity and seller information
+        ctx.accounts.list_quantity(price, quantity, payment_mint, expires_at, &ctx.bumps)?;
+
+        // Then escrow the listed quantity in the marketplace vault
+        ctx.accounts.deposit_tokens(ctx.remaining_accounts)
+    }
+
+    // ========================================================================
+    // LIST DELEGATED NFT INSTRUCTION
+    // ========================================================================
+    // Lists an NFT without escrow: it stays in the seller's token account and
+    // the listing PDA is approved as delegate, so purchase can move it
+    // Revoking the delegation or moving the NFT makes the listing unbuyable
+    //
+    // Parameters:
+    // - ctx: Contains seller, listing, NFT mint, seller's token account, metadata, etc.
+    //   The master edition is only needed with freeze
+    // - price: Sale price in lamports or payment mint base units
+    // - payment_mint: Accepted SPL mint to price in, or None for SOL
+    // - expires_at: Unix timestamp the listing stops being buyable, or None
+    // - freeze: Freeze the NFT through Token Metadata until it is sold or delisted
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn list_delegated(
+        ctx: Context<ListDelegated>,
+        price: u64,
+        payment_mint: Option<Pubkey>,
+        expires_at: Option<i64>,
+        freeze: bool,
+    ) -> Result<()> {
+        // First, create the listing with the same checks as an escrowed one
+        ctx.accounts
+            .list_delegated(price, payment_mint, expires_at, freeze, &ctx.bumps)?;
+
+        // Then approve the listing PDA on the seller's token account
+        ctx.accounts.delegate_nft()
     }
 
     // ========================================================================
@@ -68,22 +193,89 @@ pub mod marketplace {
     // ========================================================================
     // Allows the original seller to cancel their listing and get their NFT back
     // This closes the listing account and vault, refunding rent to the seller
+    // Delegated listings are thawed if needed and their delegation revoked instead
     //
     // Parameters:
     // - ctx: Contains seller account, listing, vault, marketplace, etc.
+    //   The vault is left out for delegated listings
+    //   Remaining accounts are the transfer hook accounts of a Token-2022 mint
     //
     // Returns: Result indicating success or failure
     // ========================================================================
-    pub fn delisting(ctx: Context<Delist>) -> Result<()> {
+    pub fn delisting<'info>(ctx: Context<'_, '_, 'info, 'info, Delist<'info>>) -> Result<()> {
         // First, transfer the NFT back from vault to seller's token account
         // Must happen before closing accounts to avoid losing the NFT
-        ctx.accounts.withdraw_nft()?; // ? operator propagates errors
+        ctx.accounts.withdraw_nft(ctx.remaining_accounts)?; // ? operator propagates errors
 
         // Then close the vault account, sending remaining lamports to seller
         // This cleans up the marketplace and refunds rent
         ctx.accounts.close_account()
     }
 
+    // ========================================================================
+    // CRANK EXPIRED LISTING INSTRUCTION
+    // ========================================================================
+    // Lets anyone clean up a listing past its expiry
+    // The NFT goes back to the seller and the vault and listing rent is
+    // refunded to the seller, exactly as if they had delisted
+    // Delegated listings only thaw the NFT, which never left the seller
+    //
+    // Parameters:
+    // - ctx: Contains cranker, seller, listing, vault, marketplace, etc.
+    //   The vault is left out for delegated listings
+    //   Remaining accounts are the transfer hook accounts of a Token-2022 mint
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn crank_expired<'info>(ctx: Context<'_, '_, 'info, 'info, CrankExpired<'info>>) -> Result<()> {
+        // Return the NFT from the vault to the seller's token account
+        ctx.accounts.return_nft(ctx.remaining_accounts)?;
+
+        // Then close the vault, sending its rent to the seller
+        ctx.accounts.close_vault()
+    }
+
+    // ========================================================================
+    // CLOSE DEAD LISTING INSTRUCTION
+    // ========================================================================
+    // Lets anyone clean up a delegated listing that can no longer be bought,
+    // because the seller revoked the delegation or moved the NFT
+    // The listing rent goes back to the seller
+    //
+    // Parameters:
+    // - ctx: Contains closer, seller, listing, mint, the seller's ATA and marketplace
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn close_dead_listing(ctx: Context<CloseDeadListing>) -> Result<()> {
+        ctx.accounts.close_dead_listing()
+    }
+
+    // ========================================================================
+    // UPDATE LISTING INSTRUCTION
+    // ========================================================================
+    // Allows the seller to change the price and expiry of a listing in place
+    // Avoids delisting and relisting, which would pay account rent twice
+    //
+    // Parameters:
+    // - ctx: Contains seller account, listing, NFT mint or Core asset and marketplace
+    // - price: New sale price in lamports or payment mint base units
+    // - payment_mint: Accepted SPL mint to price in, or None for SOL (Core listings
+    //   must stay in SOL)
+    // - expires_at: New Unix timestamp after which the listing can't be bought,
+    //   or None to keep it open until sold or delisted
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn update_listing(
+        ctx: Context<UpdateListing>,
+        price: u64,
+        payment_mint: Option<Pubkey>,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts.update(price, payment_mint, expires_at)
+    }
+
     // ========================================================================
     // PURCHASE NFT INSTRUCTION
     // ========================================================================
@@ -92,20 +284,719 @@ pub mod marketplace {
     //
     // Parameters:
     // - ctx: Contains buyer, seller, marketplace, listing, vault, etc.
+    //   Delegated listings pass the seller's token account instead of the vault
+    //   Remaining accounts are the verified creators from the NFT metadata, in order,
+    //   then the transfer hook accounts of a Token-2022 mint
+    // - expected_price: Price per whole token the buyer was shown; must match
+    //   a fixed price exactly and caps a Dutch listing's current price
+    // - expected_seller: Seller the buyer was shown, so a relist can't be bought by mistake
+    // - quantity: Base units to buy (1 for an NFT); the rest stays listed
     //
     // Returns: Result indicating success or failure
     // ========================================================================
-    pub fn purchase(ctx: Context<Purchase>) -> Result<()> {
-        // First, handle all payment transfers (buyer -> seller, marketplace fee)
+    pub fn purchase<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Purchase<'info>>,
+        expected_price: u64,
+        expected_seller: Pubkey,
+        quantity: u64,
+    ) -> Result<()> {
+        // First, handle all payment transfers (buyer -> seller, creators, marketplace fee)
         // Payment must be completed before NFT transfer for security
-        ctx.accounts.transfer_amounts()?; // ? operator propagates errors
+        let (creators, hook_accounts) = Royalties::from_optional_metadata(ctx.accounts.metadata.as_deref())
+            .split_accounts(ctx.remaining_accounts);
+        let total = ctx.accounts.transfer_amounts(
+            expected_price,
+            expected_seller,
+            quantity,
+            creators,
+        )?; // ? operator propagates errors
 
-        // Then transfer the NFT from vault to buyer's token account
-        // Buyer now owns the NFT after successful payment
-        ctx.accounts.transfer_nft()?; // ? operator propagates errors
+        // Then transfer the bought quantity from vault to buyer's token account
+        // Buyer now owns it after successful payment
+        ctx.accounts.transfer_nft(quantity, hook_accounts)?; // ? operator propagates errors
 
-        // Finally, close the empty vault account to clean up and refund rent
-        // This completes the purchase and cleans up marketplace state
-        ctx.accounts.close_vault()
+        // Reward both sides of the trade with marketplace loyalty tokens
+        ctx.accounts.mint_rewards(total)?; // ? operator propagates errors
+
+        // Finally, once nothing is left, close the vault and listing to refund rent
+        // so the same mint can be listed again later
+        ctx.accounts.close_if_sold_out()
+    }
+
+    // ========================================================================
+    // LIST BUNDLE INSTRUCTION
+    // ========================================================================
+    // Lists several NFTs from one verified collection as a single lot
+    // Each NFT is escrowed in its own vault owned by the bundle PDA
+    //
+    // Parameters:
+    // - ctx: Contains seller, marketplace, bundle listing and collection mint
+    //   Remaining accounts are, per NFT: mint, metadata, edition, seller's token account,
+    //   its token record, vault, vault token record, rule set (pNFT-only accounts and
+    //   the edition of Token-2022 group members are replaced by the program ID), then
+    //   the transfer hook accounts of any Token-2022 mint with a hook
+    // - bundle_id: Seller-chosen id, so one seller can list several bundles
+    // - price: Price for the whole bundle in lamports, which must be inside the
+    //   collection's price range as a total, not per NFT
+    // - nft_count: Number of NFTs in the bundle, whose accounts come before the hook accounts
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn list_bundle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ListBundle<'info>>,
+        bundle_id: u64,
+        price: u64,
+        nft_count: u8,
+    ) -> Result<()> {
+        ctx.accounts
+            .list_bundle(bundle_id, price, nft_count, ctx.remaining_accounts, &ctx.bumps)
+    }
+
+    // ========================================================================
+    // PURCHASE BUNDLE INSTRUCTION
+    // ========================================================================
+    // Buys every NFT in a bundle with a single payment
+    // Fee and royalties are paid once on the bundle price, royalties following
+    // the first NFT's metadata, whose terms list_bundle requires every NFT to share
+    // Bundle sales mint no loyalty rewards; only purchase does
+    //
+    // Parameters:
+    // - ctx: Contains buyer, seller, marketplace, bundle listing, treasury, etc.
+    //   Remaining accounts are, per NFT: mint, metadata, edition, vault, vault token
+    //   record, buyer's token account, its token record, rule set (pNFT-only accounts
+    //   are replaced by the program ID for regular NFTs), then the verified creators
+    //   from the first NFT's metadata, in order, then the transfer hook accounts of
+    //   any Token-2022 mint with a hook
+    // - expected_price: Bundle price the buyer was shown
+    // - expected_seller: Seller the buyer was shown
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn purchase_bundle<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PurchaseBundle<'info>>,
+        expected_price: u64,
+        expected_seller: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts
+            .purchase_bundle(expected_price, expected_seller, ctx.remaining_accounts)
+    }
+
+    // ========================================================================
+    // DELIST BUNDLE INSTRUCTION
+    // ========================================================================
+    // Allows the seller to cancel a bundle and get every NFT back
+    // Closes the vaults and bundle listing, refunding rent to the seller
+    //
+    // Parameters:
+    // - ctx: Contains seller, marketplace and bundle listing
+    //   Remaining accounts are, per NFT: mint, metadata, edition, vault, vault token
+    //   record, seller's token account, its token record, rule set (pNFT-only accounts
+    //   are replaced by the program ID for regular NFTs), then the transfer hook
+    //   accounts of any Token-2022 mint with a hook
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn delist_bundle<'info>(ctx: Context<'_, '_, 'info, 'info, DelistBundle<'info>>) -> Result<()> {
+        ctx.accounts.delist_bundle(ctx.remaining_accounts)
+    }
+
+    // ========================================================================
+    // LIST CORE ASSET INSTRUCTION
+    // ========================================================================
+    // Lists a Metaplex Core asset from a Core collection for SOL
+    // The asset is escrowed by making the listing PDA its owner
+    //
+    // Parameters:
+    // - ctx: Contains seller, listing, asset, collection and the Core program
+    // - price: Sale price in lamports
+    // - expires_at: Unix timestamp the listing stops being buyable, or None
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn list_core(ctx: Context<ListCore>, price: u64, expires_at: Option<i64>) -> Result<()> {
+        // First, check the asset and collection and create the listing
+        ctx.accounts.list_core(price, expires_at, &ctx.bumps)?;
+
+        // Then hand the asset over to the listing PDA
+        ctx.accounts.deposit_asset()
+    }
+
+    // ========================================================================
+    // PURCHASE CORE ASSET INSTRUCTION
+    // ========================================================================
+    // Buys a listed Core asset; fee and royalties are settled as for NFTs,
+    // with royalties read from the asset's or collection's royalties plugin
+    // Core sales mint no loyalty rewards; only purchase does
+    //
+    // Parameters:
+    // - ctx: Contains buyer, seller, listing, asset, collection, treasury, etc.
+    //   Remaining accounts are the royalty creators, in order
+    // - expected_price: Price the buyer was shown
+    // - expected_seller: Seller the buyer was shown
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn purchase_core<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PurchaseCore<'info>>,
+        expected_price: u64,
+        expected_seller: Pubkey,
+    ) -> Result<()> {
+        // Pay the seller, creators and treasury first
+        ctx.accounts
+            .purchase_core(expected_price, expected_seller, ctx.remaining_accounts)?;
+
+        // Then move the asset to the buyer; the listing closes to the seller
+        ctx.accounts.transfer_asset()
+    }
+
+    // ========================================================================
+    // DELIST CORE ASSET INSTRUCTION
+    // ========================================================================
+    // Allows the seller to cancel a Core listing and get the asset back
+    //
+    // Parameters:
+    // - ctx: Contains seller, listing, asset, collection and the Core program
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn delist_core(ctx: Context<DelistCore>) -> Result<()> {
+        ctx.accounts.withdraw_asset()
+    }
+
+    // ========================================================================
+    // CRANK EXPIRED CORE LISTING INSTRUCTION
+    // ========================================================================
+    // Lets anyone clean up a Core listing past its expiry
+    // The asset and the listing rent go back to the seller
+    //
+    // Parameters:
+    // - ctx: Contains cranker, seller, listing, asset, collection and the Core program
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn crank_expired_core(ctx: Context<CrankExpiredCore>) -> Result<()> {
+        ctx.accounts.return_asset()
+    }
+
+    // ========================================================================
+    // LIST COMPRESSED NFT INSTRUCTION
+    // ========================================================================
+    // Lists a Bubblegum compressed NFT from a verified collection for SOL
+    // The leaf is escrowed by transferring it to the listing PDA, which keeps
+    // the asset id, tree and leaf hashes needed to move it again
+    //
+    // Parameters:
+    // - ctx: Contains seller, leaf delegate, listing, merkle tree and programs
+    //   Remaining accounts are the proof nodes not covered by the canopy
+    // - nonce: Leaf nonce (the asset id is derived from tree and nonce)
+    // - index: Leaf index in the tree
+    // - root: Current root of the merkle tree
+    // - metadata_args: Borsh-serialized Bubblegum MetadataArgs of the NFT
+    // - price: Sale price in lamports
+    // - expires_at: Unix timestamp the listing stops being buyable, or None
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn list_compressed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ListCompressed<'info>>,
+        nonce: u64,
+        index: u32,
+        root: [u8; 32],
+        metadata_args: Vec<u8>,
+        price: u64,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        ctx.accounts.list_compressed(
+            nonce,
+            index,
+            root,
+            &metadata_args,
+            price,
+            expires_at,
+            ctx.remaining_accounts,
+            &ctx.bumps,
+        )
+    }
+
+    // ========================================================================
+    // PURCHASE COMPRESSED NFT INSTRUCTION
+    // ========================================================================
+    // Buys a listed compressed NFT; fee and royalties are settled as for NFTs,
+    // with royalties read from the metadata the leaf was listed with
+    // Compressed sales mint no loyalty rewards; only purchase does
+    //
+    // Parameters:
+    // - ctx: Contains buyer, seller, listing, treasury, merkle tree and programs
+    //   Remaining accounts are the verified creators, in order, then the proof nodes
+    // - root: Current root of the merkle tree
+    // - metadata_args: Borsh-serialized Bubblegum MetadataArgs of the NFT
+    // - expected_price: Price the buyer was shown
+    // - expected_seller: Seller the buyer was shown
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn purchase_compressed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, PurchaseCompressed<'info>>,
+        root: [u8; 32],
+        metadata_args: Vec<u8>,
+        expected_price: u64,
+        expected_seller: Pubkey,
+    ) -> Result<()> {
+        ctx.accounts.purchase_compressed(
+            root,
+            &metadata_args,
+            expected_price,
+            expected_seller,
+            ctx.remaining_accounts,
+        )
+    }
+
+    // ========================================================================
+    // DELIST COMPRESSED NFT INSTRUCTION
+    // ========================================================================
+    // Allows the seller to cancel a compressed listing and get the leaf back
+    //
+    // Parameters:
+    // - ctx: Contains seller, listing, merkle tree and programs
+    //   Remaining accounts are the proof nodes not covered by the canopy
+    // - root: Current root of the merkle tree
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn delist_compressed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DelistCompressed<'info>>,
+        root: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.withdraw_compressed(root, ctx.remaining_accounts)
+    }
+
+    // ========================================================================
+    // CRANK EXPIRED COMPRESSED LISTING INSTRUCTION
+    // ========================================================================
+    // Lets anyone clean up a compressed listing past its expiry
+    // The leaf and the listing rent go back to the seller
+    //
+    // Parameters:
+    // - ctx: Contains cranker, seller, listing, merkle tree and programs
+    //   Remaining accounts are the proof nodes not covered by the canopy
+    // - root: Current root of the merkle tree
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn crank_expired_compressed<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CrankExpiredCompressed<'info>>,
+        root: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.return_compressed(root, ctx.remaining_accounts)
+    }
+
+    // ========================================================================
+    // MAKE OFFER INSTRUCTION
+    // ========================================================================
+    // Allows a buyer to bid on any NFT, listed or not, by escrowing the bid
+    // SOL bids are held by the offer PDA, token bids by its associated token account
+    //
+    // Parameters:
+    // - ctx: Contains buyer, marketplace, NFT mint, offer and payment accounts
+    // - price: Bid in lamports or payment mint base units
+    // - payment_mint: Accepted SPL mint to bid in, or None for SOL
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn make_offer(ctx: Context<MakeOffer>, price: u64, payment_mint: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.make(price, payment_mint, &ctx.bumps)
+    }
+
+    // ========================================================================
+    // CANCEL OFFER INSTRUCTION
+    // ========================================================================
+    // Allows the buyer to withdraw their offer and get the escrowed bid back
+    //
+    // Parameters:
+    // - ctx: Contains buyer, marketplace, NFT mint, offer and payment accounts
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
+        ctx.accounts.refund()
+    }
+
+    // ========================================================================
+    // ACCEPT OFFER INSTRUCTION
+    // ========================================================================
+    // Allows the NFT holder to sell into an offer
+    // The NFT comes from the listing vault if listed, otherwise the holder's wallet
+    // Offer sales mint no loyalty rewards; only purchase does
+    //
+    // Parameters:
+    // - ctx: Contains seller, buyer, offer, collection config, NFT accounts and payment accounts
+    //   Token-2022 NFTs without metadata still pass its (empty) PDA; pNFTs pass
+    //   their edition, token records and rule set
+    //   Remaining accounts are the verified creators from the NFT metadata, in order,
+    //   then the transfer hook accounts of a Token-2022 mint
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn accept_offer<'info>(ctx: Context<'_, '_, 'info, 'info, AcceptOffer<'info>>) -> Result<()> {
+        // Read the metadata once, if the NFT has any
+        let metadata = NftMetadata::try_from_account(&ctx.accounts.metadata)?;
+
+        // The collection config passed in must be the NFT's, since it sets the fee
+        ctx.accounts.verify_collection(metadata.as_ref())?;
+
+        // Pay out of escrow first with the same fee and royalty split as purchase
+        let (creators, hook_accounts) = Royalties::from_optional_metadata(metadata.as_ref())
+            .split_accounts(ctx.remaining_accounts);
+        ctx.accounts.settle(metadata.as_ref(), creators)?;
+
+        // Then move the NFT to the buyer
+        ctx.accounts.transfer_nft(metadata.as_ref(), hook_accounts)
+    }
+
+    // ========================================================================
+    // MAKE COLLECTION OFFER INSTRUCTION
+    // ========================================================================
+    // Allows a buyer to bid on any NFT in a collection (a floor bid)
+    // Escrows price * quantity so every fill is fully funded
+    //
+    // Parameters:
+    // - ctx: Contains buyer, marketplace, collection mint, offer and payment accounts
+    // - price: Bid per NFT in lamports or payment mint base units
+    // - quantity: Number of NFTs the buyer wants
+    // - payment_mint: Accepted SPL mint to bid in, or None for SOL
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn make_collection_offer(
+        ctx: Context<MakeCollectionOffer>,
+        price: u64,
+        quantity: u64,
+        payment_mint: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.make(price, quantity, payment_mint, &ctx.bumps)
+    }
+
+    // ========================================================================
+    // CANCEL COLLECTION OFFER INSTRUCTION
+    // ========================================================================
+    // Allows the buyer to withdraw the unfilled part of a collection offer
+    //
+    // Parameters:
+    // - ctx: Contains buyer, marketplace, collection mint, offer and payment accounts
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn cancel_collection_offer(ctx: Context<CancelCollectionOffer>) -> Result<()> {
+        ctx.accounts.refund()
+    }
+
+    // ========================================================================
+    // ACCEPT COLLECTION OFFER INSTRUCTION
+    // ========================================================================
+    // Allows a holder of a verified collection member to sell into a collection offer
+    // The offer closes once its quantity reaches zero
+    // Collection offer fills mint no loyalty rewards; only purchase does
+    //
+    // Parameters:
+    // - ctx: Contains seller, buyer, offer, NFT accounts and payment accounts
+    //   Token-2022 group members without metadata still pass its (empty) PDA;
+    //   pNFTs pass their edition, token records and rule set
+    //   Remaining accounts are the verified creators from the NFT metadata, in order,
+    //   then the transfer hook accounts of a Token-2022 mint
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn accept_collection_offer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptCollectionOffer<'info>>,
+    ) -> Result<()> {
+        // Read the metadata once, if the NFT has any
+        let metadata = NftMetadata::try_from_account(&ctx.accounts.metadata)?;
+
+        // Only verified members of the collection can fill the offer
+        ctx.accounts.verify_collection(metadata.as_ref())?;
+
+        // Pay one unit out of escrow with the same fee and royalty split as purchase
+        let (creators, hook_accounts) = Royalties::from_optional_metadata(metadata.as_ref())
+            .split_accounts(ctx.remaining_accounts);
+        ctx.accounts.settle(metadata.as_ref(), creators)?;
+
+        // Then move the NFT to the buyer
+        ctx.accounts.transfer_nft(metadata.as_ref(), hook_accounts)
+    }
+
+    // ========================================================================
+    // CREATE AUCTION INSTRUCTION
+    // ========================================================================
+    // Allows an NFT holder to sell their NFT in a timed English auction
+    // The NFT is transferred to a vault controlled by the auction PDA
+    //
+    // Parameters:
+    // - ctx: Contains seller, NFT mint, auction, vault, collection and metadata
+    //   Token-2022 group members without metadata still pass its (empty) PDA;
+    //   pNFTs pass their edition, token records and rule set
+    //   Remaining accounts are the transfer hook accounts of a Token-2022 mint
+    // - reserve_price: Lowest acceptable bid in lamports
+    // - start_time: Unix timestamp bidding opens
+    // - end_time: Unix timestamp bidding closes
+    // - extension_window: Seconds a late bid pushes the end time back by, at most
+    //   the auction's length; late bids add at most MAX_AUCTION_EXTENSIONS windows
+    // - min_bid_increment_bps: Share of the high bid each new bid must beat it by
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn create_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateAuction<'info>>,
+        reserve_price: u64,
+        start_time: i64,
+        end_time: i64,
+        extension_window: i64,
+        min_bid_increment_bps: u16,
+    ) -> Result<()> {
+        // Read the metadata once, if the NFT has any
+        let metadata = NftMetadata::try_from_account(&ctx.accounts.metadata)?;
+
+        // First, create the auction account with reserve, timing and bid increment
+        ctx.accounts.create(
+            metadata.as_ref(),
+            reserve_price,
+            start_time,
+            end_time,
+            extension_window,
+            min_bid_increment_bps,
+            &ctx.bumps,
+        )?;
+
+        // Then transfer the NFT from seller to the auction vault
+        ctx.accounts.deposit_nft(metadata.as_ref(), ctx.remaining_accounts)
+    }
+
+    // ========================================================================
+    // PLACE BID INSTRUCTION
+    // ========================================================================
+    // Allows anyone to outbid the current high bid while the auction is open
+    // The previous high bid is refunded from escrow in the same transaction
+    //
+    // Parameters:
+    // - ctx: Contains bidder, previous bidder, marketplace, NFT mint and auction
+    // - amount: Bid in lamports
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn place_bid(ctx: Context<PlaceBid>, amount: u64) -> Result<()> {
+        ctx.accounts.bid(amount)
+    }
+
+    // ========================================================================
+    // SETTLE AUCTION INSTRUCTION
+    // ========================================================================
+    // Permissionless once the auction has ended
+    // Pays the seller, creators and treasury and sends the NFT to the winner,
+    // or returns the NFT to the seller if nobody bid
+    // Auction sales mint no loyalty rewards; only purchase does
+    //
+    // Parameters:
+    // - ctx: Contains seller, winner, auction, vault, token accounts and metadata
+    //   Remaining accounts are the verified creators from the NFT metadata, in order,
+    //   then the transfer hook accounts of a Token-2022 mint
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn settle_auction<'info>(ctx: Context<'_, '_, 'info, 'info, SettleAuction<'info>>) -> Result<()> {
+        let (creators, hook_accounts) = Royalties::from_optional_metadata(ctx.accounts.metadata.as_deref())
+            .split_accounts(ctx.remaining_accounts);
+        ctx.accounts.settle(creators, hook_accounts)
+    }
+
+    // ========================================================================
+    // UPDATE MARKETPLACE INSTRUCTION
+    // ========================================================================
+    // Allows the admin to change the fee, reward rates and print edition policy
+    // and propose a new admin
+    // A proposed admin only takes control after calling accept_admin
+    //
+    // Parameters:
+    // - ctx: Contains the admin signer and the marketplace account
+    // - fee: New fee in basis points, or None to keep the current fee
+    // - new_admin: Admin to propose, or None to leave the handoff unchanged
+    // - buyer_reward_rate: Reward units per SOL of volume minted to buyers
+    // - seller_reward_rate: Reward units per SOL of volume minted to sellers
+    // - allow_print_editions: Whether print editions may be listed, or None to keep it
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn update_marketplace(
+        ctx: Context<UpdateMarketplace>,
+        fee: Option<u16>,
+        new_admin: Option<Pubkey>,
+        buyer_reward_rate: Option<u64>,
+        seller_reward_rate: Option<u64>,
+        allow_print_editions: Option<bool>,
+    ) -> Result<()> {
+        ctx.accounts.update(
+            fee,
+            new_admin,
+            buyer_reward_rate,
+            seller_reward_rate,
+            allow_print_editions,
+        )
+    }
+
+    // ========================================================================
+    // ACCEPT ADMIN INSTRUCTION
+    // ========================================================================
+    // Completes the two-step admin handoff started by update_marketplace
+    // Must be signed by the proposed admin
+    //
+    // Parameters:
+    // - ctx: Contains the proposed admin signer and the marketplace account
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        ctx.accounts.accept()
+    }
+
+    // ========================================================================
+    // CANCEL ADMIN TRANSFER INSTRUCTION
+    // ========================================================================
+    // Allows the admin to withdraw a proposed admin before they accept
+    // Fails if no handoff is in progress
+    //
+    // Parameters:
+    // - ctx: Contains the admin signer and the marketplace account
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        ctx.accounts.cancel()
+    }
+
+    // ========================================================================
+    // WITHDRAW TREASURY INSTRUCTION
+    // ========================================================================
+    // Allows the admin to recover fees collected in the treasury PDA
+    // The treasury always keeps its rent-exempt minimum
+    //
+    // Parameters:
+    // - ctx: Contains the admin, marketplace, treasury and destination
+    // - amount: Lamports to withdraw, or None for everything above rent
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: Option<u64>) -> Result<()> {
+        ctx.accounts.withdraw(amount)
+    }
+
+    // ========================================================================
+    // WITHDRAW TREASURY TOKENS INSTRUCTION
+    // ========================================================================
+    // Allows the admin to recover fees collected in a payment mint
+    // The fees sit in the treasury PDA's associated token account
+    //
+    // Parameters:
+    // - ctx: Contains the admin, marketplace, treasury token account and destination
+    // - amount: Token base units to withdraw, or None for the whole balance
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn withdraw_treasury_tokens(
+        ctx: Context<WithdrawTreasuryTokens>,
+        amount: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.withdraw(amount)
+    }
+
+    // ========================================================================
+    // ADD PAYMENT MINT INSTRUCTION
+    // ========================================================================
+    // Allows the admin to accept a new SPL/Token-2022 mint for listing prices
+    //
+    // Parameters:
+    // - ctx: Contains the admin, marketplace and the mint to accept
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn add_payment_mint(ctx: Context<AddPaymentMint>) -> Result<()> {
+        ctx.accounts.add()
+    }
+
+    // ========================================================================
+    // REMOVE PAYMENT MINT INSTRUCTION
+    // ========================================================================
+    // Allows the admin to stop accepting a mint for new listings
+    //
+    // Parameters:
+    // - ctx: Contains the admin and marketplace
+    // - mint: Payment mint to remove from the allowlist
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn remove_payment_mint(ctx: Context<RemovePaymentMint>, mint: Pubkey) -> Result<()> {
+        ctx.accounts.remove(mint)
+    }
+
+    // ========================================================================
+    // ADD COLLECTION INSTRUCTION
+    // ========================================================================
+    // Allows the admin to approve a collection for listing
+    // Every listing instruction requires the collection's config to exist
+    //
+    // Parameters:
+    // - ctx: Contains the admin, marketplace and new collection config
+    // - collection: Collection mint, Core collection or Token-2022 group address
+    // - fee: Fee override in basis points, or None for the marketplace fee
+    // - min_price: Lowest listing price allowed, or None
+    // - max_price: Highest listing price allowed, or None
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn add_collection(
+        ctx: Context<AddCollection>,
+        collection: Pubkey,
+        fee: Option<u16>,
+        min_price: Option<u64>,
+        max_price: Option<u64>,
+    ) -> Result<()> {
+        ctx.accounts.add(collection, fee, min_price, max_price, &ctx.bumps)
+    }
+
+    // ========================================================================
+    // UPDATE COLLECTION INSTRUCTION
+    // ========================================================================
+    // Allows the admin to replace a collection's settings
+    // A disabled collection can't be listed or bought, only delisted
+    //
+    // Parameters:
+    // - ctx: Contains the admin, marketplace and collection config
+    // - fee: Fee override in basis points, or None for the marketplace fee
+    // - min_price: Lowest listing price allowed, or None
+    // - max_price: Highest listing price allowed, or None
+    // - enabled: Whether the collection can be listed and bought
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn update_collection(
+        ctx: Context<UpdateCollection>,
+        fee: Option<u16>,
+        min_price: Option<u64>,
+        max_price: Option<u64>,
+        enabled: bool,
+    ) -> Result<()> {
+        ctx.accounts.update(fee, min_price, max_price, enabled)
+    }
+
+    // ========================================================================
+    // REMOVE COLLECTION INSTRUCTION
+    // ========================================================================
+    // Allows the admin to remove a collection and reclaim the config's rent
+    // Live listings in the collection can only be delisted afterwards
+    //
+    // Parameters:
+    // - ctx: Contains the admin, marketplace and collection config to close
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn remove_collection(ctx: Context<RemoveCollection>) -> Result<()> {
+        ctx.accounts.remove()
     }
 }
\ No newline at end of file
@@ -0,0 +1,50 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// Metaplex imports
+use anchor_spl::metadata::{mpl_token_metadata, MetadataAccount};
+use std::ops::Deref;
+
+/// Metaplex metadata of an NFT that may not have any, such as a Token-2022 NFT
+/// using the group/member extensions instead
+/// The metadata PDA is always passed and read here, so a seller can't leave out
+/// metadata the NFT does have to dodge its collection check and royalties
+pub struct NftMetadata<'info> {
+    info: AccountInfo<'info>,  // Metadata PDA, for Token Metadata CPIs
+    metadata: MetadataAccount, // Parsed metadata
+}
+
+impl<'info> NftMetadata<'info> {
+    /// Reads the metadata PDA, None if Token Metadata never created it
+    /// The address must already be checked against the mint's metadata seeds
+    pub fn try_from_account(info: &AccountInfo<'info>) -> Result<Option<Self>> {
+        if info.data_is_empty() {
+            return Ok(None); // Only Token Metadata can create data at its PDA
+        }
+
+        require_keys_eq!(
+            *info.owner,
+            mpl_token_metadata::ID,
+            ErrorCode::AccountOwnedByWrongProgram
+        );
+        let metadata = MetadataAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+
+        Ok(Some(Self {
+            info: info.clone(),
+            metadata,
+        }))
+    }
+}
+
+impl Deref for NftMetadata<'_> {
+    type Target = MetadataAccount;
+    fn deref(&self) -> &Self::Target {
+        &self.metadata
+    }
+}
+
+impl<'info> AsRef<AccountInfo<'info>> for NftMetadata<'info> {
+    fn as_ref(&self) -> &AccountInfo<'info> {
+        &self.info
+    }
+}
\ No newline at end of file
@@ -0,0 +1,271 @@
+// Core Anchor framework imports
+use anchor_lang::{prelude::*, solana_program::program::invoke_signed};
+
+// SPL Token and Metaplex imports
+use anchor_spl::{
+    metadata::{
+        mpl_token_metadata::{
+            instructions::{
+                FreezeDelegatedAccountCpi, FreezeDelegatedAccountCpiAccounts,
+                ThawDelegatedAccountCpi, ThawDelegatedAccountCpiAccounts, TransferV1Cpi,
+                TransferV1CpiAccounts, TransferV1InstructionArgs,
+            },
+            types::TokenStandard,
+        },
+        MetadataAccount,
+    },
+    token_2022::spl_token_2022,
+    token_interface::{close_account, transfer_checked, CloseAccount, TransferChecked},
+};
+use std::ops::Deref;
+
+// Local error imports
+use crate::error::MarketplaceError;
+
+/// Whether the NFT is a programmable NFT, which only Token Metadata can move
+pub fn is_programmable(metadata: &MetadataAccount) -> bool {
+    matches!(
+        metadata.token_standard,
+        Some(TokenStandard::ProgrammableNonFungible | TokenStandard::ProgrammableNonFungibleEdition)
+    )
+}
+
+/// Token Metadata accounts needed on top of a plain transfer to move a programmable NFT
+/// Token records, edition and rules are validated by Token Metadata itself
+pub struct ProgrammableAccounts<'info> {
+    pub metadata_program: AccountInfo<'info>,                    // Token Metadata program
+    pub metadata: AccountInfo<'info>,                            // NFT metadata PDA
+    pub edition: Option<AccountInfo<'info>>,                     // NFT master edition PDA
+    pub token_record: Option<AccountInfo<'info>>,                // Token record of the source account
+    pub destination_token_record: Option<AccountInfo<'info>>,    // Token record of the destination account
+    pub authorization_rules: Option<AccountInfo<'info>>,         // Rule set, if the NFT has one
+    pub authorization_rules_program: Option<AccountInfo<'info>>, // Token Auth Rules program, if the NFT has a rule set
+    pub sysvar_instructions: Option<AccountInfo<'info>>,         // Instructions sysvar
+    pub system_program: AccountInfo<'info>,                      // For token record creation
+    pub associated_token_program: AccountInfo<'info>,            // For destination ATA creation
+}
+
+impl<'info> ProgrammableAccounts<'info> {
+    /// Collects the optional pNFT accounts of an instruction
+    /// None unless `metadata` is present and belongs to a programmable NFT, so
+    /// everything else keeps using a plain token transfer
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_optional<M: ToAccountInfo<'info> + Deref<Target = MetadataAccount>>(
+        metadata_program: &impl ToAccountInfo<'info>,
+        metadata: Option<&M>,
+        edition: Option<&impl ToAccountInfo<'info>>,
+        token_record: Option<&impl ToAccountInfo<'info>>,
+        destination_token_record: Option<&impl ToAccountInfo<'info>>,
+        authorization_rules: Option<&impl ToAccountInfo<'info>>,
+        authorization_rules_program: Option<&impl ToAccountInfo<'info>>,
+        sysvar_instructions: Option<&impl ToAccountInfo<'info>>,
+        system_program: &impl ToAccountInfo<'info>,
+        associated_token_program: &impl ToAccountInfo<'info>,
+    ) -> Option<Self> {
+        let metadata = metadata.filter(|metadata| is_programmable(metadata))?;
+        Some(Self {
+            metadata_program: metadata_program.to_account_info(),
+            metadata: metadata.to_account_info(),
+            edition: edition.map(|a| a.to_account_info()),
+            token_record: token_record.map(|a| a.to_account_info()),
+            destination_token_record: destination_token_record.map(|a| a.to_account_info()),
+            authorization_rules: authorization_rules.map(|a| a.to_account_info()),
+            authorization_rules_program: authorization_rules_program.map(|a| a.to_account_info()),
+            sysvar_instructions: sysvar_instructions.map(|a| a.to_account_info()),
+            system_program: system_program.to_account_info(),
+            associated_token_program: associated_token_program.to_account_info(),
+        })
+    }
+}
+
+/// Moves an NFT between token accounts, through Token Metadata when it is programmable
+pub struct NftTransfer<'a, 'info> {
+    pub from: AccountInfo<'info>,          // Source token account
+    pub from_owner: AccountInfo<'info>,    // Owner of the source token account
+    pub to: AccountInfo<'info>,            // Destination token account
+    pub to_owner: AccountInfo<'info>,      // Owner of the destination token account
+    pub authority: AccountInfo<'info>,     // Signs the transfer (wallet or listing PDA)
+    pub payer: AccountInfo<'info>,         // Pays for anything Token Metadata creates
+    pub mint: AccountInfo<'info>,          // NFT mint
+    pub token_program: AccountInfo<'info>, // Token program of the NFT mint
+    pub signer_seeds: &'a [&'a [&'a [u8]]], // PDA seeds when `authority` is a PDA
+    pub extra_accounts: &'a [AccountInfo<'info>], // Transfer hook accounts of a Token-2022 mint
+}
+
+impl<'a, 'info> NftTransfer<'a, 'info> {
+    /// Transfers `amount` with `decimals`; `programmable` is Some for pNFTs,
+    /// which go through Token Metadata's TransferV1 instead of transfer_checked
+    pub fn transfer(
+        &self,
+        amount: u64,
+        decimals: u8,
+        programmable: Option<&ProgrammableAccounts<'info>>,
+    ) -> Result<()> {
+        let Some(programmable) = programmable else {
+            if !self.extra_accounts.is_empty() {
+                return self.transfer_with_hook(amount, decimals);
+            }
+
+            let ctx = CpiContext::new_with_signer(
+                self.token_program.clone(),
+                TransferChecked {
+                    from: self.from.clone(), // Source token account
+                    to: self.to.clone(), // Destination token account
+                    authority: self.authority.clone(), // Owner or listing PDA
+                    mint: self.mint.clone(), // NFT mint
+                },
+                self.signer_seeds,
+            );
+            return transfer_checked(ctx, amount, decimals);
+        };
+
+        // pNFT transfers always need the edition, both token records and the sysvar
+        let missing = || error!(MarketplaceError::MissingProgrammableAccount);
+        let edition = programmable.edition.as_ref().ok_or_else(missing)?;
+        let token_record = programmable.token_record.as_ref().ok_or_else(missing)?;
+        let destination_token_record = programmable
+            .destination_token_record
+            .as_ref()
+            .ok_or_else(missing)?;
+        let sysvar_instructions = programmable
+            .sysvar_instructions
+            .as_ref()
+            .ok_or_else(missing)?;
+
+        TransferV1Cpi::new(
+            &programmable.metadata_program,
+            TransferV1CpiAccounts {
+                token: &self.from,
+                token_owner: &self.from_owner,
+                destination_token: &self.to,
+                destination_owner: &self.to_owner,
+                mint: &self.mint,
+                metadata: &programmable.metadata,
+                edition: Some(edition),
+                token_record: Some(token_record),
+                destination_token_record: Some(destination_token_record),
+                authority: &self.authority,
+                payer: &self.payer,
+                system_program: &programmable.system_program,
+                sysvar_instructions,
+                spl_token_program: &self.token_program,
+                spl_ata_program: &programmable.associated_token_program,
+                authorization_rules_program: programmable.authorization_rules_program.as_ref(),
+                authorization_rules: programmable.authorization_rules.as_ref(),
+            },
+            TransferV1InstructionArgs {
+                amount,
+                authorization_data: None, // Rule sets that need payloads aren't supported
+            },
+        )
+        .invoke_signed(self.signer_seeds)
+        .map_err(Into::into)
+    }
+
+    /// Token-2022 transfer_checked with the mint's transfer hook accounts appended
+    /// Token-2022 hands these on to the hook program, so they must be the extra
+    /// accounts from the validation account, the hook program and the validation
+    /// account itself, as resolved by the client
+    fn transfer_with_hook(&self, amount: u64, decimals: u8) -> Result<()> {
+        let mut ix = spl_token_2022::instruction::transfer_checked(
+            self.token_program.key,
+            self.from.key,
+            self.mint.key,
+            self.to.key,
+            self.authority.key,
+            &[], // No multisig signers
+            amount,
+            decimals,
+        )?;
+        ix.accounts.extend(self.extra_accounts.iter().map(|account| {
+            if account.is_writable {
+                AccountMeta::new(account.key(), false)
+            } else {
+                AccountMeta::new_readonly(account.key(), false)
+            }
+        }));
+
+        let mut account_infos = vec![
+            self.from.clone(),
+            self.mint.clone(),
+            self.to.clone(),
+            self.authority.clone(),
+            self.token_program.clone(),
+        ];
+        account_infos.extend_from_slice(self.extra_accounts);
+
+        invoke_signed(&ix, &account_infos, self.signer_seeds).map_err(Into::into)
+    }
+}
+
+/// Closes an emptied NFT vault and sends its rent to `destination`
+/// Token Metadata closes a pNFT vault itself when it moves the NFT out,
+/// so a vault that is already gone is left alone
+pub fn close_vault<'info>(
+    vault: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if vault.lamports() == 0 {
+        return Ok(());
+    }
+
+    let ctx = CpiContext::new_with_signer(
+        token_program,
+        CloseAccount {
+            account: vault, // Vault account to close
+            destination, // Receives the vault rent
+            authority, // Listing, auction or bundle PDA owning the vault
+        },
+        signer_seeds,
+    );
+    close_account(ctx)
+}
+
+/// Token account of a delegated listing that the listing PDA can freeze and thaw
+/// through Token Metadata, which holds the freeze authority of Metaplex NFTs
+pub struct DelegatedNft<'a, 'info> {
+    pub metadata_program: AccountInfo<'info>, // Token Metadata program
+    pub delegate: AccountInfo<'info>,         // Listing PDA, the approved delegate
+    pub token_account: AccountInfo<'info>,    // Seller's token account
+    pub edition: AccountInfo<'info>,          // Master edition, the mint's freeze authority
+    pub mint: AccountInfo<'info>,             // NFT mint
+    pub token_program: AccountInfo<'info>,    // Token program of the NFT mint
+    pub signer_seeds: &'a [&'a [&'a [u8]]],   // Listing PDA seeds
+}
+
+impl<'a, 'info> DelegatedNft<'a, 'info> {
+    /// Freezes the token account so the seller can't move the NFT or revoke the listing
+    pub fn freeze(&self) -> Result<()> {
+        FreezeDelegatedAccountCpi::new(
+            &self.metadata_program,
+            FreezeDelegatedAccountCpiAccounts {
+                delegate: &self.delegate,
+                token_account: &self.token_account,
+                edition: &self.edition,
+                mint: &self.mint,
+                token_program: &self.token_program,
+            },
+        )
+        .invoke_signed(self.signer_seeds)
+        .map_err(Into::into)
+    }
+
+    /// Thaws a token account frozen by `freeze`
+    pub fn thaw(&self) -> Result<()> {
+        ThawDelegatedAccountCpi::new(
+            &self.metadata_program,
+            ThawDelegatedAccountCpiAccounts {
+                delegate: &self.delegate,
+                token_account: &self.token_account,
+                edition: &self.edition,
+                mint: &self.mint,
+                token_program: &self.token_program,
+            },
+        )
+        .invoke_signed(self.signer_seeds)
+        .map_err(Into::into)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,262 @@
+// Core Anchor framework imports
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+
+// SPL Token and Metaplex imports
+use anchor_spl::{
+    metadata::MetadataAccount,
+    token_interface::{transfer_checked, Mint, TokenAccount, TransferChecked},
+};
+use std::ops::Deref;
+
+// Local error imports
+use crate::error::MarketplaceError;
+
+/// Where the money for a sale comes from
+pub enum Payer<'a, 'info> {
+    /// A signing wallet, debited through the System Program
+    Wallet {
+        from: AccountInfo<'info>,           // Buyer's wallet
+        system_program: AccountInfo<'info>, // System Program for the transfer
+    },
+    /// A program-owned escrow account (e.g. an offer PDA), debited directly
+    Escrow {
+        from: AccountInfo<'info>, // Escrow holding lamports above its rent
+    },
+    /// A token account, debited with transfer_checked
+    Token {
+        from: AccountInfo<'info>,                // Token account paying
+        authority: AccountInfo<'info>,           // Owner or PDA authority of `from`
+        mint: AccountInfo<'info>,                // Payment mint
+        decimals: u8,                            // Payment mint decimals
+        token_program: AccountInfo<'info>,       // Token program of the payment mint
+        signer_seeds: &'a [&'a [&'a [u8]]],     // PDA seeds when `authority` is a PDA
+    },
+}
+
+impl<'a, 'info> Payer<'a, 'info> {
+    /// Moves `amount` to `to`, which is a wallet for SOL and a token account for tokens
+    pub fn pay(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        match self {
+            Payer::Wallet { from, system_program } => {
+                let ctx = CpiContext::new(
+                    system_program.clone(),
+                    Transfer {
+                        from: from.clone(), // Source: buyer's wallet
+                        to, // Destination wallet
+                    },
+                );
+                transfer(ctx, amount)
+            }
+            Payer::Escrow { from } => {
+                // Program-owned accounts can't use the System Program, so move lamports directly
+                from.sub_lamports(amount)?;
+                to.add_lamports(amount)?;
+                Ok(())
+            }
+            Payer::Token {
+                from,
+                authority,
+                mint,
+                decimals,
+                token_program,
+                signer_seeds,
+            } => {
+                let ctx = CpiContext::new_with_signer(
+                    token_program.clone(),
+                    TransferChecked {
+                        from: from.clone(), // Source token account
+                        to, // Destination token account
+                        authority: authority.clone(), // Owner or PDA authority
+                        mint: mint.clone(), // Payment mint
+                    },
+                    signer_seeds,
+                );
+                transfer_checked(ctx, amount, *decimals)
+            }
+        }
+    }
+}
+
+/// Breakdown of where a sale's price went
+pub struct Settlement {
+    pub marketplace_fee: u64, // Sent to the treasury
+    pub royalty: u64,         // Sent to verified creators
+    pub seller_proceeds: u64, // Sent to the seller
+}
+
+/// Royalty terms of an asset: basis points of the price, split across creators
+#[derive(Default, PartialEq)]
+pub struct Royalties {
+    pub basis_points: u16,           // Royalty on the full price
+    pub creators: Vec<(Pubkey, u8)>, // Creator and share in percent (shares add up to 100)
+}
+
+impl Royalties {
+    /// Metaplex metadata royalties
+    /// Unverified creators can be set by anyone, so only verified ones get paid
+    pub fn from_metadata(metadata: &MetadataAccount) -> Self {
+        Royalties {
+            basis_points: metadata.seller_fee_basis_points,
+            creators: metadata
+                .creators
+                .iter()
+                .flatten()
+                .filter(|creator| creator.verified)
+                .map(|creator| (creator.address, creator.share))
+                .collect(),
+        }
+    }
+
+    /// Royalties of an instruction's optional Metaplex metadata,
+    /// none for Token-2022 NFTs without it
+    pub fn from_optional_metadata<M: Deref<Target = MetadataAccount>>(metadata: Option<&M>) -> Self {
+        metadata
+            .map(|metadata| Self::from_metadata(metadata))
+            .unwrap_or_default()
+    }
+
+    /// Splits remaining accounts into one account per creator and the transfer
+    /// hook accounts of a Token-2022 mint that follow them
+    pub fn split_accounts<'a, 'info>(
+        &self,
+        remaining: &'a [AccountInfo<'info>],
+    ) -> (&'a [AccountInfo<'info>], &'a [AccountInfo<'info>]) {
+        remaining.split_at(self.creators.len().min(remaining.len()))
+    }
+}
+
+/// Pays the marketplace fee, creator royalties and seller proceeds for a sale
+///
+/// `seller` and `treasury` are wallets for SOL and token accounts for tokens.
+/// `creators` must hold one writable account per entry in `royalties.creators`,
+/// in the same order (their token accounts for token payments). Royalties are
+/// capped at what the fee leaves of the price, so the seller may get nothing but
+/// the sale never fails on high royalties
+pub fn settle_sale_with_royalties<'info>(
+    payer: &Payer<'_, 'info>,
+    price: u64,
+    fee_bps: u16,
+    royalties: &Royalties,
+    creators: &[AccountInfo<'info>],
+    seller: AccountInfo<'info>,
+    treasury: AccountInfo<'info>,
+) -> Result<Settlement> {
+    // Fee was already bounded by max_fee in init_marketplace/update_marketplace
+    // Calculate marketplace fee using safe arithmetic to prevent overflow
+    let marketplace_fee = bps_of(price, fee_bps)?;
+
+    // Royalty and fee together can be more than the price (e.g. a 95% royalty
+    // and a 10% fee); cap the royalty at what the fee leaves, so the sale still settles
+    let max_royalty = price
+        .checked_sub(marketplace_fee)
+        .ok_or(MarketplaceError::MathOverflow)?; // Fee is at most MAX_FEE_BPS of the price
+
+    // Pay creator royalties before working out what the seller keeps
+    let royalty = pay_royalties(payer, price, max_royalty, royalties, creators)?;
+
+    // Calculate amount seller receives after marketplace fee and royalties
+    let seller_proceeds = max_royalty
+        .checked_sub(royalty)
+        .ok_or(MarketplaceError::MathOverflow)?; // Royalty never exceeds max_royalty
+
+    payer.pay(seller, seller_proceeds)?; // Seller gets the rest
+    payer.pay(treasury, marketplace_fee)?; // Marketplace fee goes to treasury
+
+    Ok(Settlement {
+        marketplace_fee,
+        royalty,
+        seller_proceeds,
+    })
+}
+
+/// Checks optional payment accounts against the payment mint a sale is priced in
+/// SOL sales take no payment mint, token sales need the mint and all their accounts
+pub fn check_payment_accounts(
+    expected: Option<Pubkey>,
+    payment_mint: Option<&InterfaceAccount<Mint>>,
+    accounts_present: bool,
+) -> Result<()> {
+    match expected {
+        Some(expected) => {
+            let payment_mint = payment_mint.ok_or(MarketplaceError::MissingPaymentAccount)?;
+            require_keys_eq!(
+                payment_mint.key(),
+                expected,
+                MarketplaceError::PaymentMintMismatch
+            );
+            require!(accounts_present, MarketplaceError::MissingPaymentAccount);
+        }
+        None => require!(payment_mint.is_none(), MarketplaceError::PaymentMintMismatch),
+    }
+    Ok(())
+}
+
+/// Takes `bps` basis points of `amount`
+pub fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    (bps as u64)
+        .checked_mul(amount)
+        .ok_or(MarketplaceError::MathOverflow)? // Handle multiplication overflow
+        .checked_div(10000) // Divide by 10000 for basis points (1 basis point = 0.01%)
+        .ok_or(MarketplaceError::MathOverflow.into()) // Handle division overflow
+}
+
+/// Splits the royalty across creators by their share, paying at most `max_royalty`
+/// Returns the total paid so the seller's cut can be reduced by it
+fn pay_royalties<'info>(
+    payer: &Payer<'_, 'info>,
+    price: u64,
+    max_royalty: u64,
+    royalties: &Royalties,
+    creators: &[AccountInfo<'info>],
+) -> Result<u64> {
+    // Royalty on the full price, as set by seller_fee_basis_points in metadata
+    let royalty = bps_of(price, royalties.basis_points)?.min(max_royalty);
+
+    let mut remaining = creators.iter();
+    let mut paid: u64 = 0;
+
+    for &(address, share) in &royalties.creators {
+        // Each creator must be passed, in order, as a writable account
+        let account = remaining
+            .next()
+            .ok_or(MarketplaceError::MissingCreatorAccount)?;
+        require!(account.is_writable, MarketplaceError::InvalidCreator);
+
+        match payer {
+            // Token payments go to a creator-owned token account for the payment mint
+            Payer::Token {
+                mint,
+                token_program,
+                ..
+            } => {
+                require_keys_eq!(*account.owner, token_program.key(), MarketplaceError::InvalidCreator);
+                let token_account = TokenAccount::try_deserialize(&mut &account.data.borrow()[..])?;
+                require_keys_eq!(token_account.owner, address, MarketplaceError::InvalidCreator);
+                require_keys_eq!(token_account.mint, mint.key(), MarketplaceError::InvalidCreator);
+            }
+            // SOL payments go straight to the creator's wallet
+            _ => require_keys_eq!(account.key(), address, MarketplaceError::InvalidCreator),
+        }
+
+        // Creator share is a percentage (all shares add up to 100)
+        let amount = royalty
+            .checked_mul(share as u64)
+            .ok_or(MarketplaceError::MathOverflow)?
+            .checked_div(100)
+            .ok_or(MarketplaceError::MathOverflow)?;
+
+        if amount > 0 {
+            payer.pay(account.clone(), amount)?;
+        }
+
+        paid = paid.checked_add(amount).ok_or(MarketplaceError::MathOverflow)?;
+    }
+
+    // Extra accounts would otherwise be silently ignored
+    require!(remaining.next().is_none(), MarketplaceError::InvalidCreator);
+
+    Ok(paid)
+}
\ No newline at end of file
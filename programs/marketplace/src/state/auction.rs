@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*; // Import essential Anchor framework components
+
+use crate::error::MarketplaceError; // Errors for bid calculation
+use crate::settlement::bps_of; // Basis point math shared with sales
+
+#[account] // Tell Anchor this is an account that can be serialized/deserialized
+#[derive(InitSpace)] // Automatically calculate space needed for this struct
+pub struct Auction {
+    pub seller: Pubkey,                 // Public key of NFT seller (32 bytes)
+    pub mint: Pubkey,                   // Public key of NFT mint being auctioned (32 bytes)
+    pub collection: Pubkey,             // Collection the NFT was checked against, for its fee (32 bytes)
+    pub has_metadata: bool,             // Created with Metaplex metadata, so settlement must pass it for royalties (1 byte)
+    pub reserve_price: u64,             // Lowest bid accepted, in lamports (8 bytes)
+    pub start_time: i64,                // Unix timestamp bidding opens (8 bytes)
+    pub end_time: i64,                  // Unix timestamp bidding closes, may be extended (8 bytes)
+    pub extension_window: i64,          // Bids this close to the end push it back by this many seconds (8 bytes)
+    pub max_end_time: i64,              // Latest the end can be pushed back to by late bids (8 bytes)
+    pub min_bid_increment_bps: u16,     // Share of the high bid a new bid must beat it by (2 bytes)
+    pub highest_bid: u64,               // Current high bid escrowed in this account, in lamports (8 bytes)
+    pub highest_bidder: Option<Pubkey>, // Current high bidder, None until the first bid (1 + 32 bytes)
+    pub bump: u8                        // Canonical bump seed for auction PDA (1 byte)
+}
+
+impl Auction {
+    /// Lowest bid accepted right now: the reserve, or the high bid plus the
+    /// minimum increment (always at least one lamport) once someone has bid
+    pub fn min_next_bid(&self) -> Result<u64> {
+        if self.highest_bidder.is_none() {
+            return Ok(self.reserve_price);
+        }
+        let increment = bps_of(self.highest_bid, self.min_bid_increment_bps)?.max(1);
+        self.highest_bid
+            .checked_add(increment)
+            .ok_or(MarketplaceError::MathOverflow.into())
+    }
+}
\ No newline at end of file
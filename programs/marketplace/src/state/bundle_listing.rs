@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*; // Import essential Anchor framework components
+
+#[account] // Tell Anchor this is an account that can be serialized/deserialized
+#[derive(InitSpace)] // Automatically calculate space needed for this struct
+pub struct BundleListing {
+    pub maker: Pubkey,           // Public key of the seller (32 bytes)
+    pub bundle_id: u64,          // Seller-chosen id so one seller can list several bundles (8 bytes)
+    pub collection_mint: Pubkey, // Verified collection every NFT in the bundle belongs to (32 bytes)
+    #[max_len(5)]
+    pub mints: Vec<Pubkey>,      // NFT mints sold together, each in its own vault (4 + 5 * 32 bytes)
+    pub price: u64,              // Price for the whole bundle in lamports (8 bytes)
+    pub bump: u8                 // Canonical bump seed for bundle PDA (1 byte)
+}
\ No newline at end of file
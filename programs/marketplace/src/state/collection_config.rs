@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*; // Import essential Anchor framework components
+
+use crate::error::MarketplaceError; // Errors for listing checks
+
+#[account] // Tell Anchor this is an account that can be serialized/deserialized
+#[derive(InitSpace)] // Automatically calculate space needed for this struct
+pub struct CollectionConfig {
+    pub marketplace: Pubkey,    // Marketplace the collection is approved on (32 bytes)
+    pub collection: Pubkey,     // Collection mint, Core collection or token group address (32 bytes)
+    pub fee: Option<u16>,       // Fee override in basis points, None for the marketplace fee (1 + 2 bytes)
+    pub min_price: Option<u64>, // Lowest listing price allowed, None for no minimum (1 + 8 bytes)
+    pub max_price: Option<u64>, // Highest listing price allowed, None for no maximum (1 + 8 bytes)
+    pub enabled: bool,          // Whether the collection can currently be listed and bought (1 byte)
+    pub bump: u8                // Canonical bump seed for collection config PDA (1 byte)
+}
+
+impl CollectionConfig {
+    /// Checks settings the admin wants to store against the marketplace fee cap
+    pub fn check_settings(
+        fee: Option<u16>,
+        min_price: Option<u64>,
+        max_price: Option<u64>,
+        max_fee: u16,
+    ) -> Result<()> {
+        if let Some(fee) = fee {
+            require!(fee <= max_fee, MarketplaceError::FeeTooHigh);
+        }
+        if let (Some(min_price), Some(max_price)) = (min_price, max_price) {
+            require!(min_price <= max_price, MarketplaceError::InvalidPriceRange);
+        }
+        Ok(())
+    }
+
+    /// Fails if the admin has paused trading in the collection
+    pub fn check_enabled(&self) -> Result<()> {
+        require!(self.enabled, MarketplaceError::CollectionDisabled);
+        Ok(())
+    }
+
+    /// Fails if the collection is paused or `price` is outside its price range
+    pub fn check_price(&self, price: u64) -> Result<()> {
+        self.check_enabled()?;
+        if let Some(min_price) = self.min_price {
+            require!(price >= min_price, MarketplaceError::PriceOutOfRange);
+        }
+        if let Some(max_price) = self.max_price {
+            require!(price <= max_price, MarketplaceError::PriceOutOfRange);
+        }
+        Ok(())
+    }
+
+    /// Fee charged on sales in this collection, in basis points
+    pub fn fee_bps(&self, marketplace_fee: u16) -> u16 {
+        self.fee.unwrap_or(marketplace_fee)
+    }
+}
\ No newline at end of file
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*; // Import essential Anchor framework components
+
+#[account] // Tell Anchor this is an account that can be serialized/deserialized
+#[derive(InitSpace)] // Automatically calculate space needed for this struct
+pub struct CollectionOffer {
+    pub buyer: Pubkey,                // Public key of the bidder (32 bytes)
+    pub collection_mint: Pubkey,      // Collection any filled NFT must belong to (32 bytes)
+    pub price: u64,                   // Bid per NFT in lamports or payment mint base units (8 bytes)
+    pub quantity: u64,                // NFTs still wanted, escrow holds price * quantity (8 bytes)
+    pub payment_mint: Option<Pubkey>, // SPL mint the bid is in, None for SOL (1 + 32 bytes)
+    pub bump: u8                      // Canonical bump seed for collection offer PDA (1 byte)
+}
\ No newline at end of file
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*; // Import essential Anchor framework components
+
+#[account] // Tell Anchor this is an account that can be serialized/deserialized
+#[derive(InitSpace)] // Automatically calculate space needed for this struct
+pub struct CompressedListing {
+    pub maker: Pubkey,           // Public key of the seller (32 bytes)
+    pub asset_id: Pubkey,        // Bubblegum asset id of the compressed NFT (32 bytes)
+    pub merkle_tree: Pubkey,     // Tree holding the NFT's leaf (32 bytes)
+    pub nonce: u64,              // Leaf nonce the asset id is derived from (8 bytes)
+    pub index: u32,              // Leaf index in the tree (4 bytes)
+    pub data_hash: [u8; 32],     // Metadata hash stored in the leaf (32 bytes)
+    pub creator_hash: [u8; 32],  // Creators hash stored in the leaf (32 bytes)
+    pub collection_mint: Pubkey, // Verified collection the NFT belongs to (32 bytes)
+    pub price: u64,              // Sale price in lamports (8 bytes)
+    pub expires_at: Option<i64>, // Unix timestamp after which the listing can't be bought (1 + 8 bytes)
+    pub bump: u8                 // Canonical bump seed for listing PDA (1 byte)
+}
+
+impl CompressedListing {
+    /// Whether the listing has an expiry that is at or before `now`
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+}
\ No newline at end of file
@@ -1,10 +1,97 @@
 use anchor_lang::prelude::*; // Import essential Anchor framework components
 
+use crate::error::MarketplaceError; // Errors for price calculation
+
 #[account] // Tell Anchor this is an account that can be serialized/deserialized
 #[derive(InitSpace)] // Automatically calculate space needed for this struct
 pub struct Listing {
     pub maker: Pubkey,         // Public key of NFT seller (32 bytes)
     pub maker_mint: Pubkey,           // Public key of NFT mint being sold (32 bytes)
-    pub price: u64,             // Sale price in lamports (8 bytes)
+    pub collection: Pubkey,     // Collection the listed token was checked against (32 bytes)
+    pub price: u64,             // Price per whole token in lamports, or payment mint base units (8 bytes)
+    pub quantity: u64,          // Base units of the listed token still for sale (8 bytes)
+    pub payment_mint: Option<Pubkey>, // SPL mint the price is in, None for SOL (1 + 32 bytes)
+    pub dutch_auction: Option<DutchAuction>, // Declining price schedule, None for fixed price (1 + 41 bytes)
+    pub expires_at: Option<i64>, // Unix timestamp the listing stops being buyable, None for never (1 + 8 bytes)
+    pub custody: Custody,       // Where the listed token is held while for sale (1 + 1 bytes)
+    pub edition_number: Option<u64>, // Print number of a print edition NFT, for display (1 + 8 bytes)
+    pub has_metadata: bool,     // Listed with Metaplex metadata, so purchases must pass it for royalties (1 byte)
     pub bump: u8               // Canonical bump seed for listing PDA (1 byte)
+}
+
+impl Listing {
+    /// Whether the listing can no longer be bought at unix timestamp `now`
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    /// Cost of `amount` base units of a token with `decimals` at `unit_price` per whole token
+    /// Rounded up, so tiny fills can't be bought for free
+    pub fn cost_of(unit_price: u64, amount: u64, decimals: u8) -> Result<u64> {
+        let one_token = 10u128
+            .checked_pow(decimals as u32)
+            .ok_or(MarketplaceError::MathOverflow)?; // Base units in one whole token
+        let cost = (unit_price as u128)
+            .checked_mul(amount as u128)
+            .ok_or(MarketplaceError::MathOverflow)?
+            .div_ceil(one_token);
+
+        u64::try_from(cost).map_err(|_| MarketplaceError::MathOverflow.into())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)] // Stored inside Listing
+pub enum Custody {
+    Escrow,                    // Held by the listing PDA (its vault, or the PDA itself for Core assets)
+    Delegate { frozen: bool }, // Left in the seller's token account with the listing PDA as delegate
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)] // Stored inside Listing
+pub struct DutchAuction {
+    pub start_price: u64,   // Price at start_time (8 bytes)
+    pub floor_price: u64,   // Price at and after end_time (8 bytes)
+    pub start_time: i64,    // Unix timestamp the price starts falling (8 bytes)
+    pub end_time: i64,      // Unix timestamp the price reaches the floor (8 bytes)
+    pub curve: DecayCurve,  // How the price falls between the two (1 + 8 bytes)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)] // Stored inside DutchAuction
+pub enum DecayCurve {
+    Linear,                         // Falls by the same amount every second
+    Exponential { half_life: i64 }, // Distance to the floor halves every half_life seconds
+}
+
+impl DutchAuction {
+    /// Price a buyer pays at unix timestamp `now`
+    /// Before start_time it's the start price, at or after end_time it's the floor
+    pub fn price_at(&self, now: i64) -> Result<u64> {
+        if now <= self.start_time {
+            return Ok(self.start_price);
+        }
+        if now >= self.end_time {
+            return Ok(self.floor_price);
+        }
+
+        let elapsed = (now - self.start_time) as u128; // Seconds since the price started falling
+        let range = (self.start_price - self.floor_price) as u128; // Amount the price falls in total
+
+        let above_floor = match self.curve {
+            DecayCurve::Linear => {
+                let duration = (self.end_time - self.start_time) as u128;
+                range - range * elapsed / duration
+            }
+            DecayCurve::Exponential { half_life } => {
+                let half_life = half_life as u128;
+                let halvings = elapsed / half_life;
+                // Halve once per full half-life, then interpolate within the current one
+                let excess = if halvings < 128 { range >> halvings } else { 0 };
+                excess - excess * (elapsed % half_life) / (2 * half_life)
+            }
+        };
+
+        let price = (self.floor_price as u128)
+            .checked_add(above_floor)
+            .ok_or(MarketplaceError::MathOverflow)?;
+        Ok(price as u64) // Never above start_price, so it fits in u64
+    }
 }
\ No newline at end of file
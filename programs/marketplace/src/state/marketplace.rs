@@ -4,7 +4,14 @@ use anchor_lang::prelude::*; // Import essential Anchor framework components
 #[derive(InitSpace)] // Automatically calculate space needed for this struct
 pub struct Marketplace {    
     pub admin: Pubkey,              // Public key of marketplace administrator (32 bytes)
+    pub pending_admin: Option<Pubkey>, // Proposed admin awaiting acceptance (1 + 32 bytes)
     pub fee: u16,                   // Marketplace fee in basis points (e.g., 200 = 2%) (2 bytes)
+    pub max_fee: u16,               // Highest fee the admin may ever set, in basis points (2 bytes)
+    pub buyer_reward_rate: u64,     // Reward base units minted to the buyer per SOL of volume (8 bytes)
+    pub seller_reward_rate: u64,    // Reward base units minted to the seller per SOL of volume (8 bytes)
+    #[max_len(8)]
+    pub payment_mints: Vec<Pubkey>, // SPL mints listings may be priced in (4 + 8 * 32 bytes)
+    pub allow_print_editions: bool, // Whether print editions of limited edition NFTs may be listed (1 byte)
     pub bump: u8,                   // Canonical bump seed for marketplace PDA (1 byte)
     pub treasury_bump: u8,          // Canonical bump seed for treasury PDA (1 byte)
     pub rewards_bump: u8,           // Canonical bump seed for rewards mint PDA (1 byte)
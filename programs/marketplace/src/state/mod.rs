@@ -1,5 +1,17 @@
+pub mod auction;
+pub mod bundle_listing;
+pub mod collection_config;
+pub mod collection_offer;
+pub mod compressed_listing;
 pub mod listing;
 pub mod marketplace;
+pub mod offer;
 
+pub use auction::*;
+pub use bundle_listing::*;
+pub use collection_config::*;
+pub use collection_offer::*;
+pub use compressed_listing::*;
 pub use listing::*;
-pub use marketplace::*;
\ No newline at end of file
+pub use marketplace::*;
+pub use offer::*;
\ No newline at end of file
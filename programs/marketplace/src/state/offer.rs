@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*; // Import essential Anchor framework components
+
+#[account] // Tell Anchor this is an account that can be serialized/deserialized
+#[derive(InitSpace)] // Automatically calculate space needed for this struct
+pub struct Offer {
+    pub buyer: Pubkey,                // Public key of the bidder (32 bytes)
+    pub mint: Pubkey,                 // Public key of the NFT mint being bid on (32 bytes)
+    pub price: u64,                   // Escrowed bid in lamports or payment mint base units (8 bytes)
+    pub payment_mint: Option<Pubkey>, // SPL mint the bid is in, None for SOL (1 + 32 bytes)
+    pub bump: u8                      // Canonical bump seed for offer PDA (1 byte)
+}
\ No newline at end of file
@@ -0,0 +1,76 @@
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// SPL Token-2022 imports
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            group_member_pointer::GroupMemberPointer, BaseStateWithExtensions, ExtensionType,
+            StateWithExtensions,
+        },
+    },
+    token_interface::Mint,
+};
+use spl_token_group_interface::state::TokenGroupMember;
+
+// Local error imports
+use crate::error::MarketplaceError;
+
+/// Rejects Token-2022 mints whose extensions make escrow or delegation unsafe:
+/// NonTransferable tokens can't be sold, and a PermanentDelegate could take the
+/// token back out of the vault or the buyer's wallet at any time
+pub fn check_listable_mint(mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let info = mint.to_account_info();
+    if *info.owner != spl_token_2022::ID {
+        return Ok(()); // Legacy token mints have no extensions
+    }
+
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    let extensions = state.get_extension_types()?;
+
+    require!(
+        !extensions.contains(&ExtensionType::NonTransferable),
+        MarketplaceError::NonTransferableMint
+    );
+    require!(
+        !extensions.contains(&ExtensionType::PermanentDelegate),
+        MarketplaceError::PermanentDelegateMint
+    );
+    Ok(())
+}
+
+/// Verifies a Token-2022 NFT without Metaplex metadata, which keeps its metadata
+/// in the mint through the metadata pointer and its collection through the
+/// group/member extensions: a member can only be added by the group's update
+/// authority, so membership is as good as a verified Metaplex collection
+pub fn verify_group_member(mint: &InterfaceAccount<Mint>, collection: &Pubkey) -> Result<()> {
+    let info = mint.to_account_info();
+    require_keys_eq!(*info.owner, spl_token_2022::ID, MarketplaceError::InvalidCollection);
+
+    // One indivisible token that can never be minted again
+    require!(
+        mint.decimals == 0 && mint.supply == 1 && mint.mint_authority.is_none(),
+        MarketplaceError::NotUniqueToken
+    );
+
+    let data = info.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+
+    // Member data must live in the mint itself, where Token-2022 maintains it
+    let pointer = state
+        .get_extension::<GroupMemberPointer>()
+        .map_err(|_| MarketplaceError::InvalidCollection)?;
+    require!(
+        Option::<Pubkey>::from(pointer.member_address) == Some(mint.key()),
+        MarketplaceError::InvalidCollection
+    );
+
+    let member = state
+        .get_extension::<TokenGroupMember>()
+        .map_err(|_| MarketplaceError::InvalidCollection)?;
+    require_keys_eq!(member.mint, mint.key(), MarketplaceError::InvalidCollection);
+    require_keys_eq!(member.group, *collection, MarketplaceError::InvalidCollection);
+    Ok(())
+}
\ No newline at end of file
@@ -0,0 +1,138 @@
+#![allow(unexpected_cfgs)] // Suppress warnings about unexpected configuration flags
+#![allow(deprecated)] // Suppress warnings about deprecated features
+
+// Core Anchor framework imports
+use anchor_lang::prelude::*;
+
+// SPL Token and transfer hook interface imports
+use anchor_spl::token_interface::Mint;
+use spl_discriminator::SplDiscriminate;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+declare_id!("5tE8wc76hFYKrnKZMXb7jWiY5WAjUHQeYp3JPSCshSD5");
+
+/// Minimal Token-2022 transfer hook used by the marketplace tests
+/// Every transfer of a hooked mint bumps a per-mint counter, which Token-2022
+/// only finds if the marketplace forwards the hook's extra accounts
+#[program]
+pub mod transfer_hook {
+    use super::*;
+
+    // ========================================================================
+    // INITIALIZE EXTRA ACCOUNT META LIST INSTRUCTION
+    // ========================================================================
+    // Creates the validation account Token-2022 reads the extra accounts from,
+    // and the counter the hook writes to
+    //
+    // Parameters:
+    // - ctx: Contains payer, validation account, mint and counter
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    pub fn initialize_extra_account_meta_list(
+        ctx: Context<InitializeExtraAccountMetaList>,
+    ) -> Result<()> {
+        ctx.accounts.counter.set_inner(Counter {
+            transfers: 0, // No transfers yet
+            bump: ctx.bumps.counter, // Store counter PDA bump
+        });
+
+        // The counter is the only extra account: ["counter", mint], mint being account 1 of Execute
+        let extra_account_metas = [ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal { bytes: b"counter".to_vec() }, // Counter seed prefix
+                Seed::AccountKey { index: 1 }, // Mint being transferred
+            ],
+            false, // Not a signer
+            true, // Writable, the hook bumps it
+        )?];
+
+        ExtraAccountMetaList::init::<ExecuteInstruction>(
+            &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+            &extra_account_metas,
+        )?;
+        Ok(())
+    }
+
+    // ========================================================================
+    // TRANSFER HOOK INSTRUCTION
+    // ========================================================================
+    // Called by Token-2022 on every transfer_checked of a hooked mint
+    //
+    // Parameters:
+    // - ctx: Execute accounts followed by the counter
+    // - _amount: Amount transferred
+    //
+    // Returns: Result indicating success or failure
+    // ========================================================================
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, _amount: u64) -> Result<()> {
+        ctx.accounts.counter.transfers += 1; // Count the transfer
+        Ok(())
+    }
+}
+
+#[derive(Accounts)] // Define accounts needed to set up the hook for a mint
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)] // Mutable because payer funds the new accounts
+    pub payer: Signer<'info>,
+
+    /// CHECK: Validation account, written as a TLV ExtraAccountMetaList
+    #[account(
+        init, // Create the validation account
+        payer = payer, // Payer funds it
+        space = ExtraAccountMetaList::size_of(1).unwrap(), // One extra account: the counter
+        seeds = [b"extra-account-metas", mint.key().as_ref()], // PDA required by the transfer hook interface
+        bump // Canonical bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>, // Token-2022 mint pointing its hook at this program
+
+    #[account(
+        init, // Create the counter
+        payer = payer, // Payer funds it
+        space = 8 + Counter::INIT_SPACE, // Account size: discriminator + counter data
+        seeds = [b"counter", mint.key().as_ref()], // PDA: "counter" + mint
+        bump // Canonical bump
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub system_program: Program<'info, System>, // For account creation
+}
+
+#[derive(Accounts)] // Accounts of the transfer hook interface's Execute instruction
+pub struct TransferHook<'info> {
+    /// CHECK: Source token account, checked by Token-2022
+    pub source: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>, // Mint being transferred
+
+    /// CHECK: Destination token account, checked by Token-2022
+    pub destination: UncheckedAccount<'info>,
+
+    /// CHECK: Owner or delegate of the source, checked by Token-2022
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: Validation account, checked by seeds
+    #[account(
+        seeds = [b"extra-account-metas", mint.key().as_ref()], // PDA required by the transfer hook interface
+        bump // Canonical bump
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        mut, // Mutable because the transfer is counted
+        seeds = [b"counter", mint.key().as_ref()], // PDA: "counter" + mint
+        bump = counter.bump // Use stored bump to verify PDA
+    )]
+    pub counter: Account<'info, Counter>,
+}
+
+#[account] // Transfers seen by the hook for one mint
+#[derive(InitSpace)] // Automatically calculate space needed
+pub struct Counter {
+    pub transfers: u64, // Transfers of the mint so far
+    pub bump: u8, // PDA bump seed
+}
\ No newline at end of file
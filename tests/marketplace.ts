@@ -1,17 +1,22 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Marketplace } from "../target/types/marketplace";
-import { createNft, findMasterEditionPda, findMetadataPda, mplTokenMetadata, verifySizedCollectionItem } from '@metaplex-foundation/mpl-token-metadata'
+import { TransferHook } from "../target/types/transfer_hook";
+import { TokenStandard, createFungibleAsset, createNft, findMasterEditionPda, findMetadataPda, findTokenRecordPda, mintV1, mplTokenMetadata, printSupply, printV1, verifyCollectionV1, verifySizedCollectionItem } from '@metaplex-foundation/mpl-token-metadata'
+import { MPL_BUBBLEGUM_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_PROGRAM_ID, SPL_NOOP_PROGRAM_ID, TokenProgramVersion, TokenStandard as CompressedTokenStandard, createTree, findTreeConfigPda, getMerkleProof, getMerkleRoot, getMetadataArgsSerializer, hashLeaf, mintToCollectionV1, mplBubblegum } from "@metaplex-foundation/mpl-bubblegum";
+import { MPL_CORE_PROGRAM_ID, create as createCoreAsset, createCollection as createCoreCollection, fetchAsset, fetchCollection, mplCore, ruleSet } from "@metaplex-foundation/mpl-core";
 import { createUmi } from "@metaplex-foundation/umi-bundle-defaults"
-import { KeypairSigner, PublicKey, createSignerFromKeypair, generateSigner, keypairIdentity, percentAmount, publicKey } from '@metaplex-foundation/umi';
-import { TOKEN_PROGRAM_ID, getOrCreateAssociatedTokenAccount } from "@solana/spl-token";
+import { KeypairSigner, PublicKey, createSignerFromKeypair, generateSigner, keypairIdentity, percentAmount, publicKey, publicKeyBytes } from '@metaplex-foundation/umi';
+import { AuthorityType, ExtensionType, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID, createAssociatedTokenAccountIdempotent, createInitializeGroupMemberPointerInstruction, createInitializeGroupPointerInstruction, createInitializeMintInstruction, createInitializeNonTransferableMintInstruction, createInitializePermanentDelegateInstruction, createInitializeTransferHookInstruction, createMint, getAccount, getAssociatedTokenAddressSync, getMintLen, getOrCreateAssociatedTokenAccount, mintTo, revoke, setAuthority, tokenGroupInitializeGroupWithRentTransfer, tokenGroupMemberInitializeWithRentTransfer } from "@solana/spl-token";
 import NodeWallet from "@coral-xyz/anchor/dist/cjs/nodewallet";
-import { Keypair, LAMPORTS_PER_SOL, SystemProgram } from "@solana/web3.js";
+import { Keypair, LAMPORTS_PER_SOL, SYSVAR_INSTRUCTIONS_PUBKEY, SystemProgram, Transaction, TransactionInstruction } from "@solana/web3.js";
+import { assert } from "chai";
 
 describe("marketplace", () => {
   const provider = anchor.AnchorProvider.env();
   anchor.setProvider(provider);
   const program = anchor.workspace.marketplace  as Program<Marketplace>;
+  const hookProgram = anchor.workspace.transferHook as Program<TransferHook>;
   const connection = provider.connection;
   const umi = createUmi(provider.connection);
   const payer = provider.wallet as NodeWallet;
@@ -23,6 +28,8 @@ describe("marketplace", () => {
   const creator = createSignerFromKeypair(umi, creatorWallet);
   umi.use(keypairIdentity(creator));
   umi.use(mplTokenMetadata());
+  umi.use(mplCore());
+  umi.use(mplBubblegum());
 
   let makerAta: anchor.web3.PublicKey;
   let takerAta: anchor.web3.PublicKey;
@@ -31,13 +38,175 @@ describe("marketplace", () => {
   const maker = Keypair.generate();
   const taker = Keypair.generate();
 
-  const name = "user123"; 
-  const price = new anchor.BN(1);
+  const name = "user123";
+  const price = new anchor.BN(LAMPORTS_PER_SOL);
+  const fee = 250; // 2.5%
+  const maxFee = 1000; // 10%
+
+  // createNft marks the umi identity as the single verified creator
+  const creatorAccounts = [{ pubkey: provider.wallet.publicKey, isSigner: false, isWritable: true }];
 
   const marketplace = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("marketplace"), Buffer.from(name)], program.programId)[0];
-  const rewardsMint = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("rewards"), marketplace.toBuffer()], program.programId)[0];
+  const rewardsMint = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("reward"), marketplace.toBuffer()], program.programId)[0];
   const treasury = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("treasury"), marketplace.toBuffer()], program.programId)[0];
   const listing = anchor.web3.PublicKey.findProgramAddressSync([marketplace.toBuffer(), new anchor.web3.PublicKey(nftMint.publicKey as PublicKey).toBuffer()], program.programId)[0];
+  const collectionConfig = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("collection"), marketplace.toBuffer(), new anchor.web3.PublicKey(collectionMint.publicKey as PublicKey).toBuffer()], program.programId)[0];
+
+  // Decodes the event called `name` from the logs of a transaction
+  const eventOf = async (tx: string, name: string) => {
+    await connection.confirmTransaction(tx, "confirmed");
+    const { meta } = await connection.getTransaction(tx, { commitment: "confirmed", maxSupportedTransactionVersion: 0 });
+    const parser = new anchor.EventParser(program.programId, new anchor.BorshCoder(program.idl));
+    const event = Array.from(parser.parseLogs(meta.logMessages)).find((event) => event.name.toLowerCase() === name.toLowerCase());
+    assert.ok(event, `${name} event should be emitted`);
+    return event.data;
+  };
+
+  // Regular NFT in the approved collection, minted to `owner` with the provider wallet as verified creator
+  const createCollectionNft = async (owner: anchor.web3.PublicKey, sellerFeeBasisPoints = percentAmount(5)) => {
+    const mint = generateSigner(umi);
+    await createNft(umi, {
+      mint,
+      name: "GM",
+      symbol: "GM",
+      uri: "https://arweave.net/123",
+      sellerFeeBasisPoints,
+      collection: { verified: false, key: collectionMint.publicKey },
+      tokenOwner: publicKey(owner),
+    }).sendAndConfirm(umi);
+    await verifyCollectionV1(umi, {
+      metadata: findMetadataPda(umi, { mint: mint.publicKey }),
+      collectionMint: collectionMint.publicKey,
+      authority: creator,
+    }).sendAndConfirm(umi);
+    return new anchor.web3.PublicKey(mint.publicKey);
+  };
+
+  // Metaplex metadata and master edition PDAs of a mint
+  const metadataOf = (mint: anchor.web3.PublicKey) => new anchor.web3.PublicKey(findMetadataPda(umi, { mint: publicKey(mint) })[0]);
+  const editionOf = (mint: anchor.web3.PublicKey) => new anchor.web3.PublicKey(findMasterEditionPda(umi, { mint: publicKey(mint) })[0]);
+
+  // Listing PDA of a mint and the vault it escrows the NFT in
+  const listingOf = (mint: anchor.web3.PublicKey) => anchor.web3.PublicKey.findProgramAddressSync([marketplace.toBuffer(), mint.toBuffer()], program.programId)[0];
+  const vaultOf = (mint: anchor.web3.PublicKey) => anchor.utils.token.associatedAddress({ mint, owner: listingOf(mint) });
+
+  // Lists a collection NFT held by the maker into escrow, priced in SOL or `paymentMint`
+  const listNft = (mint: anchor.web3.PublicKey, listPrice: anchor.BN = price, paymentMint: anchor.web3.PublicKey | null = null) => program.methods.listing(listPrice, paymentMint, null)
+  .accountsPartial({
+    seller: maker.publicKey,
+    marketplace,
+    sellerMint: mint,
+    collectionMint: collectionMint.publicKey,
+    sellerAta: anchor.utils.token.associatedAddress({ mint, owner: maker.publicKey }),
+    metadata: metadataOf(mint),
+    vault: vaultOf(mint),
+    edition: editionOf(mint),
+    listing: listingOf(mint),
+    collectionConfig,
+    systemProgram: anchor.web3.SystemProgram.programId,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  })
+  .signers([maker])
+  .rpc();
+
+  // Taker buys from a listing of the maker, paying in SOL
+  const purchaseNft = (mint: anchor.web3.PublicKey, expectedPrice: anchor.BN, quantity = new anchor.BN(1)) => program.methods.purchase(expectedPrice, maker.publicKey, quantity)
+  .accountsPartial({
+    buyer: taker.publicKey,
+    seller: maker.publicKey,
+    sellerMint: mint,
+    marketplace,
+    buyerAta: anchor.utils.token.associatedAddress({ mint, owner: taker.publicKey }),
+    vault: vaultOf(mint),
+    listing: listingOf(mint),
+    collectionConfig,
+    treasury,
+    metadata: metadataOf(mint),
+    paymentMint: null,
+    buyerPaymentAta: null,
+    sellerPaymentAta: null,
+    treasuryPaymentAta: null,
+    paymentTokenProgram: null,
+    rewardTokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: anchor.web3.SystemProgram.programId,
+    tokenProgram: TOKEN_PROGRAM_ID,
+  })
+  .remainingAccounts(creatorAccounts)
+  .signers([taker])
+  .rpc();
+
+  // Creates a Token-2022 mint with 0 decimals, the given extensions and the provider wallet as mint authority
+  const createToken2022Mint = async (extensions: ExtensionType[], initExtensions: (mint: anchor.web3.PublicKey) => TransactionInstruction[]) => {
+    const mint = Keypair.generate();
+    const space = getMintLen(extensions);
+    await provider.sendAndConfirm(new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: provider.wallet.publicKey,
+        newAccountPubkey: mint.publicKey,
+        space,
+        lamports: await connection.getMinimumBalanceForRentExemption(space),
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      ...initExtensions(mint.publicKey),
+      createInitializeMintInstruction(mint.publicKey, 0, provider.wallet.publicKey, null, TOKEN_2022_PROGRAM_ID),
+    ), [mint]);
+    return mint.publicKey;
+  };
+
+  // Token-2022 collection: a mint holding its own TokenGroup, updated by the provider wallet
+  const createTokenGroup = async () => {
+    const group = await createToken2022Mint([ExtensionType.GroupPointer], (mint) => [
+      createInitializeGroupPointerInstruction(mint, provider.wallet.publicKey, mint, TOKEN_2022_PROGRAM_ID),
+    ]);
+    await tokenGroupInitializeGroupWithRentTransfer(connection, payer.payer, group, payer.payer, provider.wallet.publicKey, BigInt(10), [], undefined, TOKEN_2022_PROGRAM_ID);
+    return group;
+  };
+
+  // Token-2022 NFT without Metaplex metadata: a member of `group`, one token minted to `owner`
+  // and the mint authority dropped so it stays unique
+  const createToken2022Nft = async (group: anchor.web3.PublicKey, owner: anchor.web3.PublicKey, extensions: ExtensionType[] = [], initExtensions: (mint: anchor.web3.PublicKey) => TransactionInstruction[] = () => []) => {
+    const mint = await createToken2022Mint([ExtensionType.GroupMemberPointer, ...extensions], (mint) => [
+      createInitializeGroupMemberPointerInstruction(mint, provider.wallet.publicKey, mint, TOKEN_2022_PROGRAM_ID),
+      ...initExtensions(mint),
+    ]);
+    await tokenGroupMemberInitializeWithRentTransfer(connection, payer.payer, mint, payer.payer, group, payer.payer, [], undefined, TOKEN_2022_PROGRAM_ID);
+    const ata = await createAssociatedTokenAccountIdempotent(connection, payer.payer, mint, owner, {}, TOKEN_2022_PROGRAM_ID);
+    await mintTo(connection, payer.payer, mint, ata, payer.payer, 1, [], undefined, TOKEN_2022_PROGRAM_ID);
+    await setAuthority(connection, payer.payer, mint, payer.payer, AuthorityType.MintTokens, null, [], undefined, TOKEN_2022_PROGRAM_ID);
+    return mint;
+  };
+
+  // Approved Token-2022 collection, created by the first Token-2022 test
+  let tokenGroup: anchor.web3.PublicKey;
+  let tokenGroupConfig: anchor.web3.PublicKey;
+
+  // Lists a Token-2022 NFT without metadata; `hookAccounts` go in as remaining accounts
+  const listToken2022 = (mint: anchor.web3.PublicKey, hookAccounts: anchor.web3.AccountMeta[] = []) => {
+    const mintListing = anchor.web3.PublicKey.findProgramAddressSync([marketplace.toBuffer(), mint.toBuffer()], program.programId)[0];
+    return program.methods.listing(price, null, null)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      sellerMint: mint,
+      collectionMint: tokenGroup,
+      collectionConfig: tokenGroupConfig,
+      sellerAta: getAssociatedTokenAddressSync(mint, maker.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      metadata: metadataOf(mint), // Never created for this NFT, but always passed
+      vault: getAssociatedTokenAddressSync(mint, mintListing, true, TOKEN_2022_PROGRAM_ID),
+      edition: null,
+      tokenRecord: null,
+      destinationTokenRecord: null,
+      authorizationRules: null,
+      authorizationRulesProgram: null,
+      sysvarInstructions: null,
+      listing: mintListing,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .remainingAccounts(hookAccounts)
+    .signers([maker])
+    .rpc();
+  };
 
   before(async () => {
     // Airdrop SOL to maker and taker
@@ -105,8 +274,48 @@ describe("marketplace", () => {
     });
   });
 
+  it("Initialize rejects a bad name or fee!", async () => {
+    // Derives every account for `badName` so only the arguments are wrong
+    const init = (badName: string, badFee: number, badMaxFee: number, badMarketplace?: anchor.web3.PublicKey) => {
+      const market = badMarketplace ?? anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("marketplace"), Buffer.from(badName)], program.programId)[0];
+      return program.methods.initMarketplace(badName, badFee, badMaxFee)
+      .accountsPartial({
+        admin: provider.wallet.publicKey,
+        marketplace: market,
+        rewardMint: anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("reward"), market.toBuffer()], program.programId)[0],
+        treasury: anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("treasury"), market.toBuffer()], program.programId)[0],
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    };
+
+    const cases: [string, () => Promise<string>, string][] = [
+      ["an empty name", () => init("", fee, maxFee), "EmptyName"],
+      ["a cap above MAX_FEE_BPS", () => init("badcap", fee, 5001), "FeeTooHigh"],
+      ["a fee above the cap", () => init("badfee", maxFee + 1, maxFee), "FeeTooHigh"],
+    ];
+    for (const [description, attempt, error] of cases) {
+      try {
+        await attempt();
+        assert.fail(`init with ${description} should fail`);
+      } catch (err) {
+        assert.include(err.toString(), error, description);
+      }
+    }
+
+    // A name over MAX_NAME_LEN bytes is also over the 32 byte seed limit, so the
+    // marketplace PDA can't be derived and the instruction fails before NameTooLong
+    try {
+      await init("x".repeat(33), fee, maxFee, Keypair.generate().publicKey);
+      assert.fail("init with a name that is too long should fail");
+    } catch (err) {
+      assert.notInclude(err.toString(), "init with a name that is too long should fail");
+    }
+  });
+
   it("Initialize Marketplace!", async () => {
-    const tx = await program.methods.initialize(name, 1)
+    const tx = await program.methods.initMarketplace(name, fee, maxFee)
       .accountsPartial({
         admin: provider.wallet.publicKey,
         marketplace,
@@ -117,25 +326,320 @@ describe("marketplace", () => {
       })
       .rpc();
     console.log("Marketplace Initialized. Tx:", tx);
+
+    const event = await eventOf(tx, "MarketplaceInitialized");
+    assert.ok(event.marketplace.equals(marketplace));
+    assert.ok(event.admin.equals(provider.wallet.publicKey));
+    assert.equal(event.name, name);
+    assert.equal(event.fee, fee);
+    assert.equal(event.maxFee, maxFee);
+    assert.isAbove(event.timestamp.toNumber(), 0);
+  });
+
+  it("Listing needs an approved collection and a price in its range!", async () => {
+    const nftMetadata = findMetadataPda(umi, {mint: nftMint.publicKey});
+    const nftEdition = findMasterEditionPda(umi, {mint: nftMint.publicKey});
+    const listNft = () => program.methods.listing(price, null, null)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      sellerMint: nftMint.publicKey,
+      collectionMint: collectionMint.publicKey,
+      collectionConfig,
+      sellerAta: makerAta,
+      metadata: new anchor.web3.PublicKey(nftMetadata[0]),
+      vault,
+      edition: new anchor.web3.PublicKey(nftEdition[0]),
+      listing,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+
+    // Collection hasn't been approved yet
+    try {
+      await listNft();
+      assert.fail("listing from an unapproved collection should fail");
+    } catch (err) {
+      assert.include(err.toString(), "AccountNotInitialized");
+    }
+
+    // Approve it with a minimum above the listing price
+    const tx = await program.methods.addCollection(new anchor.web3.PublicKey(collectionMint.publicKey), null, price.muln(2), null)
+    .accountsPartial({
+      admin: provider.wallet.publicKey,
+      marketplace,
+      collectionConfig,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+    console.log("\nCollection Added!");
+    console.log("Your transaction signature", tx);
+
+    try {
+      await listNft();
+      assert.fail("listing below the collection minimum should fail");
+    } catch (err) {
+      assert.include(err.toString(), "PriceOutOfRange");
+    }
+
+    // Disabled collections can't be listed either
+    await program.methods.updateCollection(null, null, null, false)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace, collectionConfig })
+    .rpc();
+    try {
+      await listNft();
+      assert.fail("listing in a disabled collection should fail");
+    } catch (err) {
+      assert.include(err.toString(), "CollectionDisabled");
+    }
+
+    // Re-enable it without a price range for the rest of the suite
+    await program.methods.updateCollection(null, null, null, true)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace, collectionConfig })
+    .rpc();
+    const config = await program.account.collectionConfig.fetch(collectionConfig);
+    assert.isTrue(config.enabled);
+    assert.isNull(config.minPrice);
+  });
+
+  it("Listing rejects NFTs without a verified collection or master edition!", async () => {
+    // Lists `mint` under the approved collection, with or without its master edition
+    const listUnder = async (mint: PublicKey, withEdition: boolean) => {
+      const mintKey = new anchor.web3.PublicKey(mint);
+      const mintListing = anchor.web3.PublicKey.findProgramAddressSync([marketplace.toBuffer(), mintKey.toBuffer()], program.programId)[0];
+      await program.methods.listing(price, null, null)
+      .accountsPartial({
+        seller: maker.publicKey,
+        marketplace,
+        sellerMint: mintKey,
+        collectionMint: collectionMint.publicKey,
+        collectionConfig,
+        sellerAta: anchor.utils.token.associatedAddress({ mint: mintKey, owner: maker.publicKey }),
+        metadata: new anchor.web3.PublicKey(findMetadataPda(umi, { mint })[0]),
+        vault: anchor.utils.token.associatedAddress({ mint: mintKey, owner: mintListing }),
+        edition: withEdition ? new anchor.web3.PublicKey(findMasterEditionPda(umi, { mint })[0]) : null,
+        listing: mintListing,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+    };
+    // Mints an NFT to the maker with the given collection field
+    const mintNft = async (collection?: { verified: boolean, key: PublicKey }) => {
+      const mint = generateSigner(umi);
+      await createNft(umi, {
+        mint,
+        name: "GM",
+        symbol: "GM",
+        uri: "https://arweave.net/123",
+        sellerFeeBasisPoints: percentAmount(5.5),
+        collection,
+        tokenOwner: publicKey(maker.publicKey)
+      }).sendAndConfirm(umi);
+      return mint.publicKey;
+    };
+
+    const cases: [string, () => Promise<PublicKey>, boolean, string][] = [
+      ["no collection", () => mintNft(), true, "InvalidCollection"],
+      ["an unverified collection", () => mintNft({ verified: false, key: collectionMint.publicKey }), true, "UnverifedCollection"],
+      ["a different collection", () => mintNft({ verified: false, key: generateSigner(umi).publicKey }), true, "InvalidCollection"],
+      ["no master edition passed", async () => nftMint.publicKey, false, "MissingEditionAccount"],
+    ];
+
+    for (const [description, mint, withEdition, error] of cases) {
+      try {
+        await listUnder(await mint(), withEdition);
+        assert.fail(`listing an NFT with ${description} should fail`);
+      } catch (err) {
+        assert.include(err.toString(), error, description);
+      }
+    }
+  });
+
+  it("Print editions can only be listed once the marketplace allows them!", async () => {
+    // Limited edition master in the collection, printed once to the maker
+    const masterMint = generateSigner(umi);
+    await createNft(umi, {
+      mint: masterMint,
+      name: "GM",
+      symbol: "GM",
+      uri: "https://arweave.net/123",
+      sellerFeeBasisPoints: percentAmount(5.5),
+      collection: { verified: false, key: collectionMint.publicKey },
+      printSupply: printSupply('Limited', [10]),
+    }).sendAndConfirm(umi);
+    await verifySizedCollectionItem(umi, {
+      metadata: findMetadataPda(umi, { mint: masterMint.publicKey }),
+      collectionAuthority: creator,
+      collectionMint: collectionMint.publicKey,
+      collection: findMetadataPda(umi, { mint: collectionMint.publicKey }),
+      collectionMasterEditionAccount: findMasterEditionPda(umi, { mint: collectionMint.publicKey }),
+    }).sendAndConfirm(umi);
+    const printMint = generateSigner(umi);
+    await printV1(umi, {
+      masterTokenAccountOwner: creator,
+      masterEditionMint: masterMint.publicKey,
+      editionMint: printMint,
+      editionTokenAccountOwner: publicKey(maker.publicKey),
+      editionNumber: 1,
+      tokenStandard: TokenStandard.NonFungible,
+    }).sendAndConfirm(umi);
+
+    const printKey = new anchor.web3.PublicKey(printMint.publicKey);
+    const printListing = anchor.web3.PublicKey.findProgramAddressSync([marketplace.toBuffer(), printKey.toBuffer()], program.programId)[0];
+    const listPrint = () => program.methods.listing(price, null, null)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      sellerMint: printKey,
+      collectionMint: collectionMint.publicKey,
+      collectionConfig,
+      sellerAta: anchor.utils.token.associatedAddress({ mint: printKey, owner: maker.publicKey }),
+      metadata: new anchor.web3.PublicKey(findMetadataPda(umi, { mint: printMint.publicKey })[0]),
+      vault: anchor.utils.token.associatedAddress({ mint: printKey, owner: printListing }),
+      edition: new anchor.web3.PublicKey(findMasterEditionPda(umi, { mint: printMint.publicKey })[0]),
+      listing: printListing,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+    const setPrintPolicy = (allow: boolean) => program.methods.updateMarketplace(null, null, null, null, allow)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace })
+    .rpc();
+
+    // Marketplaces start out listing originals only
+    try {
+      await listPrint();
+      assert.fail("listing a print edition should fail by default");
+    } catch (err) {
+      assert.include(err.toString(), "PrintEditionNotAllowed");
+    }
+
+    // Auctions and offers follow the same policy
+    const printMetadata = new anchor.web3.PublicKey(findMetadataPda(umi, { mint: printMint.publicKey })[0]);
+    const printEdition = new anchor.web3.PublicKey(findMasterEditionPda(umi, { mint: printMint.publicKey })[0]);
+    const printAuction = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("auction"), marketplace.toBuffer(), printKey.toBuffer()], program.programId)[0];
+    const now = Math.floor(Date.now() / 1000);
+    try {
+      await program.methods.createAuction(price, new anchor.BN(now - 5), new anchor.BN(now + 60), new anchor.BN(0), 0)
+      .accountsPartial({
+        seller: maker.publicKey,
+        marketplace,
+        mint: printKey,
+        auction: printAuction,
+        sellerAta: anchor.utils.token.associatedAddress({ mint: printKey, owner: maker.publicKey }),
+        vault: anchor.utils.token.associatedAddress({ mint: printKey, owner: printAuction }),
+        collectionMint: collectionMint.publicKey,
+        collectionConfig,
+        metadata: printMetadata,
+        edition: printEdition,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+      assert.fail("auctioning a print edition should fail by default");
+    } catch (err) {
+      assert.include(err.toString(), "PrintEditionNotAllowed");
+    }
+
+    const printOffer = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("offer"), marketplace.toBuffer(), printKey.toBuffer(), taker.publicKey.toBuffer()], program.programId)[0];
+    const offerAccounts = { buyer: taker.publicKey, marketplace, mint: printKey, offer: printOffer, paymentMint: null, buyerPaymentAta: null, escrow: null, paymentTokenProgram: null };
+    await program.methods.makeOffer(price.divn(10), null)
+    .accountsPartial(offerAccounts)
+    .signers([taker])
+    .rpc();
+    try {
+      await program.methods.acceptOffer()
+      .accountsPartial({
+        seller: maker.publicKey,
+        buyer: taker.publicKey,
+        marketplace,
+        mint: printKey,
+        collectionConfig,
+        offer: printOffer,
+        buyerAta: anchor.utils.token.associatedAddress({ mint: printKey, owner: taker.publicKey }),
+        sellerAta: anchor.utils.token.associatedAddress({ mint: printKey, owner: maker.publicKey }),
+        listing: null,
+        vault: null,
+        treasury,
+        metadata: printMetadata,
+        edition: printEdition,
+        paymentMint: null,
+        escrow: null,
+        sellerPaymentAta: null,
+        treasuryPaymentAta: null,
+        paymentTokenProgram: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(creatorAccounts)
+      .signers([maker])
+      .rpc();
+      assert.fail("selling a print edition into an offer should fail by default");
+    } catch (err) {
+      assert.include(err.toString(), "PrintEditionNotAllowed");
+    }
+    await program.methods.cancelOffer()
+    .accountsPartial(offerAccounts)
+    .signers([taker])
+    .rpc();
+
+    await setPrintPolicy(true);
+
+    // Even then a print can't skip the edition check by being listed as a quantity of 1
+    try {
+      await program.methods.listQuantity(price, new anchor.BN(1), null, null)
+      .accountsPartial({
+        seller: maker.publicKey,
+        listing: printListing,
+        sellerMint: printKey,
+        sellerAta: anchor.utils.token.associatedAddress({ mint: printKey, owner: maker.publicKey }),
+        marketplace,
+        vault: anchor.utils.token.associatedAddress({ mint: printKey, owner: printListing }),
+        collectionMint: collectionMint.publicKey,
+        collectionConfig,
+        metadata: printMetadata,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+      assert.fail("listing a print edition by quantity should fail");
+    } catch (err) {
+      assert.include(err.toString(), "NotFungibleToken");
+    }
+
+    await listPrint();
+    const printListingAccount = await program.account.listing.fetch(printListing);
+    assert.equal(printListingAccount.editionNumber.toString(), "1");
+
+    // Back to originals only for the rest of the suite
+    await setPrintPolicy(false);
   });
-  
+
   it("Listing!", async () => {
-    
+
     const nftMetadata = findMetadataPda(umi, {mint: nftMint.publicKey});
     const nftEdition = findMasterEditionPda(umi, {mint: nftMint.publicKey});
-  
+
     // Add your test here.
-    const tx = await program.methods.listing(price)
+    const tx = await program.methods.listing(price, null, null)
     .accountsPartial({
-      maker: maker.publicKey,
+      seller: maker.publicKey,
       marketplace,
-      makerMint: nftMint.publicKey,
+      sellerMint: nftMint.publicKey,
       collectionMint: collectionMint.publicKey,
-      makerAta,
+      sellerAta: makerAta,
       metadata: new anchor.web3.PublicKey(nftMetadata[0]),
       vault,
-      masterEdition: new anchor.web3.PublicKey(nftEdition[0]),
+      edition: new anchor.web3.PublicKey(nftEdition[0]),
       listing,
+      collectionConfig,
       systemProgram: anchor.web3.SystemProgram.programId,
       tokenProgram: TOKEN_PROGRAM_ID,
     })
@@ -143,17 +647,30 @@ describe("marketplace", () => {
     .rpc();
     console.log("\nListing Initialized!");
     console.log("Your transaction signature", tx);
+
+    // Originals record no edition number
+    const listingAccount = await program.account.listing.fetch(listing);
+    assert.isNull(listingAccount.editionNumber);
+
+    const event = await eventOf(tx, "Listed");
+    assert.ok(event.mint.equals(new anchor.web3.PublicKey(nftMint.publicKey)));
+    assert.ok(event.seller.equals(maker.publicKey));
+    assert.ok(event.price.eq(price));
+    assert.equal(event.quantity.toString(), "1");
+    assert.isNull(event.paymentMint);
+    assert.isNull(event.expiresAt);
+    assert.isAbove(event.timestamp.toNumber(), 0);
   });
-  
+
   // it("Delisting!", async () => {
-  
+
   //   // Add your test here.
-  //   const tx = await program.methods.delist()
+  //   const tx = await program.methods.delisting()
   //   .accountsPartial({
-  //     maker: maker.publicKey,
+  //     seller: maker.publicKey,
   //     marketplace,
-  //     makerMint: nftMint.publicKey,
-  //     makerAta,
+  //     mint: nftMint.publicKey,
+  //     sellerAta: makerAta,
   //     listing,
   //     vault,
   //     systemProgram: anchor.web3.SystemProgram.programId,
@@ -164,30 +681,2582 @@ describe("marketplace", () => {
   //   console.log("\nDelisting Initialized!");
   //   console.log("Your transaction signature", tx);
   // });
-  
+
+  it("Only the seller can reprice a listing!", async () => {
+    const reprice = (newPrice: anchor.BN, seller: Keypair, expiresAt: anchor.BN | null = null) => program.methods.updateListing(newPrice, null, expiresAt)
+    .accountsPartial({ seller: seller.publicKey, listing, mint: nftMint.publicKey, marketplace, collectionConfig })
+    .signers([seller])
+    .rpc();
+
+    // Taker can't touch the maker's listing
+    try {
+      await reprice(price.muln(2), taker);
+      assert.fail("repricing by someone other than the seller should fail");
+    } catch (err) {
+      assert.include(err.toString(), "ConstraintRaw");
+    }
+
+    // Maker doubles the price in place
+    const tx = await reprice(price.muln(2), maker);
+    console.log("\nListing Updated!");
+    console.log("Your transaction signature", tx);
+
+    const event = await eventOf(tx, "ListingUpdated");
+    assert.ok(event.seller.equals(maker.publicKey));
+    assert.ok(event.oldPrice.eq(price));
+    assert.ok(event.newPrice.eq(price.muln(2)));
+    assert.ok((await program.account.listing.fetch(listing)).price.eq(price.muln(2)));
+
+    // Expiry follows the same rule as listing: it must be in the future
+    try {
+      await reprice(price, maker, new anchor.BN(Math.floor(Date.now() / 1000) - 60));
+      assert.fail("an expiry in the past should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidExpiry");
+    }
+    const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+    const expiring = await eventOf(await reprice(price, maker, expiresAt), "ListingUpdated");
+    assert.isNull(expiring.oldExpiresAt);
+    assert.ok(expiring.newExpiresAt.eq(expiresAt));
+    assert.ok((await program.account.listing.fetch(listing)).expiresAt.eq(expiresAt));
+
+    // Back to the original price and no expiry for the purchase tests
+    await reprice(price, maker);
+    const restored = await program.account.listing.fetch(listing);
+    assert.ok(restored.price.eq(price));
+    assert.isNull(restored.expiresAt);
+  });
+
+  it("Purchase at a stale price, from another seller or over the listed quantity fails!", async () => {
+    const purchaseAccounts = {
+      buyer: taker.publicKey,
+      seller: maker.publicKey,
+      sellerMint: nftMint.publicKey,
+      marketplace,
+      buyerAta: takerAta,
+      vault,
+      listing,
+      treasury,
+      paymentMint: null,
+      buyerPaymentAta: null,
+      sellerPaymentAta: null,
+      treasuryPaymentAta: null,
+      paymentTokenProgram: null,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // Buyer saw a lower price than the listing now asks
+    try {
+      await program.methods.purchase(price.divn(2), maker.publicKey, new anchor.BN(1))
+      .accountsPartial(purchaseAccounts)
+      .remainingAccounts(creatorAccounts)
+      .signers([taker])
+      .rpc();
+      assert.fail("purchase at a different price should fail");
+    } catch (err) {
+      assert.include(err.toString(), "PriceMismatch");
+    }
+
+    // Buyer expected a different seller than the one who listed
+    try {
+      await program.methods.purchase(price, taker.publicKey, new anchor.BN(1))
+      .accountsPartial(purchaseAccounts)
+      .remainingAccounts(creatorAccounts)
+      .signers([taker])
+      .rpc();
+      assert.fail("purchase from an unexpected seller should fail");
+    } catch (err) {
+      assert.include(err.toString(), "SellerMismatch");
+    }
+
+    // Buyer asks for more than the listing holds
+    try {
+      await program.methods.purchase(price, maker.publicKey, new anchor.BN(2))
+      .accountsPartial(purchaseAccounts)
+      .remainingAccounts(creatorAccounts)
+      .signers([taker])
+      .rpc();
+      assert.fail("purchase of more than the listed quantity should fail");
+    } catch (err) {
+      assert.include(err.toString(), "InsufficientQuantity");
+    }
+  });
+
+  it("Purchase without creator accounts fails!", async () => {
+    try {
+      await program.methods.purchase(price, maker.publicKey, new anchor.BN(1))
+      .accountsPartial({
+        buyer: taker.publicKey,
+        seller: maker.publicKey,
+        sellerMint: nftMint.publicKey,
+        marketplace,
+        buyerAta: takerAta,
+        vault,
+        listing,
+        collectionConfig,
+        treasury,
+        paymentMint: null,
+        buyerPaymentAta: null,
+        sellerPaymentAta: null,
+        treasuryPaymentAta: null,
+        paymentTokenProgram: null,
+        rewardTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([taker])
+      .rpc();
+      assert.fail("purchase should require the verified creators");
+    } catch (err) {
+      assert.include(err.toString(), "MissingCreatorAccount");
+    }
+  });
+
+  it("Purchase without metadata fails for a Metaplex listing!", async () => {
+    try {
+      await program.methods.purchase(price, maker.publicKey, new anchor.BN(1))
+      .accountsPartial({
+        buyer: taker.publicKey,
+        seller: maker.publicKey,
+        sellerMint: nftMint.publicKey,
+        marketplace,
+        buyerAta: takerAta,
+        vault,
+        listing,
+        collectionConfig,
+        treasury,
+        metadata: null,
+        paymentMint: null,
+        buyerPaymentAta: null,
+        sellerPaymentAta: null,
+        treasuryPaymentAta: null,
+        paymentTokenProgram: null,
+        rewardTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([taker])
+      .rpc();
+      assert.fail("purchase should require the metadata of a Metaplex listing");
+    } catch (err) {
+      assert.include(err.toString(), "MissingMetadataAccount");
+    }
+
+    // Listing remembers it was made with metadata
+    const listingAccount = await program.account.listing.fetch(listing);
+    assert.isTrue(listingAccount.hasMetadata);
+  });
+
   it("Purchase Initialized!", async () => {
-    
+
     // Add your test here.
-    const tx = await program.methods.purchase()
+    const tx = await program.methods.purchase(price, maker.publicKey, new anchor.BN(1))
     .accountsPartial({
-      taker: taker.publicKey,
-      maker: maker.publicKey,
-      makerMint: nftMint.publicKey,
+      buyer: taker.publicKey,
+      seller: maker.publicKey,
+      sellerMint: nftMint.publicKey,
       marketplace,
-      takerAta,
+      buyerAta: takerAta,
       vault,
-      rewardsMint,
       listing,
+      collectionConfig,
       treasury,
+      paymentMint: null,
+      buyerPaymentAta: null,
+      sellerPaymentAta: null,
+      treasuryPaymentAta: null,
+      paymentTokenProgram: null,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
       systemProgram: anchor.web3.SystemProgram.programId,
       tokenProgram: TOKEN_PROGRAM_ID,
     })
+    .remainingAccounts(creatorAccounts)
     .signers([taker])
     .rpc();
     console.log("\nPurchase Initialized!");
     console.log("Your transaction signature", tx);
+
+    // Listing is closed on purchase and its rent goes back to the seller
+    assert.isNull(await connection.getAccountInfo(listing));
+
+    // Reward rates start at zero, so no reward token accounts are created
+    assert.isNull(await connection.getAccountInfo(getAssociatedTokenAddressSync(rewardsMint, taker.publicKey, true)));
+    assert.isNull(await connection.getAccountInfo(getAssociatedTokenAddressSync(rewardsMint, maker.publicKey, true)));
+
+    // Sale breaks down into the marketplace fee, royalties and what the seller keeps
+    const event = await eventOf(tx, "Sold");
+    assert.ok(event.mint.equals(new anchor.web3.PublicKey(nftMint.publicKey)));
+    assert.ok(event.buyer.equals(taker.publicKey));
+    assert.ok(event.seller.equals(maker.publicKey));
+    assert.ok(event.price.eq(price));
+    assert.ok(event.marketplaceFee.eq(price.muln(fee).divn(10000)));
+    assert.ok(event.marketplaceFee.add(event.royalty).add(event.sellerProceeds).eq(price));
+    assert.isAbove(event.timestamp.toNumber(), 0);
+  });
+
+  it("Same mint can be relisted after being bought back!", async () => {
+    const nftMetadata = new anchor.web3.PublicKey(findMetadataPda(umi, {mint: nftMint.publicKey})[0]);
+    const nftEdition = new anchor.web3.PublicKey(findMasterEditionPda(umi, {mint: nftMint.publicKey})[0]);
+
+    // Taker lists the NFT they just bought
+    await program.methods.listing(price, null, null)
+    .accountsPartial({
+      seller: taker.publicKey,
+      marketplace,
+      sellerMint: nftMint.publicKey,
+      collectionMint: collectionMint.publicKey,
+      sellerAta: takerAta,
+      metadata: nftMetadata,
+      vault,
+      edition: nftEdition,
+      listing,
+      collectionConfig,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([taker])
+    .rpc();
+
+    // Maker buys it back
+    await program.methods.purchase(price, taker.publicKey, new anchor.BN(1))
+    .accountsPartial({
+      buyer: maker.publicKey,
+      seller: taker.publicKey,
+      sellerMint: nftMint.publicKey,
+      marketplace,
+      buyerAta: makerAta,
+      vault,
+      listing,
+      collectionConfig,
+      treasury,
+      paymentMint: null,
+      buyerPaymentAta: null,
+      sellerPaymentAta: null,
+      treasuryPaymentAta: null,
+      paymentTokenProgram: null,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(creatorAccounts)
+    .signers([maker])
+    .rpc();
+
+    // Maker can list the same mint again because the old listing was closed
+    const tx = await program.methods.listing(price, null, null)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      sellerMint: nftMint.publicKey,
+      collectionMint: collectionMint.publicKey,
+      sellerAta: makerAta,
+      metadata: nftMetadata,
+      vault,
+      edition: nftEdition,
+      listing,
+      collectionConfig,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+    console.log("\nRelisted after buy back!");
+    console.log("Your transaction signature", tx);
+
+    const relisted = await program.account.listing.fetch(listing);
+    assert.ok(relisted.maker.equals(maker.publicKey));
   });
-  
+
+  it("Rewards are minted to buyer and seller once the admin sets rates!", async () => {
+    // Fresh NFT in the approved collection, minted to the maker
+    const rewardNft = generateSigner(umi);
+    await createNft(umi, {
+      mint: rewardNft,
+      name: "GM",
+      symbol: "GM",
+      uri: "https://arweave.net/123",
+      sellerFeeBasisPoints: percentAmount(5.5),
+      collection: { verified: false, key: collectionMint.publicKey },
+      tokenOwner: publicKey(maker.publicKey),
+    }).sendAndConfirm(umi);
+    await verifyCollectionV1(umi, {
+      metadata: findMetadataPda(umi, { mint: rewardNft.publicKey }),
+      collectionMint: collectionMint.publicKey,
+      authority: creator,
+    }).sendAndConfirm(umi);
+
+    // Reward base units per SOL of volume
+    const buyerRate = new anchor.BN(100);
+    const sellerRate = new anchor.BN(40);
+    await program.methods.updateMarketplace(null, null, buyerRate, sellerRate, null)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace })
+    .rpc();
+
+    const rewardKey = new anchor.web3.PublicKey(rewardNft.publicKey);
+    const rewardListing = anchor.web3.PublicKey.findProgramAddressSync([marketplace.toBuffer(), rewardKey.toBuffer()], program.programId)[0];
+    const rewardVault = anchor.utils.token.associatedAddress({ mint: rewardKey, owner: rewardListing });
+    const buyerRewardAta = getAssociatedTokenAddressSync(rewardsMint, taker.publicKey, true);
+    const sellerRewardAta = getAssociatedTokenAddressSync(rewardsMint, maker.publicKey, true);
+
+    await program.methods.listing(price, null, null)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      sellerMint: rewardKey,
+      collectionMint: collectionMint.publicKey,
+      collectionConfig,
+      sellerAta: anchor.utils.token.associatedAddress({ mint: rewardKey, owner: maker.publicKey }),
+      metadata: new anchor.web3.PublicKey(findMetadataPda(umi, { mint: rewardNft.publicKey })[0]),
+      vault: rewardVault,
+      edition: new anchor.web3.PublicKey(findMasterEditionPda(umi, { mint: rewardNft.publicKey })[0]),
+      listing: rewardListing,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+
+    const tx = await program.methods.purchase(price, maker.publicKey, new anchor.BN(1))
+    .accountsPartial({
+      buyer: taker.publicKey,
+      seller: maker.publicKey,
+      sellerMint: rewardKey,
+      marketplace,
+      buyerAta: anchor.utils.token.associatedAddress({ mint: rewardKey, owner: taker.publicKey }),
+      vault: rewardVault,
+      listing: rewardListing,
+      collectionConfig,
+      treasury,
+      buyerRewardAta,
+      sellerRewardAta,
+      paymentMint: null,
+      buyerPaymentAta: null,
+      sellerPaymentAta: null,
+      treasuryPaymentAta: null,
+      paymentTokenProgram: null,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(creatorAccounts)
+    .signers([taker])
+    .rpc();
+    console.log("\nRewards Minted!");
+    console.log("Your transaction signature", tx);
+
+    // Price is 1 SOL, so each side gets exactly its rate
+    assert.equal((await getAccount(connection, buyerRewardAta)).amount.toString(), buyerRate.toString());
+    assert.equal((await getAccount(connection, sellerRewardAta)).amount.toString(), sellerRate.toString());
+
+    // Rewards off again for the tests that follow
+    await program.methods.updateMarketplace(null, null, new anchor.BN(0), new anchor.BN(0), null)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace })
+    .rpc();
+  });
+
+  it("Royalties are capped at what the fee leaves of the price!", async () => {
+    // 95% royalty NFT in the approved collection, minted to the maker
+    const royaltyMint = generateSigner(umi);
+    await createNft(umi, {
+      mint: royaltyMint,
+      name: "GM",
+      symbol: "GM",
+      uri: "https://arweave.net/123",
+      sellerFeeBasisPoints: percentAmount(95),
+      collection: { verified: false, key: collectionMint.publicKey },
+      tokenOwner: publicKey(maker.publicKey),
+    }).sendAndConfirm(umi);
+    await verifyCollectionV1(umi, {
+      metadata: findMetadataPda(umi, { mint: royaltyMint.publicKey }),
+      collectionMint: collectionMint.publicKey,
+      authority: creator,
+    }).sendAndConfirm(umi);
+
+    // Collection charges the highest fee the marketplace allows, so royalty + fee > price
+    await program.methods.updateCollection(maxFee, null, null, true)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace, collectionConfig })
+    .rpc();
+
+    const royaltyKey = new anchor.web3.PublicKey(royaltyMint.publicKey);
+    const royaltyListing = anchor.web3.PublicKey.findProgramAddressSync([marketplace.toBuffer(), royaltyKey.toBuffer()], program.programId)[0];
+    const royaltyVault = anchor.utils.token.associatedAddress({ mint: royaltyKey, owner: royaltyListing });
+
+    await program.methods.listing(price, null, null)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      sellerMint: royaltyKey,
+      collectionMint: collectionMint.publicKey,
+      collectionConfig,
+      sellerAta: anchor.utils.token.associatedAddress({ mint: royaltyKey, owner: maker.publicKey }),
+      metadata: new anchor.web3.PublicKey(findMetadataPda(umi, { mint: royaltyMint.publicKey })[0]),
+      vault: royaltyVault,
+      edition: new anchor.web3.PublicKey(findMasterEditionPda(umi, { mint: royaltyMint.publicKey })[0]),
+      listing: royaltyListing,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+
+    const tx = await program.methods.purchase(price, maker.publicKey, new anchor.BN(1))
+    .accountsPartial({
+      buyer: taker.publicKey,
+      seller: maker.publicKey,
+      sellerMint: royaltyKey,
+      marketplace,
+      buyerAta: anchor.utils.token.associatedAddress({ mint: royaltyKey, owner: taker.publicKey }),
+      vault: royaltyVault,
+      listing: royaltyListing,
+      collectionConfig,
+      treasury,
+      paymentMint: null,
+      buyerPaymentAta: null,
+      sellerPaymentAta: null,
+      treasuryPaymentAta: null,
+      paymentTokenProgram: null,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(creatorAccounts)
+    .signers([taker])
+    .rpc();
+    console.log("\nHigh Royalty Purchase!");
+    console.log("Your transaction signature", tx);
+
+    // Fee is taken in full, the royalty gets the rest and the seller nothing
+    const event = await eventOf(tx, "Sold");
+    assert.ok(event.marketplaceFee.eq(price.muln(maxFee).divn(10000)));
+    assert.ok(event.royalty.eq(price.sub(event.marketplaceFee)));
+    assert.ok(event.sellerProceeds.isZero());
+
+    // Back to the marketplace fee for the tests that follow
+    await program.methods.updateCollection(null, null, null, true)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace, collectionConfig })
+    .rpc();
+  });
+
+  it("Bundle is sold as one lot, only while its collection is enabled!", async () => {
+    // Two NFTs in the approved collection, minted to the maker, and a third with
+    // higher royalties that can't share a lot with them
+    const bundleMints = [generateSigner(umi), generateSigner(umi)];
+    const pricierMint = generateSigner(umi);
+    const royaltyOf = (mint: KeypairSigner) => mint === pricierMint ? 10 : 5.5;
+    for (const mint of [...bundleMints, pricierMint]) {
+      await createNft(umi, {
+        mint,
+        name: "GM",
+        symbol: "GM",
+        uri: "https://arweave.net/123",
+        sellerFeeBasisPoints: percentAmount(royaltyOf(mint)),
+        collection: { verified: false, key: collectionMint.publicKey },
+        tokenOwner: publicKey(maker.publicKey),
+      }).sendAndConfirm(umi);
+      await verifyCollectionV1(umi, {
+        metadata: findMetadataPda(umi, { mint: mint.publicKey }),
+        collectionMint: collectionMint.publicKey,
+        authority: creator,
+      }).sendAndConfirm(umi);
+    }
+
+    const bundleId = new anchor.BN(1);
+    const bundleListing = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("bundle"), marketplace.toBuffer(), maker.publicKey.toBuffer(), bundleId.toArrayLike(Buffer, "le", 8)], program.programId)[0];
+    const keys = bundleMints.map((mint) => new anchor.web3.PublicKey(mint.publicKey));
+    const metadataOf = (mint: KeypairSigner) => new anchor.web3.PublicKey(findMetadataPda(umi, { mint: mint.publicKey })[0]);
+    const vaultOf = (mint: anchor.web3.PublicKey) => anchor.utils.token.associatedAddress({ mint, owner: bundleListing });
+    // Token records and rule set are only read for pNFTs, so the program ID stands in for them
+    // (and for the edition on release, which only moves pNFTs through it)
+    const none = { pubkey: program.programId, isSigner: false, isWritable: false };
+    const account = (pubkey: anchor.web3.PublicKey, isWritable = false) => ({ pubkey, isSigner: false, isWritable });
+
+    const listBundle = (nftAccounts: anchor.web3.AccountMeta[]) => program.methods.listBundle(bundleId, price, nftAccounts.length / 8)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      bundleListing,
+      collectionMint: collectionMint.publicKey,
+      collectionConfig,
+      authorizationRulesProgram: null,
+      sysvarInstructions: null,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(nftAccounts)
+    .signers([maker])
+    .rpc();
+
+    // A bundle holds 2 to MAX_BUNDLE_SIZE (5) NFTs; the count is checked before any account is read
+    for (const count of [1, 6]) {
+      try {
+        await listBundle(Array(count * 8).fill(none));
+        assert.fail(`a bundle of ${count} NFTs should be rejected`);
+      } catch (err) {
+        assert.include(err.toString(), "InvalidBundleSize");
+      }
+    }
+
+    // mint, metadata, edition, seller ATA, seller token record, vault, vault token record, rule set
+    const nftAccounts = bundleMints.flatMap((mint, i) => [
+      account(keys[i]),
+      account(metadataOf(mint), true),
+      account(editionOf(keys[i])),
+      account(anchor.utils.token.associatedAddress({ mint: keys[i], owner: maker.publicKey }), true),
+      none,
+      account(vaultOf(keys[i]), true),
+      none,
+      none,
+    ]);
+
+    // The lot pays the first NFT's royalties once, so every NFT must have the same terms
+    const pricierKey = new anchor.web3.PublicKey(pricierMint.publicKey);
+    try {
+      await listBundle([
+        ...nftAccounts.slice(0, 8),
+        account(pricierKey),
+        account(metadataOf(pricierMint), true),
+        account(editionOf(pricierKey)),
+        account(anchor.utils.token.associatedAddress({ mint: pricierKey, owner: maker.publicKey }), true),
+        none,
+        account(vaultOf(pricierKey), true),
+        none,
+        none,
+      ]);
+      assert.fail("a bundle mixing royalty terms should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "BundleRoyaltiesMismatch");
+    }
+
+    // The collection's price range applies to the bundle's total price
+    await program.methods.updateCollection(null, null, price.subn(1), true)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace, collectionConfig })
+    .rpc();
+    try {
+      await listBundle(nftAccounts);
+      assert.fail("a bundle priced above the collection's maximum should be rejected");
+    } catch (err) {
+      assert.include(err.toString(), "PriceOutOfRange");
+    }
+    await program.methods.updateCollection(null, null, null, true)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace, collectionConfig })
+    .rpc();
+
+    let tx = await listBundle(nftAccounts);
+    console.log("\nBundle Listed!");
+    console.log("Your transaction signature", tx);
+
+    const listed = await eventOf(tx, "BundleListed");
+    assert.ok(listed.bundle.equals(bundleListing));
+    assert.deepEqual(listed.mints.map((mint: anchor.web3.PublicKey) => mint.toBase58()), keys.map((key) => key.toBase58()));
+    for (const key of keys) {
+      assert.equal((await connection.getTokenAccountBalance(vaultOf(key))).value.amount, "1");
+    }
+
+    // mint, metadata, edition, vault, vault token record, buyer ATA, buyer token record, rule set
+    const purchaseBundle = () => program.methods.purchaseBundle(price, maker.publicKey)
+    .accountsPartial({
+      buyer: taker.publicKey,
+      seller: maker.publicKey,
+      marketplace,
+      bundleListing,
+      collectionConfig,
+      treasury,
+      metadata: metadataOf(bundleMints[0]),
+      authorizationRulesProgram: null,
+      sysvarInstructions: null,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts([
+      ...bundleMints.flatMap((mint, i) => [
+        account(keys[i]),
+        account(metadataOf(mint), true),
+        none,
+        account(vaultOf(keys[i]), true),
+        none,
+        account(anchor.utils.token.associatedAddress({ mint: keys[i], owner: taker.publicKey }), true),
+        none,
+        none,
+      ]),
+      ...creatorAccounts,
+    ])
+    .signers([taker])
+    .rpc();
+
+    // Paused collections can't be bought
+    await program.methods.updateCollection(null, null, null, false)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace, collectionConfig })
+    .rpc();
+    try {
+      await purchaseBundle();
+      assert.fail("buying a bundle of a disabled collection should fail");
+    } catch (err) {
+      assert.include(err.toString(), "CollectionDisabled");
+    }
+    await program.methods.updateCollection(null, null, null, true)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace, collectionConfig })
+    .rpc();
+
+    tx = await purchaseBundle();
+    console.log("\nBundle Purchased!");
+    console.log("Your transaction signature", tx);
+
+    // One payment for the lot, every NFT to the buyer and every vault closed
+    const sold = await eventOf(tx, "BundleSold");
+    assert.ok(sold.price.eq(price));
+    assert.ok(sold.marketplaceFee.add(sold.royalty).add(sold.sellerProceeds).eq(price));
+    assert.isNull(await connection.getAccountInfo(bundleListing));
+    for (const key of keys) {
+      assert.equal((await connection.getTokenAccountBalance(anchor.utils.token.associatedAddress({ mint: key, owner: taker.publicKey }))).value.amount, "1");
+      assert.isNull(await connection.getAccountInfo(vaultOf(key)));
+    }
+  });
+
+  it("Dutch listing rejects a bad schedule!", async () => {
+    const mint = await createCollectionNft(maker.publicKey);
+    const now = Math.floor(Date.now() / 1000);
+    const listDutch = (startPrice: anchor.BN, floorPrice: anchor.BN, startTime: number, endTime: number, curve: any) => program.methods.listDutch({ startPrice, floorPrice, startTime: new anchor.BN(startTime), endTime: new anchor.BN(endTime), curve }, null, null)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      sellerMint: mint,
+      collectionMint: collectionMint.publicKey,
+      sellerAta: anchor.utils.token.associatedAddress({ mint, owner: maker.publicKey }),
+      metadata: metadataOf(mint),
+      vault: vaultOf(mint),
+      edition: editionOf(mint),
+      listing: listingOf(mint),
+      collectionConfig,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+
+    const cases: [string, () => Promise<string>][] = [
+      ["a start price below the floor", () => listDutch(price.divn(10), price.divn(5), now, now + 60, { linear: {} })],
+      ["an end time equal to the start", () => listDutch(price.divn(5), price.divn(10), now, now, { linear: {} })],
+      ["an end time before the start", () => listDutch(price.divn(5), price.divn(10), now, now - 60, { linear: {} })],
+      ["a zero half-life", () => listDutch(price.divn(5), price.divn(10), now, now + 60, { exponential: { halfLife: new anchor.BN(0) } })],
+    ];
+    for (const [description, attempt] of cases) {
+      try {
+        await attempt();
+        assert.fail(`Dutch listing with ${description} should fail`);
+      } catch (err) {
+        assert.include(err.toString(), "InvalidDutchAuction", description);
+      }
+    }
+  });
+
+  it("Dutch listing price falls over time to its floor and is capped by the buyer!", async () => {
+    const startPrice = price.divn(5);
+    const floorPrice = price.divn(10);
+    const listDutch = (mint: anchor.web3.PublicKey, startTime: number, endTime: number, curve: any) => program.methods.listDutch({ startPrice, floorPrice, startTime: new anchor.BN(startTime), endTime: new anchor.BN(endTime), curve }, null, null)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      sellerMint: mint,
+      collectionMint: collectionMint.publicKey,
+      sellerAta: anchor.utils.token.associatedAddress({ mint, owner: maker.publicKey }),
+      metadata: metadataOf(mint),
+      vault: vaultOf(mint),
+      edition: editionOf(mint),
+      listing: listingOf(mint),
+      collectionConfig,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+
+    // Linear decay over a long window, so the price is still well above the floor
+    const linearMint = await createCollectionNft(maker.publicKey);
+    const now = Math.floor(Date.now() / 1000);
+    const tx = await listDutch(linearMint, now - 1, now + 60, { linear: {} });
+    console.log("\nDutch Listing Initialized!");
+    console.log("Your transaction signature", tx);
+
+    const listed = await eventOf(tx, "Listed");
+    assert.ok(listed.price.eq(startPrice));
+    assert.ok((await program.account.listing.fetch(listingOf(linearMint))).dutchAuction.floorPrice.eq(floorPrice));
+
+    // A cap at the floor is below the current price
+    try {
+      await purchaseNft(linearMint, floorPrice);
+      assert.fail("purchase capped below the current price should fail");
+    } catch (err) {
+      assert.include(err.toString(), "PriceAboveMax");
+    }
+
+    // A cap at the start price pays the decayed price, not the cap
+    await sleep(3000);
+    const linearTx = await purchaseNft(linearMint, startPrice);
+    console.log("\nDutch Listing Purchased!");
+    console.log("Your transaction signature", linearTx);
+
+    const linearSale = await eventOf(linearTx, "Sold");
+    assert.ok(linearSale.price.lt(startPrice), "price should have fallen since the start");
+    assert.ok(linearSale.price.gt(floorPrice), "price should still be above the floor");
+
+    // Exponential decay that already ran its course sells at exactly the floor
+    const exponentialMint = await createCollectionNft(maker.publicKey);
+    const later = Math.floor(Date.now() / 1000);
+    await listDutch(exponentialMint, later - 10, later + 2, { exponential: { halfLife: new anchor.BN(1) } });
+    await sleep(4000);
+    const floorTx = await purchaseNft(exponentialMint, floorPrice);
+
+    const floorSale = await eventOf(floorTx, "Sold");
+    assert.ok(floorSale.price.eq(floorPrice));
+    assert.ok(floorSale.marketplaceFee.add(floorSale.royalty).add(floorSale.sellerProceeds).eq(floorPrice));
+    assert.isNull(await connection.getAccountInfo(listingOf(exponentialMint)));
+  });
+
+  it("Quantity listing sells in parts and closes once sold out!", async () => {
+    // Semi-fungible token in the collection, five whole tokens held by the maker
+    const sft = generateSigner(umi);
+    await createFungibleAsset(umi, {
+      mint: sft,
+      name: "GM",
+      symbol: "GM",
+      uri: "https://arweave.net/123",
+      sellerFeeBasisPoints: percentAmount(5),
+      collection: { verified: false, key: collectionMint.publicKey },
+    }).sendAndConfirm(umi);
+    await mintV1(umi, {
+      mint: sft.publicKey,
+      authority: creator,
+      amount: 5,
+      tokenOwner: publicKey(maker.publicKey),
+      tokenStandard: TokenStandard.FungibleAsset,
+    }).sendAndConfirm(umi);
+    await verifyCollectionV1(umi, {
+      metadata: findMetadataPda(umi, { mint: sft.publicKey }),
+      collectionMint: collectionMint.publicKey,
+      authority: creator,
+    }).sendAndConfirm(umi);
+    const mint = new anchor.web3.PublicKey(sft.publicKey);
+    const unitPrice = price.divn(20);
+
+    const listQuantity = (quantity: number) => program.methods.listQuantity(unitPrice, new anchor.BN(quantity), null, null)
+    .accountsPartial({
+      seller: maker.publicKey,
+      listing: listingOf(mint),
+      sellerMint: mint,
+      sellerAta: anchor.utils.token.associatedAddress({ mint, owner: maker.publicKey }),
+      marketplace,
+      vault: vaultOf(mint),
+      collectionMint: collectionMint.publicKey,
+      collectionConfig,
+      metadata: metadataOf(mint),
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+
+    try {
+      await listQuantity(0);
+      assert.fail("listing no tokens should fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidQuantity");
+    }
+
+    // A regular NFT in the collection has to go through listing instead
+    const nft = generateSigner(umi);
+    await createNft(umi, {
+      mint: nft,
+      name: "GM",
+      symbol: "GM",
+      uri: "https://arweave.net/123",
+      sellerFeeBasisPoints: percentAmount(5),
+      collection: { verified: false, key: collectionMint.publicKey },
+      tokenOwner: publicKey(maker.publicKey),
+    }).sendAndConfirm(umi);
+    await verifyCollectionV1(umi, {
+      metadata: findMetadataPda(umi, { mint: nft.publicKey }),
+      collectionMint: collectionMint.publicKey,
+      authority: creator,
+    }).sendAndConfirm(umi);
+    const nftKey = new anchor.web3.PublicKey(nft.publicKey);
+    try {
+      await program.methods.listQuantity(unitPrice, new anchor.BN(1), null, null)
+      .accountsPartial({
+        seller: maker.publicKey,
+        listing: listingOf(nftKey),
+        sellerMint: nftKey,
+        sellerAta: anchor.utils.token.associatedAddress({ mint: nftKey, owner: maker.publicKey }),
+        marketplace,
+        vault: vaultOf(nftKey),
+        collectionMint: collectionMint.publicKey,
+        collectionConfig,
+        metadata: metadataOf(nftKey),
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([maker])
+      .rpc();
+      assert.fail("listing an NFT by quantity should fail");
+    } catch (err) {
+      assert.include(err.toString(), "NotFungibleToken");
+    }
+
+    const tx = await listQuantity(5);
+    console.log("\nQuantity Listing Initialized!");
+    console.log("Your transaction signature", tx);
+    assert.equal((await connection.getTokenAccountBalance(vaultOf(mint))).value.amount, "5");
+
+    // Can't buy more than is still listed
+    try {
+      await purchaseNft(mint, unitPrice, new anchor.BN(6));
+      assert.fail("buying above the listed quantity should fail");
+    } catch (err) {
+      assert.include(err.toString(), "InsufficientQuantity");
+    }
+
+    // Partial fill pays for what was bought and leaves the rest listed
+    const partialTx = await purchaseNft(mint, unitPrice, new anchor.BN(2));
+    console.log("\nQuantity Listing Partially Purchased!");
+    console.log("Your transaction signature", partialTx);
+
+    const partial = await eventOf(partialTx, "Sold");
+    assert.equal(partial.quantity.toString(), "2");
+    assert.ok(partial.price.eq(unitPrice.muln(2)));
+    assert.equal((await program.account.listing.fetch(listingOf(mint))).quantity.toString(), "3");
+    assert.equal((await connection.getTokenAccountBalance(vaultOf(mint))).value.amount, "3");
+
+    // What's left can't be oversold either
+    try {
+      await purchaseNft(mint, unitPrice, new anchor.BN(4));
+      assert.fail("buying above the remaining quantity should fail");
+    } catch (err) {
+      assert.include(err.toString(), "InsufficientQuantity");
+    }
+
+    // Buying the rest closes the listing and its vault
+    const lastTx = await purchaseNft(mint, unitPrice, new anchor.BN(3));
+    const last = await eventOf(lastTx, "Sold");
+    assert.ok(last.price.eq(unitPrice.muln(3)));
+    assert.isNull(await connection.getAccountInfo(listingOf(mint)));
+    assert.isNull(await connection.getAccountInfo(vaultOf(mint)));
+    assert.equal((await connection.getTokenAccountBalance(anchor.utils.token.associatedAddress({ mint, owner: taker.publicKey }))).value.amount, "5");
+  });
+
+  it("Payment mint allowlist holds at most eight mints and only the admin changes it!", async () => {
+    const addPaymentMint = (mint: anchor.web3.PublicKey, admin?: Keypair) => program.methods.addPaymentMint()
+    .accountsPartial({ admin: admin ? admin.publicKey : provider.wallet.publicKey, marketplace, paymentMint: mint })
+    .signers(admin ? [admin] : [])
+    .rpc();
+    const removePaymentMint = (mint: anchor.web3.PublicKey) => program.methods.removePaymentMint(mint)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace })
+    .rpc();
+
+    const mints: anchor.web3.PublicKey[] = [];
+    for (let i = 0; i < 9; i++) {
+      mints.push(await createMint(connection, payer.payer, provider.wallet.publicKey, null, 6));
+    }
+
+    // Only the admin can change the allowlist
+    try {
+      await addPaymentMint(mints[0], maker);
+      assert.fail("non-admin adding a payment mint should fail");
+    } catch (err) {
+      assert.include(err.toString(), "ConstraintHasOne");
+    }
+
+    const tx = await addPaymentMint(mints[0]);
+    console.log("\nPayment Mint Added!");
+    console.log("Your transaction signature", tx);
+    const added = await eventOf(tx, "PaymentMintUpdated");
+    assert.ok(added.mint.equals(mints[0]));
+    assert.isTrue(added.allowed);
+
+    try {
+      await addPaymentMint(mints[0]);
+      assert.fail("adding the same payment mint twice should fail");
+    } catch (err) {
+      assert.include(err.toString(), "PaymentMintAlreadyAllowed");
+    }
+
+    // Fill the allowlist up to its limit, then one more is refused
+    for (const mint of mints.slice(1, 8)) {
+      await addPaymentMint(mint);
+    }
+    assert.equal((await program.account.marketplace.fetch(marketplace)).paymentMints.length, 8);
+    try {
+      await addPaymentMint(mints[8]);
+      assert.fail("a ninth payment mint should fail");
+    } catch (err) {
+      assert.include(err.toString(), "TooManyPaymentMints");
+    }
+
+    // Removing one makes room again
+    const removeTx = await removePaymentMint(mints[7]);
+    const removed = await eventOf(removeTx, "PaymentMintUpdated");
+    assert.ok(removed.mint.equals(mints[7]));
+    assert.isFalse(removed.allowed);
+    await addPaymentMint(mints[8]);
+
+    // Leave the allowlist empty for the tests that follow
+    for (const mint of [...mints.slice(0, 7), mints[8]]) {
+      await removePaymentMint(mint);
+    }
+    assert.equal((await program.account.marketplace.fetch(marketplace)).paymentMints.length, 0);
+  });
+
+  it("Token-priced listing pays the seller, creators and treasury in its mint!", async () => {
+    // Six-decimal payment token, with a stash for the taker and an account for the creator
+    const paymentMint = await createMint(connection, payer.payer, provider.wallet.publicKey, null, 6);
+    const takerPaymentAta = await createAssociatedTokenAccountIdempotent(connection, payer.payer, paymentMint, taker.publicKey);
+    const creatorPaymentAta = await createAssociatedTokenAccountIdempotent(connection, payer.payer, paymentMint, provider.wallet.publicKey);
+    await mintTo(connection, payer.payer, paymentMint, takerPaymentAta, payer.payer, 10_000_000);
+    const makerPaymentAta = getAssociatedTokenAddressSync(paymentMint, maker.publicKey);
+    const treasuryPaymentAta = getAssociatedTokenAddressSync(paymentMint, treasury, true);
+    const tokenPrice = new anchor.BN(2_000_000);
+    const mint = await createCollectionNft(maker.publicKey);
+
+    // Mints the admin hasn't accepted can't price a listing
+    try {
+      await listNft(mint, tokenPrice, paymentMint);
+      assert.fail("listing in a mint that isn't allowed should fail");
+    } catch (err) {
+      assert.include(err.toString(), "PaymentMintNotAllowed");
+    }
+
+    await program.methods.addPaymentMint()
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace, paymentMint })
+    .rpc();
+    const tx = await listNft(mint, tokenPrice, paymentMint);
+    console.log("\nToken Priced Listing Initialized!");
+    console.log("Your transaction signature", tx);
+    assert.ok((await eventOf(tx, "Listed")).paymentMint.equals(paymentMint));
+
+    const purchase = (withPaymentAccounts: boolean) => program.methods.purchase(tokenPrice, maker.publicKey, new anchor.BN(1))
+    .accountsPartial({
+      buyer: taker.publicKey,
+      seller: maker.publicKey,
+      sellerMint: mint,
+      marketplace,
+      buyerAta: anchor.utils.token.associatedAddress({ mint, owner: taker.publicKey }),
+      vault: vaultOf(mint),
+      listing: listingOf(mint),
+      collectionConfig,
+      treasury,
+      metadata: metadataOf(mint),
+      paymentMint: withPaymentAccounts ? paymentMint : null,
+      buyerPaymentAta: withPaymentAccounts ? takerPaymentAta : null,
+      sellerPaymentAta: withPaymentAccounts ? makerPaymentAta : null,
+      treasuryPaymentAta: withPaymentAccounts ? treasuryPaymentAta : null,
+      paymentTokenProgram: withPaymentAccounts ? TOKEN_PROGRAM_ID : null,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts([{ pubkey: withPaymentAccounts ? creatorPaymentAta : provider.wallet.publicKey, isSigner: false, isWritable: true }])
+    .signers([taker])
+    .rpc();
+
+    // Paying in SOL for a token-priced listing is refused
+    try {
+      await purchase(false);
+      assert.fail("paying in SOL for a token listing should fail");
+    } catch (err) {
+      assert.include(err.toString(), "MissingPaymentAccount");
+    }
+
+    const purchaseTx = await purchase(true);
+    console.log("\nToken Priced Listing Purchased!");
+    console.log("Your transaction signature", purchaseTx);
+
+    // Every party is paid in the payment mint
+    const event = await eventOf(purchaseTx, "Sold");
+    assert.ok(event.price.eq(tokenPrice));
+    assert.ok(event.marketplaceFee.eq(tokenPrice.muln(fee).divn(10000)));
+    assert.ok(event.royalty.eq(tokenPrice.muln(500).divn(10000)));
+    assert.ok(event.sellerProceeds.eq(tokenPrice.sub(event.marketplaceFee).sub(event.royalty)));
+    assert.equal((await getAccount(connection, takerPaymentAta)).amount.toString(), (10_000_000 - tokenPrice.toNumber()).toString());
+    assert.equal((await getAccount(connection, makerPaymentAta)).amount.toString(), event.sellerProceeds.toString());
+    assert.equal((await getAccount(connection, creatorPaymentAta)).amount.toString(), event.royalty.toString());
+    assert.equal((await getAccount(connection, treasuryPaymentAta)).amount.toString(), event.marketplaceFee.toString());
+
+    // Admin takes the token fees out of the treasury
+    const destination = await createAssociatedTokenAccountIdempotent(connection, payer.payer, paymentMint, Keypair.generate().publicKey);
+    const withdrawTokens = (amount: anchor.BN | null, admin?: Keypair) => program.methods.withdrawTreasuryTokens(amount)
+    .accountsPartial({
+      admin: admin ? admin.publicKey : provider.wallet.publicKey,
+      marketplace,
+      treasury,
+      paymentMint,
+      treasuryAta: treasuryPaymentAta,
+      destination,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers(admin ? [admin] : [])
+    .rpc();
+
+    try {
+      await withdrawTokens(null, maker);
+      assert.fail("non-admin withdrawing token fees should fail");
+    } catch (err) {
+      assert.include(err.toString(), "ConstraintHasOne");
+    }
+    try {
+      await withdrawTokens(event.marketplaceFee.addn(1));
+      assert.fail("withdrawing more than the treasury holds should fail");
+    } catch (err) {
+      assert.include(err.toString(), "InsufficientTreasuryBalance");
+    }
+
+    const withdrawTx = await withdrawTokens(null);
+    console.log("\nTreasury Tokens Withdrawn!");
+    console.log("Your transaction signature", withdrawTx);
+
+    const withdrawn = await eventOf(withdrawTx, "TreasuryWithdrawn");
+    assert.ok(withdrawn.mint.equals(paymentMint));
+    assert.ok(withdrawn.amount.eq(event.marketplaceFee));
+    assert.equal(withdrawn.remaining.toString(), "0");
+    assert.equal((await getAccount(connection, destination)).amount.toString(), event.marketplaceFee.toString());
+    assert.equal((await getAccount(connection, treasuryPaymentAta)).amount.toString(), "0");
+
+    await program.methods.removePaymentMint(paymentMint)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace })
+    .rpc();
+  });
+
+  it("Offer escrows the bid and cancelling refunds it!", async () => {
+    const mint = await createCollectionNft(maker.publicKey);
+    const offer = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("offer"), marketplace.toBuffer(), mint.toBuffer(), taker.publicKey.toBuffer()], program.programId)[0];
+    const bid = price.divn(10);
+
+    const tx = await program.methods.makeOffer(bid, null)
+    .accountsPartial({
+      buyer: taker.publicKey,
+      marketplace,
+      mint,
+      offer,
+      paymentMint: null,
+      buyerPaymentAta: null,
+      escrow: null,
+      paymentTokenProgram: null,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([taker])
+    .rpc();
+    console.log("\nOffer Made!");
+    console.log("Your transaction signature", tx);
+
+    // SOL bids sit in the offer account on top of its rent
+    const offerAccount = await program.account.offer.fetch(offer);
+    assert.ok(offerAccount.buyer.equals(taker.publicKey));
+    assert.ok(offerAccount.price.eq(bid));
+    assert.isNull(offerAccount.paymentMint);
+    const offerLamports = await connection.getBalance(offer);
+    const rent = await connection.getMinimumBalanceForRentExemption((await connection.getAccountInfo(offer)).data.length);
+    assert.equal(offerLamports, rent + bid.toNumber());
+
+    // Only the bidder can withdraw it
+    try {
+      await program.methods.cancelOffer()
+      .accountsPartial({ buyer: maker.publicKey, marketplace, mint, offer, paymentMint: null, buyerPaymentAta: null, escrow: null, paymentTokenProgram: null })
+      .signers([maker])
+      .rpc();
+      assert.fail("cancelling someone else's offer should fail");
+    } catch (err) {
+      assert.include(err.toString(), "Constraint");
+    }
+
+    // Cancelling hands back the bid and the rent
+    const takerBefore = await connection.getBalance(taker.publicKey);
+    const cancelTx = await program.methods.cancelOffer()
+    .accountsPartial({ buyer: taker.publicKey, marketplace, mint, offer, paymentMint: null, buyerPaymentAta: null, escrow: null, paymentTokenProgram: null })
+    .signers([taker])
+    .rpc();
+    console.log("\nOffer Cancelled!");
+    console.log("Your transaction signature", cancelTx);
+
+    assert.isNull(await connection.getAccountInfo(offer));
+    assert.equal(await connection.getBalance(taker.publicKey), takerBefore + offerLamports);
+  });
+
+  it("Offer is accepted from the seller's wallet or out of the listing vault!", async () => {
+    const bid = price.divn(10);
+    const makeOffer = async (mint: anchor.web3.PublicKey) => {
+      const offer = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("offer"), marketplace.toBuffer(), mint.toBuffer(), taker.publicKey.toBuffer()], program.programId)[0];
+      await program.methods.makeOffer(bid, null)
+      .accountsPartial({ buyer: taker.publicKey, marketplace, mint, offer, paymentMint: null, buyerPaymentAta: null, escrow: null, paymentTokenProgram: null })
+      .signers([taker])
+      .rpc();
+      return offer;
+    };
+    const acceptOffer = (mint: anchor.web3.PublicKey, offer: anchor.web3.PublicKey, listed: boolean) => program.methods.acceptOffer()
+    .accountsPartial({
+      seller: maker.publicKey,
+      buyer: taker.publicKey,
+      marketplace,
+      mint,
+      collectionConfig,
+      offer,
+      buyerAta: anchor.utils.token.associatedAddress({ mint, owner: taker.publicKey }),
+      sellerAta: listed ? null : anchor.utils.token.associatedAddress({ mint, owner: maker.publicKey }),
+      listing: listed ? listingOf(mint) : null,
+      vault: listed ? vaultOf(mint) : null,
+      treasury,
+      metadata: metadataOf(mint),
+      edition: editionOf(mint),
+      paymentMint: null,
+      escrow: null,
+      sellerPaymentAta: null,
+      treasuryPaymentAta: null,
+      paymentTokenProgram: null,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(creatorAccounts)
+    .signers([maker])
+    .rpc();
+
+    // Unlisted NFT comes straight out of the seller's wallet
+    const walletMint = await createCollectionNft(maker.publicKey);
+    const walletOffer = await makeOffer(walletMint);
+    const offerLamports = await connection.getBalance(walletOffer);
+    const takerBefore = await connection.getBalance(taker.publicKey);
+    const treasuryBefore = await connection.getBalance(treasury);
+    const tx = await acceptOffer(walletMint, walletOffer, false);
+    console.log("\nOffer Accepted From Wallet!");
+    console.log("Your transaction signature", tx);
+
+    // Same fee and royalty split as a purchase, and the offer rent goes back to the buyer
+    const event = await eventOf(tx, "OfferAccepted");
+    assert.ok(event.price.eq(bid));
+    assert.ok(event.marketplaceFee.eq(bid.muln(fee).divn(10000)));
+    assert.ok(event.royalty.gtn(0));
+    assert.ok(event.marketplaceFee.add(event.royalty).add(event.sellerProceeds).eq(bid));
+    assert.equal(await connection.getBalance(treasury), treasuryBefore + event.marketplaceFee.toNumber());
+    assert.equal(await connection.getBalance(taker.publicKey), takerBefore + offerLamports - bid.toNumber());
+    assert.isNull(await connection.getAccountInfo(walletOffer));
+    assert.equal((await connection.getTokenAccountBalance(anchor.utils.token.associatedAddress({ mint: walletMint, owner: taker.publicKey }))).value.amount, "1");
+    assert.equal((await connection.getTokenAccountBalance(anchor.utils.token.associatedAddress({ mint: walletMint, owner: maker.publicKey }))).value.amount, "0");
+
+    // Listed NFT comes out of the vault, closing the listing along the way
+    const listedMint = await createCollectionNft(maker.publicKey);
+    await listNft(listedMint);
+    const listedOffer = await makeOffer(listedMint);
+
+    // The listed NFT isn't in the seller's wallet any more
+    try {
+      await acceptOffer(listedMint, listedOffer, false);
+      assert.fail("accepting from the wallet while listed should fail");
+    } catch (err) {
+      assert.include(err.toString(), "custom program error: 0x1");
+    }
+
+    const listedTx = await acceptOffer(listedMint, listedOffer, true);
+    console.log("\nOffer Accepted From Listing!");
+    console.log("Your transaction signature", listedTx);
+
+    assert.isNull(await connection.getAccountInfo(listedOffer));
+    assert.isNull(await connection.getAccountInfo(listingOf(listedMint)));
+    assert.isNull(await connection.getAccountInfo(vaultOf(listedMint)));
+    assert.equal((await connection.getTokenAccountBalance(anchor.utils.token.associatedAddress({ mint: listedMint, owner: taker.publicKey }))).value.amount, "1");
+  });
+
+  it("Collection offer is filled by any verified member until its quantity runs out!", async () => {
+    const collectionOffer = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("collection_offer"), marketplace.toBuffer(), new anchor.web3.PublicKey(collectionMint.publicKey).toBuffer(), taker.publicKey.toBuffer()], program.programId)[0];
+    const bid = price.divn(10);
+
+    const makeCollectionOffer = (quantity: number) => program.methods.makeCollectionOffer(bid, new anchor.BN(quantity), null)
+    .accountsPartial({
+      buyer: taker.publicKey,
+      marketplace,
+      collectionMint: collectionMint.publicKey,
+      collectionOffer,
+      paymentMint: null,
+      buyerPaymentAta: null,
+      escrow: null,
+      paymentTokenProgram: null,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([taker])
+    .rpc();
+    const acceptCollectionOffer = (mint: anchor.web3.PublicKey) => program.methods.acceptCollectionOffer()
+    .accountsPartial({
+      seller: maker.publicKey,
+      buyer: taker.publicKey,
+      marketplace,
+      collectionMint: collectionMint.publicKey,
+      collectionConfig,
+      mint,
+      collectionOffer,
+      buyerAta: anchor.utils.token.associatedAddress({ mint, owner: taker.publicKey }),
+      sellerAta: anchor.utils.token.associatedAddress({ mint, owner: maker.publicKey }),
+      listing: null,
+      vault: null,
+      treasury,
+      metadata: metadataOf(mint),
+      edition: editionOf(mint),
+      paymentMint: null,
+      escrow: null,
+      sellerPaymentAta: null,
+      treasuryPaymentAta: null,
+      paymentTokenProgram: null,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(creatorAccounts)
+    .signers([maker])
+    .rpc();
+
+    // A floor bid needs at least one NFT
+    try {
+      await makeCollectionOffer(0);
+      assert.fail("collection offer for no NFTs should fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidQuantity");
+    }
+
+    const tx = await makeCollectionOffer(2);
+    console.log("\nCollection Offer Made!");
+    console.log("Your transaction signature", tx);
+
+    // Every unit is escrowed up front
+    const rent = await connection.getMinimumBalanceForRentExemption((await connection.getAccountInfo(collectionOffer)).data.length);
+    assert.equal(await connection.getBalance(collectionOffer), rent + bid.muln(2).toNumber());
+    const made = await eventOf(tx, "CollectionOfferMade");
+    assert.equal(made.quantity.toString(), "2");
+
+    // NFTs from another collection can't fill it
+    const outsider = generateSigner(umi);
+    await createNft(umi, {
+      mint: outsider,
+      name: "GM",
+      symbol: "GM",
+      uri: "https://arweave.net/123",
+      sellerFeeBasisPoints: percentAmount(5),
+      collection: { verified: false, key: generateSigner(umi).publicKey },
+      tokenOwner: publicKey(maker.publicKey),
+    }).sendAndConfirm(umi);
+    try {
+      await acceptCollectionOffer(new anchor.web3.PublicKey(outsider.publicKey));
+      assert.fail("filling with an NFT from another collection should fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidCollection");
+    }
+
+    // First fill pays one unit and leaves the offer open
+    const first = await createCollectionNft(maker.publicKey);
+    const firstTx = await acceptCollectionOffer(first);
+    console.log("\nCollection Offer Filled!");
+    console.log("Your transaction signature", firstTx);
+
+    const firstFill = await eventOf(firstTx, "CollectionOfferFilled");
+    assert.ok(firstFill.mint.equals(first));
+    assert.ok(firstFill.price.eq(bid));
+    assert.equal(firstFill.remainingQuantity.toString(), "1");
+    assert.ok(firstFill.marketplaceFee.add(firstFill.royalty).add(firstFill.sellerProceeds).eq(bid));
+    assert.equal((await program.account.collectionOffer.fetch(collectionOffer)).quantity.toString(), "1");
+    assert.equal(await connection.getBalance(collectionOffer), rent + bid.toNumber());
+    assert.equal((await connection.getTokenAccountBalance(anchor.utils.token.associatedAddress({ mint: first, owner: taker.publicKey }))).value.amount, "1");
+
+    // Last fill closes the offer and hands the rent back to the buyer
+    const second = await createCollectionNft(maker.publicKey);
+    const takerBefore = await connection.getBalance(taker.publicKey);
+    const secondTx = await acceptCollectionOffer(second);
+    const secondFill = await eventOf(secondTx, "CollectionOfferFilled");
+    assert.equal(secondFill.remainingQuantity.toString(), "0");
+    assert.isNull(await connection.getAccountInfo(collectionOffer));
+    assert.equal(await connection.getBalance(taker.publicKey), takerBefore + rent);
+
+    // Nothing left to fill
+    const third = await createCollectionNft(maker.publicKey);
+    try {
+      await acceptCollectionOffer(third);
+      assert.fail("filling a closed collection offer should fail");
+    } catch (err) {
+      assert.include(err.toString(), "AccountNotInitialized");
+    }
+
+    // A fresh offer can be withdrawn with whatever is still unfilled
+    await makeCollectionOffer(3);
+    await acceptCollectionOffer(third);
+    const offerLamports = await connection.getBalance(collectionOffer);
+    const beforeCancel = await connection.getBalance(taker.publicKey);
+    const cancelTx = await program.methods.cancelCollectionOffer()
+    .accountsPartial({
+      buyer: taker.publicKey,
+      marketplace,
+      collectionMint: collectionMint.publicKey,
+      collectionOffer,
+      paymentMint: null,
+      buyerPaymentAta: null,
+      escrow: null,
+      paymentTokenProgram: null,
+    })
+    .signers([taker])
+    .rpc();
+    console.log("\nCollection Offer Cancelled!");
+    console.log("Your transaction signature", cancelTx);
+
+    const cancelled = await eventOf(cancelTx, "CollectionOfferCancelled");
+    assert.equal(cancelled.unfilledQuantity.toString(), "2");
+    assert.equal(offerLamports, rent + bid.muln(2).toNumber());
+    assert.equal(await connection.getBalance(taker.publicKey), beforeCancel + offerLamports);
+    assert.isNull(await connection.getAccountInfo(collectionOffer));
+  });
+
+  it("Auction takes bids above the increment, refunds the outbid and extends on late bids!", async () => {
+    const mint = await createCollectionNft(maker.publicKey);
+    const auction = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("auction"), marketplace.toBuffer(), mint.toBuffer()], program.programId)[0];
+    const auctionVault = anchor.utils.token.associatedAddress({ mint, owner: auction });
+    const reserve = price.divn(10);
+    const bidder = provider.wallet.publicKey;
+
+    const createAuction = (startTime: number, endTime: number, extensionWindow: number, incrementBps: number) => program.methods.createAuction(reserve, new anchor.BN(startTime), new anchor.BN(endTime), new anchor.BN(extensionWindow), incrementBps)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      mint,
+      auction,
+      sellerAta: anchor.utils.token.associatedAddress({ mint, owner: maker.publicKey }),
+      vault: auctionVault,
+      collectionMint: collectionMint.publicKey,
+      collectionConfig,
+      metadata: metadataOf(mint),
+      edition: editionOf(mint),
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+
+    // Bad timing or an increment above the cap is rejected
+    const now = Math.floor(Date.now() / 1000);
+    const cases: [string, () => Promise<string>, string][] = [
+      ["an end before the start", () => createAuction(now + 10, now + 5, 0, 1000), "InvalidAuctionTimes"],
+      ["an extension window longer than the auction", () => createAuction(now - 5, now + 5, 20, 1000), "InvalidAuctionTimes"],
+      ["an increment above the cap", () => createAuction(now - 5, now + 5, 0, 10001), "InvalidBidIncrement"],
+    ];
+    for (const [label, attempt, error] of cases) {
+      try {
+        await attempt();
+        assert.fail(`auction with ${label} should fail`);
+      } catch (err) {
+        assert.include(err.toString(), error, label);
+      }
+    }
+
+    // 10% increment, and every bid lands inside the extension window
+    const endTime = now + 6;
+    const tx = await createAuction(now - 5, endTime, 10, 1000);
+    console.log("\nAuction Created!");
+    console.log("Your transaction signature", tx);
+    const created = await program.account.auction.fetch(auction);
+    assert.equal(created.minBidIncrementBps, 1000);
+    assert.equal(created.maxEndTime.toNumber(), endTime + 10 * 10);
+
+    const placeBid = (amount: anchor.BN, signer?: Keypair, previousBidder: anchor.web3.PublicKey | null = null) => program.methods.placeBid(amount)
+    .accountsPartial({
+      bidder: signer ? signer.publicKey : bidder,
+      previousBidder,
+      marketplace,
+      mint,
+      auction,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers(signer ? [signer] : [])
+    .rpc();
+
+    // First bid must meet the reserve
+    try {
+      await placeBid(reserve.subn(1), taker);
+      assert.fail("bid below the reserve should fail");
+    } catch (err) {
+      assert.include(err.toString(), "BidTooLow");
+    }
+    const firstBid = await placeBid(reserve, taker);
+    const extended = await eventOf(firstBid, "BidPlaced");
+    assert.isAbove(extended.endTime.toNumber(), endTime);
+
+    // Outbidding needs 10% more than the high bid
+    try {
+      await placeBid(reserve.muln(105).divn(100), undefined, taker.publicKey);
+      assert.fail("bid below the increment should fail");
+    } catch (err) {
+      assert.include(err.toString(), "BidTooLow");
+    }
+
+    // The outbid taker gets their escrowed bid back
+    const takerBefore = await connection.getBalance(taker.publicKey);
+    const secondBid = reserve.muln(11).divn(10);
+    await placeBid(secondBid, undefined, taker.publicKey);
+    assert.equal(await connection.getBalance(taker.publicKey), takerBefore + reserve.toNumber());
+
+    const auctionAccount = await program.account.auction.fetch(auction);
+    assert.ok(auctionAccount.highestBid.eq(secondBid));
+    assert.ok(auctionAccount.highestBidder.equals(bidder));
+    assert.isAtMost(auctionAccount.endTime.toNumber(), auctionAccount.maxEndTime.toNumber());
+
+    // Can't settle while bidding is open
+    const winnerAta = anchor.utils.token.associatedAddress({ mint, owner: bidder });
+    const settle = () => program.methods.settleAuction()
+    .accountsPartial({
+      payer: taker.publicKey,
+      seller: maker.publicKey,
+      winner: bidder,
+      marketplace,
+      mint,
+      auction,
+      collectionConfig,
+      vault: auctionVault,
+      winnerAta,
+      sellerAta: null,
+      treasury,
+      metadata: metadataOf(mint),
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(creatorAccounts)
+    .signers([taker])
+    .rpc();
+    try {
+      await settle();
+      assert.fail("settling an open auction should fail");
+    } catch (err) {
+      assert.include(err.toString(), "AuctionNotEnded");
+    }
+
+    await sleep(Math.max(0, auctionAccount.endTime.toNumber() * 1000 - Date.now()) + 2000);
+    const settleTx = await settle();
+    console.log("\nAuction Settled!");
+    console.log("Your transaction signature", settleTx);
+
+    // Winning bid pays the fee and royalties, the winner gets the NFT
+    const event = await eventOf(settleTx, "AuctionSettled");
+    assert.ok(event.price.eq(secondBid));
+    assert.ok(event.marketplaceFee.eq(secondBid.muln(fee).divn(10000)));
+    assert.ok(event.royalty.gtn(0));
+    assert.ok(event.marketplaceFee.add(event.royalty).add(event.sellerProceeds).eq(secondBid));
+    assert.isNull(await connection.getAccountInfo(auction));
+    assert.equal((await connection.getTokenAccountBalance(winnerAta)).value.amount, "1");
+  });
+
+  it("Auction without bids settles the NFT back to the seller!", async () => {
+    const mint = await createCollectionNft(maker.publicKey);
+    const auction = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("auction"), marketplace.toBuffer(), mint.toBuffer()], program.programId)[0];
+    const auctionVault = anchor.utils.token.associatedAddress({ mint, owner: auction });
+    const sellerAta = anchor.utils.token.associatedAddress({ mint, owner: maker.publicKey });
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods.createAuction(price, new anchor.BN(now - 5), new anchor.BN(now + 2), new anchor.BN(0), 500)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      mint,
+      auction,
+      sellerAta,
+      vault: auctionVault,
+      collectionMint: collectionMint.publicKey,
+      collectionConfig,
+      metadata: metadataOf(mint),
+      edition: editionOf(mint),
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+    assert.equal((await connection.getTokenAccountBalance(auctionVault)).value.amount, "1");
+    await sleep(4000);
+
+    // Nobody met the reserve, so there is no winner and nothing to pay
+    const tx = await program.methods.settleAuction()
+    .accountsPartial({
+      payer: taker.publicKey,
+      seller: maker.publicKey,
+      winner: null,
+      marketplace,
+      mint,
+      auction,
+      collectionConfig,
+      vault: auctionVault,
+      winnerAta: null,
+      sellerAta,
+      treasury,
+      metadata: metadataOf(mint),
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(creatorAccounts)
+    .signers([taker])
+    .rpc();
+    console.log("\nAuction Without Bids Settled!");
+    console.log("Your transaction signature", tx);
+
+    const event = await eventOf(tx, "AuctionSettled");
+    assert.isNull(event.winner);
+    assert.ok(event.price.isZero());
+    assert.ok(event.sellerProceeds.isZero());
+    assert.isNull(await connection.getAccountInfo(auction));
+    assert.isNull(await connection.getAccountInfo(auctionVault));
+    assert.equal((await connection.getTokenAccountBalance(sellerAta)).value.amount, "1");
+  });
+
+  it("Auction with a bid settles after its collection is disabled!", async () => {
+    const mint = await createCollectionNft(maker.publicKey);
+    const auction = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("auction"), marketplace.toBuffer(), mint.toBuffer()], program.programId)[0];
+    const auctionVault = anchor.utils.token.associatedAddress({ mint, owner: auction });
+    const bid = price.divn(10);
+
+    // Short auction without anti-sniping, so it ends on its own
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods.createAuction(bid, new anchor.BN(now - 5), new anchor.BN(now + 3), new anchor.BN(0), 0)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      mint,
+      auction,
+      sellerAta: anchor.utils.token.associatedAddress({ mint, owner: maker.publicKey }),
+      vault: auctionVault,
+      collectionMint: collectionMint.publicKey,
+      collectionConfig,
+      metadata: metadataOf(mint),
+      edition: editionOf(mint),
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+
+    await program.methods.placeBid(bid)
+    .accountsPartial({ bidder: taker.publicKey, previousBidder: null, marketplace, mint, auction, systemProgram: anchor.web3.SystemProgram.programId })
+    .signers([taker])
+    .rpc();
+
+    // Admin pauses the collection while the winning bid sits in escrow
+    await program.methods.updateCollection(null, null, null, false)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace, collectionConfig })
+    .rpc();
+    await sleep(5000);
+
+    const winnerAta = anchor.utils.token.associatedAddress({ mint, owner: taker.publicKey });
+    const tx = await program.methods.settleAuction()
+    .accountsPartial({
+      payer: maker.publicKey,
+      seller: maker.publicKey,
+      winner: taker.publicKey,
+      marketplace,
+      mint,
+      auction,
+      collectionConfig,
+      vault: auctionVault,
+      winnerAta,
+      sellerAta: null,
+      treasury,
+      metadata: metadataOf(mint),
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(creatorAccounts)
+    .signers([maker])
+    .rpc();
+    console.log("\nAuction Settled In A Disabled Collection!");
+    console.log("Your transaction signature", tx);
+
+    const event = await eventOf(tx, "AuctionSettled");
+    assert.ok(event.winner.equals(taker.publicKey));
+    assert.ok(event.marketplaceFee.eq(bid.muln(fee).divn(10000)));
+    assert.isNull(await connection.getAccountInfo(auction));
+    assert.equal((await connection.getTokenAccountBalance(winnerAta)).value.amount, "1");
+
+    await program.methods.updateCollection(null, null, null, true)
+    .accountsPartial({ admin: provider.wallet.publicKey, marketplace, collectionConfig })
+    .rpc();
+  });
+
+  it("Admin withdraws collected fees!", async () => {
+    const destination = Keypair.generate().publicKey;
+    const rentExempt = await connection.getMinimumBalanceForRentExemption(0);
+    const treasuryBefore = await connection.getBalance(treasury);
+    assert.isAbove(treasuryBefore, rentExempt, "purchase should have paid fees into the treasury");
+
+    // Withdraw everything above the rent-exempt minimum
+    const tx = await program.methods.withdrawTreasury(null)
+    .accountsPartial({
+      admin: provider.wallet.publicKey,
+      marketplace,
+      treasury,
+      destination,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+    console.log("\nTreasury Withdrawn!");
+    console.log("Your transaction signature", tx);
+
+    assert.equal(await connection.getBalance(treasury), rentExempt);
+    assert.equal(await connection.getBalance(destination), treasuryBefore - rentExempt);
+  });
+
+  it("Non-admin cannot withdraw fees!", async () => {
+    try {
+      await program.methods.withdrawTreasury(new anchor.BN(1))
+      .accountsPartial({
+        admin: taker.publicKey,
+        marketplace,
+        treasury,
+        destination: taker.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([taker])
+      .rpc();
+      assert.fail("withdrawal by a non-admin should fail");
+    } catch (err) {
+      assert.include(err.toString(), "ConstraintHasOne");
+    }
+  });
+
+  it("Expired listing can't be bought and is cranked back to the seller!", async () => {
+    const nftMetadata = new anchor.web3.PublicKey(findMetadataPda(umi, {mint: nftMint.publicKey})[0]);
+    const nftEdition = new anchor.web3.PublicKey(findMasterEditionPda(umi, {mint: nftMint.publicKey})[0]);
+
+    // Take down the open-ended listing left by the relist test
+    await program.methods.delisting()
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      mint: nftMint.publicKey,
+      sellerAta: makerAta,
+      listing,
+      vault,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+
+    // Relist with an expiry a couple of seconds out
+    const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 2);
+    await program.methods.listing(price, null, expiresAt)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      sellerMint: nftMint.publicKey,
+      collectionMint: collectionMint.publicKey,
+      sellerAta: makerAta,
+      metadata: nftMetadata,
+      vault,
+      edition: nftEdition,
+      listing,
+      collectionConfig,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+
+    await sleep(4000);
+
+    try {
+      await program.methods.purchase(price, maker.publicKey, new anchor.BN(1))
+      .accountsPartial({
+        buyer: taker.publicKey,
+        seller: maker.publicKey,
+        sellerMint: nftMint.publicKey,
+        marketplace,
+        buyerAta: takerAta,
+        vault,
+        listing,
+        collectionConfig,
+        treasury,
+        paymentMint: null,
+        buyerPaymentAta: null,
+        sellerPaymentAta: null,
+        treasuryPaymentAta: null,
+        paymentTokenProgram: null,
+        rewardTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(creatorAccounts)
+      .signers([taker])
+      .rpc();
+      assert.fail("purchase of an expired listing should fail");
+    } catch (err) {
+      assert.include(err.toString(), "ListingExpired");
+    }
+
+    // Anyone can clean it up, here the taker
+    const tx = await program.methods.crankExpired()
+    .accountsPartial({
+      cranker: taker.publicKey,
+      seller: maker.publicKey,
+      listing,
+      mint: nftMint.publicKey,
+      sellerAta: makerAta,
+      marketplace,
+      vault,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([taker])
+    .rpc();
+    console.log("\nExpired Listing Cranked!");
+    console.log("Your transaction signature", tx);
+
+    const event = await eventOf(tx, "Delisted");
+    assert.ok(event.mint.equals(new anchor.web3.PublicKey(nftMint.publicKey)));
+    assert.ok(event.seller.equals(maker.publicKey));
+
+    assert.isNull(await connection.getAccountInfo(listing));
+    assert.isNull(await connection.getAccountInfo(vault));
+    const makerBalance = await connection.getTokenAccountBalance(makerAta);
+    assert.equal(makerBalance.value.amount, "1");
+  });
+
+  it("Delegated listing stays in the seller's wallet and dies once revoked!", async () => {
+    const nftMetadata = new anchor.web3.PublicKey(findMetadataPda(umi, {mint: nftMint.publicKey})[0]);
+    const listDelegated = () => program.methods.listDelegated(price, null, null, false)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      sellerMint: nftMint.publicKey,
+      collectionMint: collectionMint.publicKey,
+      sellerAta: makerAta,
+      metadata: nftMetadata,
+      edition: new anchor.web3.PublicKey(findMasterEditionPda(umi, {mint: nftMint.publicKey})[0]),
+      listing,
+      collectionConfig,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+    const purchaseDelegated = () => program.methods.purchase(price, maker.publicKey, new anchor.BN(1))
+    .accountsPartial({
+      buyer: taker.publicKey,
+      seller: maker.publicKey,
+      sellerMint: nftMint.publicKey,
+      marketplace,
+      buyerAta: takerAta,
+      vault: null,
+      sellerAta: makerAta,
+      listing,
+      collectionConfig,
+      treasury,
+      paymentMint: null,
+      buyerPaymentAta: null,
+      sellerPaymentAta: null,
+      treasuryPaymentAta: null,
+      paymentTokenProgram: null,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(creatorAccounts)
+    .signers([taker])
+    .rpc();
+
+    // NFT stays with the maker, the listing PDA is only its delegate
+    await listDelegated();
+    let makerToken = await getAccount(connection, makerAta);
+    assert.equal(makerToken.amount.toString(), "1");
+    assert.ok(makerToken.delegate.equals(listing));
+
+    const closeDead = () => program.methods.closeDeadListing()
+    .accountsPartial({
+      closer: taker.publicKey,
+      seller: maker.publicKey,
+      listing,
+      mint: nftMint.publicKey,
+      sellerAta: makerAta,
+      marketplace,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([taker])
+    .rpc();
+
+    // A listing that still holds its delegation can't be closed by others
+    try {
+      await closeDead();
+      assert.fail("closing a live delegated listing should fail");
+    } catch (err) {
+      assert.include(err.toString(), "ListingStillDelegated");
+    }
+
+    // Revoking the delegation makes the listing unbuyable
+    await revoke(connection, maker, makerAta, maker);
+    try {
+      await purchaseDelegated();
+      assert.fail("purchase of a revoked listing should fail");
+    } catch (err) {
+      assert.include(err.toString(), "DelegationRevoked");
+    }
+
+    // Seller can still take the dead listing down and list again
+    await program.methods.delisting()
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      mint: nftMint.publicKey,
+      sellerAta: makerAta,
+      listing,
+      vault: null,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+
+    // Once revoked, anyone can close the dead listing and the rent goes back to the maker
+    await listDelegated();
+    await revoke(connection, maker, makerAta, maker);
+    const makerLamports = await connection.getBalance(maker.publicKey);
+    const closeTx = await closeDead();
+    const closed = await eventOf(closeTx, "Delisted");
+    assert.ok(closed.seller.equals(maker.publicKey));
+    assert.isNull(await connection.getAccountInfo(listing));
+    assert.isAbove(await connection.getBalance(maker.publicKey), makerLamports);
+    await listDelegated();
+
+    const tx = await purchaseDelegated();
+    console.log("\nDelegated Listing Purchased!");
+    console.log("Your transaction signature", tx);
+
+    assert.isNull(await connection.getAccountInfo(listing));
+    const takerBalance = await connection.getTokenAccountBalance(takerAta);
+    assert.equal(takerBalance.value.amount, "1");
+    makerToken = await getAccount(connection, makerAta);
+    assert.equal(makerToken.amount.toString(), "0");
+  });
+
+  it("Programmable NFT is listed and bought through Token Metadata!", async () => {
+    // pNFT in the approved collection, minted to the maker
+    const pnftMint = generateSigner(umi);
+    await createNft(umi, {
+      mint: pnftMint,
+      name: "GM",
+      symbol: "GM",
+      uri: "https://arweave.net/123",
+      sellerFeeBasisPoints: percentAmount(5.5),
+      collection: { verified: false, key: collectionMint.publicKey },
+      tokenOwner: publicKey(maker.publicKey),
+      tokenStandard: TokenStandard.ProgrammableNonFungible,
+    }).sendAndConfirm(umi);
+    await verifyCollectionV1(umi, {
+      metadata: findMetadataPda(umi, { mint: pnftMint.publicKey }),
+      collectionMint: collectionMint.publicKey,
+      authority: creator,
+    }).sendAndConfirm(umi);
+
+    const pnftKey = new anchor.web3.PublicKey(pnftMint.publicKey);
+    const pnftListing = anchor.web3.PublicKey.findProgramAddressSync([marketplace.toBuffer(), pnftKey.toBuffer()], program.programId)[0];
+    const pnftMakerAta = anchor.utils.token.associatedAddress({ mint: pnftKey, owner: maker.publicKey });
+    const pnftTakerAta = anchor.utils.token.associatedAddress({ mint: pnftKey, owner: taker.publicKey });
+    const pnftVault = anchor.utils.token.associatedAddress({ mint: pnftKey, owner: pnftListing });
+    const tokenRecord = (token: anchor.web3.PublicKey) => new anchor.web3.PublicKey(findTokenRecordPda(umi, { mint: pnftMint.publicKey, token: publicKey(token) })[0]);
+    const pnftMetadata = new anchor.web3.PublicKey(findMetadataPda(umi, { mint: pnftMint.publicKey })[0]);
+    const pnftEdition = new anchor.web3.PublicKey(findMasterEditionPda(umi, { mint: pnftMint.publicKey })[0]);
+
+    await program.methods.listing(price, null, null)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      sellerMint: pnftKey,
+      collectionMint: collectionMint.publicKey,
+      collectionConfig,
+      sellerAta: pnftMakerAta,
+      metadata: pnftMetadata,
+      vault: pnftVault,
+      edition: pnftEdition,
+      tokenRecord: tokenRecord(pnftMakerAta),
+      destinationTokenRecord: tokenRecord(pnftVault),
+      authorizationRules: null,
+      authorizationRulesProgram: null,
+      sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      listing: pnftListing,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .signers([maker])
+    .rpc();
+    assert.equal((await connection.getTokenAccountBalance(pnftVault)).value.amount, "1");
+
+    const tx = await program.methods.purchase(price, maker.publicKey, new anchor.BN(1))
+    .accountsPartial({
+      buyer: taker.publicKey,
+      seller: maker.publicKey,
+      sellerMint: pnftKey,
+      marketplace,
+      buyerAta: pnftTakerAta,
+      vault: pnftVault,
+      listing: pnftListing,
+      collectionConfig,
+      treasury,
+      metadata: pnftMetadata,
+      edition: pnftEdition,
+      tokenRecord: tokenRecord(pnftVault),
+      destinationTokenRecord: tokenRecord(pnftTakerAta),
+      authorizationRules: null,
+      authorizationRulesProgram: null,
+      sysvarInstructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+      paymentMint: null,
+      buyerPaymentAta: null,
+      sellerPaymentAta: null,
+      treasuryPaymentAta: null,
+      paymentTokenProgram: null,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    })
+    .remainingAccounts(creatorAccounts)
+    .signers([taker])
+    .rpc();
+    console.log("\nProgrammable NFT Purchased!");
+    console.log("Your transaction signature", tx);
+
+    assert.isNull(await connection.getAccountInfo(pnftListing));
+    assert.equal((await connection.getTokenAccountBalance(pnftTakerAta)).value.amount, "1");
+  });
+
+  it("Core asset is listed, bought and delisted only by its owner!", async () => {
+    // Core collection paying 5% royalties to the provider wallet, approved on the marketplace
+    const coreCollection = generateSigner(umi);
+    await createCoreCollection(umi, {
+      collection: coreCollection,
+      name: "GM Core",
+      uri: "https://arweave.net/123",
+      plugins: [{
+        type: "Royalties",
+        basisPoints: 500,
+        creators: [{ address: creator.publicKey, percentage: 100 }],
+        ruleSet: ruleSet("None"),
+      }],
+    }).sendAndConfirm(umi);
+    const collectionKey = new anchor.web3.PublicKey(coreCollection.publicKey);
+    const coreConfig = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("collection"), marketplace.toBuffer(), collectionKey.toBuffer()], program.programId)[0];
+    await program.methods.addCollection(collectionKey, null, null, null)
+    .accountsPartial({
+      admin: provider.wallet.publicKey,
+      marketplace,
+      collectionConfig: coreConfig,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+
+    // Asset in the collection, owned by the maker
+    const asset = generateSigner(umi);
+    await createCoreAsset(umi, {
+      asset,
+      collection: await fetchCollection(umi, coreCollection.publicKey),
+      name: "GM Core #1",
+      uri: "https://arweave.net/123",
+      owner: publicKey(maker.publicKey),
+    }).sendAndConfirm(umi);
+    const assetKey = new anchor.web3.PublicKey(asset.publicKey);
+    const coreListing = anchor.web3.PublicKey.findProgramAddressSync([marketplace.toBuffer(), assetKey.toBuffer()], program.programId)[0];
+    const coreProgram = new anchor.web3.PublicKey(MPL_CORE_PROGRAM_ID);
+
+    const listCore = (seller: Keypair) => program.methods.listCore(price, null)
+    .accountsPartial({
+      seller: seller.publicKey,
+      listing: coreListing,
+      asset: assetKey,
+      collection: collectionKey,
+      collectionConfig: coreConfig,
+      marketplace,
+      coreProgram,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([seller])
+    .rpc();
+
+    // Only the asset's owner can list it
+    try {
+      await listCore(taker);
+      assert.fail("listing someone else's Core asset should fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidCoreAsset");
+    }
+
+    let tx = await listCore(maker);
+    console.log("\nCore Asset Listed!");
+    console.log("Your transaction signature", tx);
+    assert.equal((await fetchAsset(umi, asset.publicKey)).owner.toString(), coreListing.toBase58());
+
+    // Core listings can be repriced and given an expiry, but only in SOL
+    const updateCore = (paymentMint: anchor.web3.PublicKey | null, expiresAt: anchor.BN | null) => program.methods.updateListing(price, paymentMint, expiresAt)
+    .accountsPartial({ seller: maker.publicKey, listing: coreListing, mint: assetKey, marketplace, collectionConfig: coreConfig })
+    .signers([maker])
+    .rpc();
+    try {
+      await updateCore(anchor.web3.Keypair.generate().publicKey, null);
+      assert.fail("pricing a Core listing in a token should fail");
+    } catch (err) {
+      assert.include(err.toString(), "CoreListingSolOnly");
+    }
+    const coreExpiry = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+    await updateCore(null, coreExpiry);
+    assert.ok((await program.account.listing.fetch(coreListing)).expiresAt.eq(coreExpiry));
+
+    // Royalties come from the collection's plugin, so the provider wallet is the creator
+    tx = await program.methods.purchaseCore(price, maker.publicKey)
+    .accountsPartial({
+      buyer: taker.publicKey,
+      seller: maker.publicKey,
+      marketplace,
+      listing: coreListing,
+      collectionConfig: coreConfig,
+      asset: assetKey,
+      collection: collectionKey,
+      treasury,
+      coreProgram,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .remainingAccounts(creatorAccounts)
+    .signers([taker])
+    .rpc();
+    console.log("\nCore Asset Purchased!");
+    console.log("Your transaction signature", tx);
+
+    const event = await eventOf(tx, "Sold");
+    assert.ok(event.royalty.eq(price.muln(500).divn(10000)));
+    assert.ok(event.marketplaceFee.add(event.royalty).add(event.sellerProceeds).eq(price));
+    assert.isNull(await connection.getAccountInfo(coreListing));
+    assert.equal((await fetchAsset(umi, asset.publicKey)).owner.toString(), taker.publicKey.toBase58());
+
+    // New owner lists it and takes it back down
+    await listCore(taker);
+    tx = await program.methods.delistCore()
+    .accountsPartial({
+      seller: taker.publicKey,
+      listing: coreListing,
+      asset: assetKey,
+      collection: collectionKey,
+      marketplace,
+      coreProgram,
+    })
+    .signers([taker])
+    .rpc();
+    console.log("\nCore Asset Delisted!");
+    console.log("Your transaction signature", tx);
+
+    assert.isNull(await connection.getAccountInfo(coreListing));
+    assert.equal((await fetchAsset(umi, asset.publicKey)).owner.toString(), taker.publicKey.toBase58());
+
+    // An expired Core listing can be cleaned up by anyone, here the maker
+    const expiresAt = new anchor.BN(Math.floor(Date.now() / 1000) + 2);
+    await program.methods.listCore(price, expiresAt)
+    .accountsPartial({
+      seller: taker.publicKey,
+      listing: coreListing,
+      asset: assetKey,
+      collection: collectionKey,
+      collectionConfig: coreConfig,
+      marketplace,
+      coreProgram,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([taker])
+    .rpc();
+    await sleep(4000);
+
+    tx = await program.methods.crankExpiredCore()
+    .accountsPartial({
+      cranker: maker.publicKey,
+      seller: taker.publicKey,
+      listing: coreListing,
+      asset: assetKey,
+      collection: collectionKey,
+      marketplace,
+      coreProgram,
+    })
+    .signers([maker])
+    .rpc();
+    console.log("\nExpired Core Listing Cranked!");
+    console.log("Your transaction signature", tx);
+
+    assert.isNull(await connection.getAccountInfo(coreListing));
+    assert.equal((await fetchAsset(umi, asset.publicKey)).owner.toString(), taker.publicKey.toBase58());
+  });
+
+  it("Core assets with a permanent delegate, on the asset or its collection, can't be listed!", async () => {
+    // Plain collection holding an asset with a permanent freeze delegate, and a
+    // collection whose permanent transfer delegate covers every asset in it
+    const plainCollection = generateSigner(umi);
+    const delegatedCollection = generateSigner(umi);
+    await createCoreCollection(umi, { collection: plainCollection, name: "GM Core", uri: "https://arweave.net/123" }).sendAndConfirm(umi);
+    await createCoreCollection(umi, {
+      collection: delegatedCollection,
+      name: "GM Core",
+      uri: "https://arweave.net/123",
+      plugins: [{ type: "PermanentTransferDelegate" }],
+    }).sendAndConfirm(umi);
+
+    const cases: [string, KeypairSigner, any[], string][] = [
+      ["an asset with a permanent freeze delegate", plainCollection, [{ type: "PermanentFreezeDelegate", frozen: false }], "PermanentDelegateAsset"],
+      ["an asset in a collection with a permanent transfer delegate", delegatedCollection, [], "PermanentDelegateAsset"],
+    ];
+    for (const [label, collection, plugins, error] of cases) {
+      const collectionKey = new anchor.web3.PublicKey(collection.publicKey);
+      const config = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("collection"), marketplace.toBuffer(), collectionKey.toBuffer()], program.programId)[0];
+      if (!(await connection.getAccountInfo(config))) {
+        await program.methods.addCollection(collectionKey, null, null, null)
+        .accountsPartial({
+          admin: provider.wallet.publicKey,
+          marketplace,
+          collectionConfig: config,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .rpc();
+      }
+
+      const asset = generateSigner(umi);
+      await createCoreAsset(umi, {
+        asset,
+        collection: await fetchCollection(umi, collection.publicKey),
+        name: "GM Core #2",
+        uri: "https://arweave.net/123",
+        owner: publicKey(maker.publicKey),
+        plugins,
+      }).sendAndConfirm(umi);
+      const assetKey = new anchor.web3.PublicKey(asset.publicKey);
+
+      try {
+        await program.methods.listCore(price, null)
+        .accountsPartial({
+          seller: maker.publicKey,
+          listing: listingOf(assetKey),
+          asset: assetKey,
+          collection: collectionKey,
+          collectionConfig: config,
+          marketplace,
+          coreProgram: new anchor.web3.PublicKey(MPL_CORE_PROGRAM_ID),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+        assert.fail(`listing ${label} should fail`);
+      } catch (err) {
+        assert.include(err.toString(), error, label);
+      }
+      assert.equal((await fetchAsset(umi, asset.publicKey)).owner.toString(), maker.publicKey.toBase58(), label);
+    }
+  });
+
+  it("Compressed NFT is listed, bought and delisted only with a valid proof from its owner!", async () => {
+    // Small tree without a canopy, so every proof node is passed
+    const maxDepth = 3;
+    const merkleTree = generateSigner(umi);
+    await (await createTree(umi, { merkleTree, maxDepth, maxBufferSize: 8 })).sendAndConfirm(umi);
+
+    // Leaf in the approved collection, owned by the maker, with the provider wallet as verified creator
+    const metadata = {
+      name: "GM",
+      symbol: "GM",
+      uri: "https://arweave.net/123",
+      sellerFeeBasisPoints: 500,
+      primarySaleHappened: false,
+      isMutable: true,
+      editionNonce: null,
+      tokenStandard: CompressedTokenStandard.NonFungible,
+      collection: { key: collectionMint.publicKey, verified: false },
+      uses: null,
+      tokenProgramVersion: TokenProgramVersion.Original,
+      creators: [{ address: creator.publicKey, verified: true, share: 100 }],
+    };
+    await mintToCollectionV1(umi, {
+      leafOwner: publicKey(maker.publicKey),
+      merkleTree: merkleTree.publicKey,
+      collectionMint: collectionMint.publicKey,
+      metadata,
+    }).sendAndConfirm(umi);
+
+    // Bubblegum verifies the collection while minting, and the leaf stores it that way
+    const minted = { ...metadata, collection: { key: collectionMint.publicKey, verified: true } };
+    const metadataArgs = Buffer.from(getMetadataArgsSerializer().serialize(minted));
+    const nonce = new anchor.BN(0);
+    const index = 0;
+
+    // First and only leaf in the tree, so the proof and root are computed locally
+    const proofFor = (owner: anchor.web3.PublicKey) => {
+      const leaf = publicKey(hashLeaf(umi, { merkleTree: merkleTree.publicKey, owner: publicKey(owner), leafIndex: index, metadata: minted }));
+      return {
+        root: Array.from(publicKeyBytes(getMerkleRoot([leaf], maxDepth))),
+        nodes: getMerkleProof([leaf], maxDepth, leaf).map((node) => ({ pubkey: new anchor.web3.PublicKey(node), isSigner: false, isWritable: false })),
+      };
+    };
+
+    const treeKey = new anchor.web3.PublicKey(merkleTree.publicKey);
+    const compressedListing = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("compressed"), marketplace.toBuffer(), treeKey.toBuffer(), nonce.toArrayLike(Buffer, "le", 8)], program.programId)[0];
+    const bubblegumAccounts = {
+      merkleTree: treeKey,
+      treeAuthority: new anchor.web3.PublicKey(findTreeConfigPda(umi, { merkleTree: merkleTree.publicKey })[0]),
+      bubblegumProgram: new anchor.web3.PublicKey(MPL_BUBBLEGUM_PROGRAM_ID),
+      logWrapper: new anchor.web3.PublicKey(SPL_NOOP_PROGRAM_ID),
+      compressionProgram: new anchor.web3.PublicKey(SPL_ACCOUNT_COMPRESSION_PROGRAM_ID),
+      systemProgram: anchor.web3.SystemProgram.programId,
+    };
+
+    const listCompressed = (seller: Keypair, root: number[], nodes: anchor.web3.AccountMeta[]) => program.methods.listCompressed(nonce, index, root, metadataArgs, price, null)
+    .accountsPartial({
+      seller: seller.publicKey,
+      leafDelegate: seller.publicKey,
+      marketplace,
+      compressedListing,
+      collectionMint: collectionMint.publicKey,
+      collectionConfig,
+      ...bubblegumAccounts,
+    })
+    .remainingAccounts(nodes)
+    .signers([seller])
+    .rpc();
+
+    // Only the leaf's owner can list it: the taker's proof doesn't match the tree
+    const takerProof = proofFor(taker.publicKey);
+    const rejected = (attempt: Promise<string>) => attempt.then(() => false, () => true);
+    assert.isTrue(await rejected(listCompressed(taker, takerProof.root, takerProof.nodes)), "listing someone else's compressed NFT should fail");
+
+    // A wrong root is rejected by the compression program
+    const makerProof = proofFor(maker.publicKey);
+    assert.isTrue(await rejected(listCompressed(maker, Array(32).fill(1), makerProof.nodes)), "listing with a wrong root should fail");
+    assert.isNull(await connection.getAccountInfo(compressedListing));
+
+    let tx = await listCompressed(maker, makerProof.root, makerProof.nodes);
+    console.log("\nCompressed NFT Listed!");
+    console.log("Your transaction signature", tx);
+
+    const listed = await program.account.compressedListing.fetch(compressedListing);
+    assert.ok(listed.maker.equals(maker.publicKey));
+    assert.equal(listed.index, index);
+
+    // Remaining accounts are the verified creators, then the proof nodes
+    const listingProof = proofFor(compressedListing);
+    const purchaseCompressed = (args: Buffer) => program.methods.purchaseCompressed(listingProof.root, args, price, maker.publicKey)
+    .accountsPartial({
+      buyer: taker.publicKey,
+      seller: maker.publicKey,
+      marketplace,
+      compressedListing,
+      collectionConfig,
+      treasury,
+      ...bubblegumAccounts,
+    })
+    .remainingAccounts([...creatorAccounts, ...listingProof.nodes])
+    .signers([taker])
+    .rpc();
+
+    // Metadata that dodges royalties doesn't match the listed leaf
+    try {
+      await purchaseCompressed(Buffer.from(getMetadataArgsSerializer().serialize({ ...minted, sellerFeeBasisPoints: 0 })));
+      assert.fail("purchase with altered metadata should fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidCompressedNft");
+    }
+
+    tx = await purchaseCompressed(metadataArgs);
+    console.log("\nCompressed NFT Purchased!");
+    console.log("Your transaction signature", tx);
+
+    const event = await eventOf(tx, "Sold");
+    assert.ok(event.royalty.eq(price.muln(500).divn(10000)));
+    assert.ok(event.marketplaceFee.add(event.royalty).add(event.sellerProceeds).eq(price));
+    assert.isNull(await connection.getAccountInfo(compressedListing));
+
+    // The taker now owns the leaf, so their proof lists it, and delisting hands it back
+    await listCompressed(taker, takerProof.root, takerProof.nodes);
+    const relistedProof = proofFor(compressedListing);
+    tx = await program.methods.delistCompressed(relistedProof.root)
+    .accountsPartial({
+      seller: taker.publicKey,
+      marketplace,
+      compressedListing,
+      ...bubblegumAccounts,
+    })
+    .remainingAccounts(relistedProof.nodes)
+    .signers([taker])
+    .rpc();
+    console.log("\nCompressed NFT Delisted!");
+    console.log("Your transaction signature", tx);
+
+    assert.isNull(await connection.getAccountInfo(compressedListing));
+  });
+
+  it("Token-2022 mints that are non-transferable or have a permanent delegate can't be listed!", async () => {
+    // Approve a Token-2022 group as a collection
+    tokenGroup = await createTokenGroup();
+    tokenGroupConfig = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("collection"), marketplace.toBuffer(), tokenGroup.toBuffer()], program.programId)[0];
+    await program.methods.addCollection(tokenGroup, null, null, null)
+    .accountsPartial({
+      admin: provider.wallet.publicKey,
+      marketplace,
+      collectionConfig: tokenGroupConfig,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+
+    const nonTransferable = await createToken2022Nft(tokenGroup, maker.publicKey, [ExtensionType.NonTransferable], (mint) => [
+      createInitializeNonTransferableMintInstruction(mint, TOKEN_2022_PROGRAM_ID),
+    ]);
+    const permanentDelegate = await createToken2022Nft(tokenGroup, maker.publicKey, [ExtensionType.PermanentDelegate], (mint) => [
+      createInitializePermanentDelegateInstruction(mint, provider.wallet.publicKey, TOKEN_2022_PROGRAM_ID),
+    ]);
+
+    const cases: [string, anchor.web3.PublicKey, string][] = [
+      ["a non-transferable mint", nonTransferable, "NonTransferableMint"],
+      ["a mint with a permanent delegate", permanentDelegate, "PermanentDelegateMint"],
+    ];
+    for (const [label, mint, error] of cases) {
+      try {
+        await listToken2022(mint);
+        assert.fail(`listing ${label} should fail`);
+      } catch (err) {
+        assert.include(err.toString(), error, label);
+      }
+    }
+  });
+
+  it("Token-2022 NFT from another group can't be listed under the collection!", async () => {
+    // Member of a group the marketplace never approved
+    const otherGroup = await createTokenGroup();
+    const stranger = await createToken2022Nft(otherGroup, maker.publicKey);
+
+    try {
+      await listToken2022(stranger);
+      assert.fail("listing a member of another group should fail");
+    } catch (err) {
+      assert.include(err.toString(), "InvalidCollection");
+    }
+  });
+
+  // Member of the approved group whose transfers run the counting hook, with the hook's
+  // extra account, the hook program and its validation account to pass on every transfer
+  const createHookNft = async () => {
+    const mint = await createToken2022Nft(tokenGroup, maker.publicKey, [ExtensionType.TransferHook], (mint) => [
+      createInitializeTransferHookInstruction(mint, provider.wallet.publicKey, hookProgram.programId, TOKEN_2022_PROGRAM_ID),
+    ]);
+    const extraAccountMetaList = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("extra-account-metas"), mint.toBuffer()], hookProgram.programId)[0];
+    const counter = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("counter"), mint.toBuffer()], hookProgram.programId)[0];
+    await hookProgram.methods.initializeExtraAccountMetaList()
+    .accountsPartial({
+      payer: provider.wallet.publicKey,
+      extraAccountMetaList,
+      mint,
+      counter,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+
+    const hookAccounts = [
+      { pubkey: counter, isSigner: false, isWritable: true },
+      { pubkey: hookProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: extraAccountMetaList, isSigner: false, isWritable: false },
+    ];
+    return { mint, counter, hookAccounts };
+  };
+
+  it("Token-2022 NFT with a transfer hook is listed and bought with its hook accounts!", async () => {
+    const { mint: hookMint, counter, hookAccounts } = await createHookNft();
+
+    await listToken2022(hookMint, hookAccounts);
+    assert.equal((await hookProgram.account.counter.fetch(counter)).transfers.toNumber(), 1);
+
+    const hookListing = anchor.web3.PublicKey.findProgramAddressSync([marketplace.toBuffer(), hookMint.toBuffer()], program.programId)[0];
+    const buyerAta = getAssociatedTokenAddressSync(hookMint, taker.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    const purchase = (remaining: anchor.web3.AccountMeta[]) => program.methods.purchase(price, maker.publicKey, new anchor.BN(1))
+    .accountsPartial({
+      buyer: taker.publicKey,
+      seller: maker.publicKey,
+      sellerMint: hookMint,
+      marketplace,
+      buyerAta,
+      vault: getAssociatedTokenAddressSync(hookMint, hookListing, true, TOKEN_2022_PROGRAM_ID),
+      listing: hookListing,
+      collectionConfig: tokenGroupConfig,
+      treasury,
+      metadata: null,
+      edition: null,
+      tokenRecord: null,
+      destinationTokenRecord: null,
+      authorizationRules: null,
+      authorizationRulesProgram: null,
+      sysvarInstructions: null,
+      paymentMint: null,
+      buyerPaymentAta: null,
+      sellerPaymentAta: null,
+      treasuryPaymentAta: null,
+      paymentTokenProgram: null,
+      rewardTokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .remainingAccounts(remaining)
+    .signers([taker])
+    .rpc();
+
+    // Token-2022 can't run the hook without its accounts
+    const rejected = await purchase([]).then(() => false, () => true);
+    assert.isTrue(rejected, "purchase without the hook accounts should fail");
+
+    // No metadata means no creators, so every remaining account goes to the hook
+    const tx = await purchase(hookAccounts);
+    console.log("\nTransfer Hook NFT Purchased!");
+    console.log("Your transaction signature", tx);
+
+    assert.isNull(await connection.getAccountInfo(hookListing));
+    assert.equal((await getAccount(connection, buyerAta, undefined, TOKEN_2022_PROGRAM_ID)).amount.toString(), "1");
+    assert.equal((await hookProgram.account.counter.fetch(counter)).transfers.toNumber(), 2);
+  });
+
+  it("Token-2022 bundle is listed and bought with its hook accounts after the NFTs!", async () => {
+    // Two members of the approved group, the first with the counting hook
+    const { mint: hookMint, counter, hookAccounts } = await createHookNft();
+    const plainMint = await createToken2022Nft(tokenGroup, maker.publicKey);
+    const keys = [hookMint, plainMint];
+
+    const bundleId = new anchor.BN(2);
+    const bundleListing = anchor.web3.PublicKey.findProgramAddressSync([Buffer.from("bundle"), marketplace.toBuffer(), maker.publicKey.toBuffer(), bundleId.toArrayLike(Buffer, "le", 8)], program.programId)[0];
+    const ataOf = (mint: anchor.web3.PublicKey, owner: anchor.web3.PublicKey) => getAssociatedTokenAddressSync(mint, owner, true, TOKEN_2022_PROGRAM_ID);
+    // No Metaplex metadata, so the edition, token records and rule set are left out
+    const none = { pubkey: program.programId, isSigner: false, isWritable: false };
+    const account = (pubkey: anchor.web3.PublicKey, isWritable = false) => ({ pubkey, isSigner: false, isWritable });
+
+    // mint, (empty) metadata PDA, edition, seller ATA, seller token record, vault, vault token record, rule set
+    let tx = await program.methods.listBundle(bundleId, price, keys.length)
+    .accountsPartial({
+      seller: maker.publicKey,
+      marketplace,
+      bundleListing,
+      collectionMint: tokenGroup,
+      collectionConfig: tokenGroupConfig,
+      authorizationRulesProgram: null,
+      sysvarInstructions: null,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .remainingAccounts([
+      ...keys.flatMap((mint) => [
+        account(mint),
+        account(metadataOf(mint), true),
+        none,
+        account(ataOf(mint, maker.publicKey), true),
+        none,
+        account(ataOf(mint, bundleListing), true),
+        none,
+        none,
+      ]),
+      ...hookAccounts,
+    ])
+    .signers([maker])
+    .rpc();
+    console.log("\nToken-2022 Bundle Listed!");
+    console.log("Your transaction signature", tx);
+    assert.equal((await hookProgram.account.counter.fetch(counter)).transfers.toNumber(), 1);
+
+    // mint, (empty) metadata PDA, edition, vault, vault token record, buyer ATA, buyer token record, rule set
+    tx = await program.methods.purchaseBundle(price, maker.publicKey)
+    .accountsPartial({
+      buyer: taker.publicKey,
+      seller: maker.publicKey,
+      marketplace,
+      bundleListing,
+      collectionConfig: tokenGroupConfig,
+      treasury,
+      metadata: metadataOf(keys[0]),
+      authorizationRulesProgram: null,
+      sysvarInstructions: null,
+      systemProgram: anchor.web3.SystemProgram.programId,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .remainingAccounts([
+      ...keys.flatMap((mint) => [
+        account(mint),
+        account(metadataOf(mint), true),
+        none,
+        account(ataOf(mint, bundleListing), true),
+        none,
+        account(ataOf(mint, taker.publicKey), true),
+        none,
+        none,
+      ]),
+      // No metadata means no creators, so the hook accounts come right after the NFTs
+      ...hookAccounts,
+    ])
+    .signers([taker])
+    .rpc();
+    console.log("\nToken-2022 Bundle Purchased!");
+    console.log("Your transaction signature", tx);
+
+    const sold = await eventOf(tx, "BundleSold");
+    assert.ok(sold.royalty.eqn(0));
+    assert.equal((await hookProgram.account.counter.fetch(counter)).transfers.toNumber(), 2);
+    for (const key of keys) {
+      assert.equal((await getAccount(connection, ataOf(key, taker.publicKey), undefined, TOKEN_2022_PROGRAM_ID)).amount.toString(), "1");
+    }
+  });
+
+  it("Admin handoff needs the proposed admin to accept and can be cancelled!", async () => {
+    const admin = provider.wallet.publicKey;
+    const propose = (newAdmin: anchor.web3.PublicKey, signer?: Keypair) => program.methods.updateMarketplace(null, newAdmin, null, null, null)
+    .accountsPartial({ admin: signer ? signer.publicKey : admin, marketplace })
+    .signers(signer ? [signer] : [])
+    .rpc();
+    const accept = (signer: Keypair) => program.methods.acceptAdmin()
+    .accountsPartial({ newAdmin: signer.publicKey, marketplace })
+    .signers([signer])
+    .rpc();
+
+    // Only the current admin can update the marketplace
+    try {
+      await propose(taker.publicKey, taker);
+      assert.fail("update by a non-admin should fail");
+    } catch (err) {
+      assert.include(err.toString(), "ConstraintHasOne");
+    }
+
+    // Someone other than the proposed admin can't accept
+    await propose(taker.publicKey);
+    try {
+      await accept(maker);
+      assert.fail("accept by the wrong signer should fail");
+    } catch (err) {
+      assert.include(err.toString(), "NotPendingAdmin");
+    }
+
+    // Cancelled proposals can no longer be accepted
+    const cancelTx = await program.methods.cancelAdminTransfer()
+    .accountsPartial({ admin, marketplace })
+    .rpc();
+    const cancelled = await eventOf(cancelTx, "AdminTransferCancelled");
+    assert.ok(cancelled.pendingAdmin.equals(taker.publicKey));
+    assert.isNull((await program.account.marketplace.fetch(marketplace)).pendingAdmin);
+    try {
+      await accept(taker);
+      assert.fail("accepting a cancelled proposal should fail");
+    } catch (err) {
+      assert.include(err.toString(), "NotPendingAdmin");
+    }
+
+    // Propose again and let the taker accept
+    await propose(taker.publicKey);
+    const tx = await accept(taker);
+    console.log("\nAdmin Transferred!");
+    console.log("Your transaction signature", tx);
+
+    const event = await eventOf(tx, "AdminTransferred");
+    assert.ok(event.oldAdmin.equals(admin));
+    assert.ok(event.newAdmin.equals(taker.publicKey));
+    let marketplaceAccount = await program.account.marketplace.fetch(marketplace);
+    assert.ok(marketplaceAccount.admin.equals(taker.publicKey));
+    assert.isNull(marketplaceAccount.pendingAdmin);
+
+    // Hand the marketplace back for the rest of the suite
+    await propose(admin, taker);
+    await program.methods.acceptAdmin()
+    .accountsPartial({ newAdmin: admin, marketplace })
+    .rpc();
+    marketplaceAccount = await program.account.marketplace.fetch(marketplace);
+    assert.ok(marketplaceAccount.admin.equals(admin));
+  });
+
 });
 
 function sleep(ms: number) {